@@ -0,0 +1,104 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    ffi::OsStr,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{Playspace, WriteError};
+
+#[cfg_attr(docsrs, doc(cfg(all(windows, feature = "windows-acl"))))]
+impl Playspace {
+    /// Deny the current user write access to a file or directory in the
+    /// Playspace, using a Windows ACL.
+    ///
+    /// Unlike [`set_readonly`][std::fs::Permissions::set_readonly], which only
+    /// sets the read-only attribute (and is ignored for directories), this
+    /// adds an explicit deny entry, just as `icacls <path> /deny <user>:(W)`
+    /// does. Every deny entry added this way is removed again when the
+    /// Playspace exits, before the temporary directory is removed.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error, including `icacls` failing, is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("locked.txt", "").unwrap();
+    ///     space.deny_write("locked.txt").unwrap();
+    ///     assert!(std::fs::write("locked.txt", "changed").is_err());
+    /// }).unwrap();
+    /// ```
+    pub fn deny_write(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        let user = current_user()?;
+        icacls(&path, [OsStr::new("/deny"), format!("{user}:(W)").as_ref()])
+            .map_err(|source| WriteError::io(&path, source))?;
+        self.acl_denied.lock().push((path, user));
+        Ok(())
+    }
+
+    /// Remove the deny entry added by [`deny_write`][Playspace::deny_write].
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error, including `icacls` failing, is bubbled-up.
+    pub fn allow_write(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        let user = current_user()?;
+        icacls(&path, [OsStr::new("/remove:d"), user.as_ref()])
+            .map_err(|source| WriteError::io(&path, source))?;
+        self.acl_denied
+            .lock()
+            .retain(|(denied, denied_user)| *denied != path || *denied_user != user);
+        Ok(())
+    }
+}
+
+/// Remove deny entries on exit, so that the temporary directory can be removed.
+/// Reports the first error, but still attempts the rest.
+pub(crate) fn restore(denied: Vec<(PathBuf, String)>) -> Result<(), io::Error> {
+    let mut result = Ok(());
+    for (path, user) in denied.into_iter().rev() {
+        let restored = icacls(&path, [OsStr::new("/remove:d"), user.as_ref()]);
+        if result.is_ok() {
+            result = restored;
+        }
+    }
+    result
+}
+
+fn current_user() -> Result<String, io::Error> {
+    let output = system_command("whoami.exe").output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("could not determine the current user"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+fn icacls<'a>(path: &Path, args: impl IntoIterator<Item = &'a OsStr>) -> Result<(), io::Error> {
+    let output = system_command("icacls.exe").arg(path).args(args).output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!(
+            "icacls failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// The environment inside a Playspace may well have no `PATH`, so run system
+/// tools by their full path
+fn system_command(program: &str) -> Command {
+    let root = std::env::var_os("SystemRoot").unwrap_or_else(|| "C:\\Windows".into());
+    Command::new(Path::new(&root).join("System32").join(program))
+}
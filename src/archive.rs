@@ -0,0 +1,212 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek},
+    path::Path,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{walk::walk, Playspace, SpaceError, WriteError};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "archive")))]
+impl Playspace {
+    /// Convenience combination of [`new`][Playspace::new] followed by
+    /// [`extract`][Playspace::extract]ing `archive` into the Playspace root.
+    ///
+    /// `archive` is relative to the working directory _before_ entering the
+    /// Playspace, so fixtures can be referred to by their usual path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Space`] for any error entering the Playspace,
+    /// or any error from [`extract`][Playspace::extract]. If extraction
+    /// fails, the Playspace is exited.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # let fixtures = tempfile::tempdir().unwrap();
+    /// # let archive = fixtures.path().join("project.tar.gz");
+    /// # Playspace::scoped(|space| {
+    /// #     space.write_files([("project/Cargo.toml", "")]).unwrap();
+    /// #     space.export_tar_gz(&archive).unwrap();
+    /// # }).unwrap();
+    /// let space = Playspace::from_archive(archive).unwrap();
+    /// assert!(std::path::Path::new("project/Cargo.toml").exists());
+    /// ```
+    pub fn from_archive(archive: impl AsRef<Path>) -> Result<Self, ArchiveError> {
+        let archive = std::path::absolute(archive)?;
+        let out = Self::new()?;
+        out.extract(archive, "")?;
+        Ok(out)
+    }
+
+    /// Unpack `archive` into the directory `dest` in the Playspace, which is
+    /// created if needed.
+    ///
+    /// Gzipped tar, plain tar, and zip archives are supported, and told apart
+    /// by their contents rather than their names. Shipping one compressed
+    /// fixture is far easier on a repository than thousands of loose files.
+    /// `archive` is relative to the current working directory as usual,
+    /// while `dest` must be in the Playspace. Entries which would be unpacked
+    /// outside of `dest` are never written.
+    ///
+    /// # Errors
+    ///
+    /// If `dest` is not in the Playspace, [`ArchiveError::Write`] is
+    /// returned. [`ArchiveError::Zip`] is returned for any error reading a
+    /// zip archive. Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # let fixtures = tempfile::tempdir().unwrap();
+    /// # let archive = fixtures.path().join("corpus.zip");
+    /// # Playspace::scoped(|space| {
+    /// #     space.write_files([("a.txt", "a")]).unwrap();
+    /// #     space.export_zip(&archive).unwrap();
+    /// # }).unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.extract(&archive, "corpus").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("corpus/a.txt").unwrap(), "a");
+    /// }).unwrap();
+    /// ```
+    pub fn extract(
+        &self,
+        archive: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+    ) -> Result<(), ArchiveError> {
+        let dest = self.playspace_path(dest)?;
+        std::fs::create_dir_all(&dest)?;
+
+        let mut file = File::open(archive)?;
+        let mut magic = [0; 4];
+        let read = file.read(&mut magic)?;
+        file.rewind()?;
+        match &magic[..read] {
+            [b'P', b'K', ..] => ZipArchive::new(file)?.extract(&dest)?,
+            [0x1f, 0x8b, ..] => tar::Archive::new(GzDecoder::new(file)).unpack(&dest)?,
+            _ => tar::Archive::new(file).unpack(&dest)?,
+        }
+        Ok(())
+    }
+
+    /// Write everything in the Playspace to a gzipped tar archive at `path`.
+    ///
+    /// Paths in the archive are relative to the Playspace root, and symlinks
+    /// are stored rather than followed. `path` is relative to the current
+    /// working directory as usual, so will normally be outside of the
+    /// Playspace. If it isn't, the archive does not contain itself.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let artifacts = tempfile::tempdir().unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("out/report.xml", "<testsuites/>")]).unwrap();
+    ///     space.export_tar_gz(artifacts.path().join("space.tar.gz")).unwrap();
+    /// }).unwrap();
+    /// assert!(artifacts.path().join("space.tar.gz").exists());
+    /// ```
+    pub fn export_tar_gz(&self, path: impl AsRef<Path>) -> Result<(), ArchiveError> {
+        let root = self.directory();
+        let entries = walk(root)?;
+
+        let file = File::create(path)?;
+        let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+        builder.follow_symlinks(false);
+        for entry in &entries {
+            builder.append_path_with_name(root.join(entry), entry)?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    /// Write everything in the Playspace to a zip archive at `path`.
+    ///
+    /// Just like [`export_tar_gz`][Playspace::export_tar_gz], but in a format
+    /// which is easier to open on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ArchiveError::Zip`] for any error writing the archive. Any
+    /// stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let artifacts = tempfile::tempdir().unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("out/report.xml", "<testsuites/>")]).unwrap();
+    ///     space.export_zip(artifacts.path().join("space.zip")).unwrap();
+    /// }).unwrap();
+    /// assert!(artifacts.path().join("space.zip").exists());
+    /// ```
+    pub fn export_zip(&self, path: impl AsRef<Path>) -> Result<(), ArchiveError> {
+        let root = self.directory();
+        let entries = walk(root)?;
+
+        let mut zip = ZipWriter::new(File::create(path)?);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for entry in &entries {
+            let name = archive_name(entry);
+            let source = root.join(entry);
+            let metadata = std::fs::symlink_metadata(&source)?;
+            #[cfg(unix)]
+            let options = {
+                use std::os::unix::fs::PermissionsExt;
+                options.unix_permissions(metadata.permissions().mode())
+            };
+            if metadata.is_symlink() {
+                let target = archive_name(&std::fs::read_link(&source)?);
+                zip.add_symlink(name, target, options)?;
+            } else if metadata.is_dir() {
+                zip.add_directory(name, options)?;
+            } else {
+                zip.start_file(name, options)?;
+                io::copy(&mut File::open(&source)?, &mut zip)?;
+            }
+        }
+        zip.finish()?;
+        Ok(())
+    }
+}
+
+/// `path` with `/` separators, as stored in archives
+fn archive_name(path: &Path) -> String {
+    let parts: Vec<_> = path
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    parts.join("/")
+}
+
+/// Error reading or writing an archive of a Playspace
+#[cfg_attr(docsrs, doc(cfg(feature = "archive")))]
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    /// Error entering the Playspace.
+    #[error(transparent)]
+    Space(#[from] SpaceError),
+    /// The destination was not in the Playspace.
+    #[error(transparent)]
+    Write(#[from] WriteError),
+    /// Error from the zip archive.
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+    /// A bubbled-up error from [`std::io`] functions.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
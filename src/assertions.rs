@@ -0,0 +1,194 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{fmt::Write as _, path::Path};
+
+use crate::Playspace;
+
+impl Playspace {
+    /// Assert that something exists at `path` in the Playspace.
+    ///
+    /// # Panics
+    ///
+    /// If nothing exists at `path`, or it is not in the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("out.txt", "").unwrap();
+    ///     space.assert_exists("out.txt");
+    /// }).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn assert_exists(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        assert!(
+            self.exists_for_assert(path),
+            "expected {} to exist in the Playspace",
+            path.display()
+        );
+    }
+
+    /// Assert that nothing exists at `path` in the Playspace.
+    ///
+    /// # Panics
+    ///
+    /// If something exists at `path`, or it is not in the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.assert_not_exists("out.txt");
+    /// }).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn assert_not_exists(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        assert!(
+            !self.exists_for_assert(path),
+            "expected {} not to exist in the Playspace",
+            path.display()
+        );
+    }
+
+    /// Assert that the file at `path` in the Playspace contains exactly
+    /// `expected`, showing a line-by-line diff if it doesn't.
+    ///
+    /// # Panics
+    ///
+    /// If the contents differ, or the file can't be read or is not in the
+    /// Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("config.toml", "[server]\nport = 8080\n").unwrap();
+    ///     space.assert_file_contents("config.toml", "[server]\nport = 8080\n");
+    /// }).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn assert_file_contents(&self, path: impl AsRef<Path>, expected: impl AsRef<[u8]>) {
+        let path = path.as_ref();
+        let expected = expected.as_ref();
+        let actual = self.read_for_assert(path);
+        if actual == expected {
+            return;
+        }
+
+        match (std::str::from_utf8(expected), std::str::from_utf8(&actual)) {
+            (Ok(expected), Ok(actual)) => panic!(
+                "contents of {} differ (- expected, + actual):\n{}",
+                path.display(),
+                line_diff(expected, actual)
+            ),
+            _ => panic!(
+                "contents of {} differ: expected {} bytes, found {} bytes",
+                path.display(),
+                expected.len(),
+                actual.len()
+            ),
+        }
+    }
+
+    /// Assert that the contents of the file at `path` in the Playspace match
+    /// the regular expression `pattern`.
+    ///
+    /// The pattern can match anywhere in the file: anchor it with `^` and `$`
+    /// to match the whole file.
+    ///
+    /// # Panics
+    ///
+    /// If the contents don't match, the pattern is invalid, or the file can't
+    /// be read as UTF-8 or is not in the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("app.log", "started in 42ms\n").unwrap();
+    ///     space.assert_file_matches("app.log", r"started in \d+ms");
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "regex")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "regex")))]
+    #[track_caller]
+    pub fn assert_file_matches(&self, path: impl AsRef<Path>, pattern: &str) {
+        let path = path.as_ref();
+        let regex = match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(error) => panic!("invalid pattern {pattern:?}: {error}"),
+        };
+        let Ok(actual) = String::from_utf8(self.read_for_assert(path)) else {
+            panic!("contents of {} are not UTF-8", path.display());
+        };
+        assert!(
+            regex.is_match(&actual),
+            "contents of {} do not match {pattern:?}:\n{actual}",
+            path.display()
+        );
+    }
+
+    #[track_caller]
+    fn exists_for_assert(&self, path: &Path) -> bool {
+        match self.playspace_path(path) {
+            Ok(full) => std::fs::symlink_metadata(full).is_ok(),
+            Err(error) => panic!("could not check {}: {error}", path.display()),
+        }
+    }
+
+    #[track_caller]
+    fn read_for_assert(&self, path: &Path) -> Vec<u8> {
+        let read = self
+            .playspace_path(path)
+            .map_err(|error| error.to_string())
+            .and_then(|full| std::fs::read(full).map_err(|error| error.to_string()));
+        match read {
+            Ok(contents) => contents,
+            Err(error) => panic!("could not read {}: {error}", path.display()),
+        }
+    }
+}
+
+/// Every line of `expected` and `actual`, prefixed with `-` if it is only in
+/// `expected`, `+` if it is only in `actual`, or ` ` if it is in both
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // Longest common subsequence lengths of every pair of suffixes
+    let mut common = vec![vec![0_usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            let _ = writeln!(out, "  {}", expected[i]);
+            i += 1;
+            j += 1;
+        } else if i < expected.len() && (j == actual.len() || common[i + 1][j] >= common[i][j + 1])
+        {
+            let _ = writeln!(out, "- {}", expected[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(out, "+ {}", actual[j]);
+            j += 1;
+        }
+    }
+    out
+}
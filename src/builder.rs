@@ -0,0 +1,668 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use tempfile::TempDir;
+
+#[cfg(feature = "async")]
+use crate::mutex::lock_async;
+use crate::{
+    clock::{Clock, IdSource, ProcessSequence},
+    config,
+    env::EnvFilter,
+    mutex::{blocking_lock, try_lock, Hold},
+    quarantine::{self, Quarantine},
+    removal::Removal,
+    retention::{Retention, RetentionFormat},
+    DropErrorPolicy, EnvPreset, Playspace, SpaceError,
+};
+
+/// Configure a [`Playspace`] before entering it.
+///
+/// Created with [`Playspace::builder`]. The plain constructors (e.g.
+/// [`Playspace::new`]) are equivalent to building with the default options.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::Playspace;
+/// let space = Playspace::builder()
+///     .link_origin("__origin__")
+///     .build()
+///     .unwrap();
+/// assert!(std::path::Path::new("__origin__").exists());
+/// ```
+#[derive(Clone)]
+#[must_use]
+pub struct PlayspaceBuilder {
+    origin_link: Option<PathBuf>,
+    root: Option<PathBuf>,
+    ids: Option<Arc<dyn IdSource>>,
+    clock: Option<Arc<dyn Clock>>,
+    retention: Option<Retention>,
+    quarantine: Option<Quarantine>,
+    removal: Removal,
+    on_drop_error: DropErrorPolicy,
+    presets: Vec<EnvPreset>,
+    env_filter: EnvFilter,
+    chdir: bool,
+    fallback_dir: Option<PathBuf>,
+    #[cfg(all(target_os = "linux", feature = "harden-linux"))]
+    restrict_filesystem: bool,
+}
+
+impl std::fmt::Debug for PlayspaceBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut out = f.debug_struct("PlayspaceBuilder");
+        out.field("origin_link", &self.origin_link)
+            .field("root", &self.root)
+            .field("ids", &self.ids.as_ref().map(|_ids| ".."))
+            .field("clock", &self.clock.as_ref().map(|_clock| ".."))
+            .field("retention", &self.retention)
+            .field("quarantine", &self.quarantine)
+            .field("removal", &self.removal)
+            .field("on_drop_error", &self.on_drop_error)
+            .field("presets", &self.presets)
+            .field("env_filter", &self.env_filter)
+            .field("chdir", &self.chdir)
+            .field("fallback_dir", &self.fallback_dir);
+        #[cfg(all(target_os = "linux", feature = "harden-linux"))]
+        out.field("restrict_filesystem", &self.restrict_filesystem);
+        out.finish()
+    }
+}
+
+impl Default for PlayspaceBuilder {
+    fn default() -> Self {
+        let config = config::get();
+        Self {
+            origin_link: None,
+            root: None,
+            ids: None,
+            clock: None,
+            retention: config.keep_on_failure.then(Retention::default),
+            quarantine: None,
+            removal: config.removal,
+            on_drop_error: config.on_drop_error,
+            presets: Vec::new(),
+            env_filter: EnvFilter::default(),
+            chdir: true,
+            fallback_dir: None,
+            #[cfg(all(target_os = "linux", feature = "harden-linux"))]
+            restrict_filesystem: false,
+        }
+    }
+}
+
+impl PlayspaceBuilder {
+    /// A builder with the default options, as
+    /// [configured][crate::config::configure] for the process.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expose the original working directory inside the Playspace, as a
+    /// symlink with the given name relative to the Playspace root.
+    ///
+    /// This lets tests refer to project files by a stable relative path
+    /// (e.g. `__origin__/tests/data/input.txt`). The file helpers (e.g.
+    /// [`write_file`][Playspace::write_file]) treat everything behind the link
+    /// as outside of the Playspace, so they can't be used to accidentally
+    /// write to the original directory. Anything else writing through the
+    /// link is _not_ prevented.
+    pub fn link_origin(mut self, name: impl Into<PathBuf>) -> Self {
+        self.origin_link = Some(name.into());
+        self
+    }
+
+    /// Name Playspace directories sequentially, rather than randomly.
+    ///
+    /// Each Playspace created this way is given the next name in the
+    /// sequence `playspace-0001`, `playspace-0002`, ... in `root`, counting
+    /// from the start of the process. That keeps paths that unavoidably end
+    /// up in logs or snapshots the same between runs (so long as the
+    /// Playspaces are created in the same order), where random names would
+    /// differ every time.
+    ///
+    /// This trades away uniqueness for reproducibility: if a directory with
+    /// the same name already exists in `root` (e.g. left over from a previous
+    /// run) then it is _removed_, and processes running at the same time must
+    /// not share a `root`. `root` is created if it doesn't exist, and is not
+    /// removed afterwards.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let root = std::env::temp_dir().join("sequential-example");
+    /// let space = Playspace::builder()
+    ///     .sequential_names(&root)
+    ///     .build()
+    ///     .unwrap();
+    /// assert!(space.directory().starts_with(&root));
+    /// assert!(space.directory().file_name().unwrap().to_str().unwrap().starts_with("playspace-"));
+    /// ```
+    pub fn sequential_names(mut self, root: impl Into<PathBuf>) -> Self {
+        self.root = Some(root.into());
+        self.ids = Some(Arc::new(ProcessSequence));
+        self
+    }
+
+    /// Name Playspace directories `playspace-<id>`, using IDs from the given
+    /// source rather than random names.
+    ///
+    /// Directories are created in the system temporary directory, or the
+    /// `root` given to [`sequential_names`][PlayspaceBuilder::sequential_names]
+    /// (which this replaces the source of IDs for). As with
+    /// `sequential_names`, any existing directory with the same name is
+    /// removed, so the source must not repeat IDs used by Playspaces that
+    /// still exist.
+    ///
+    /// # Example
+    ///
+    /// See the [`clock`][crate::clock] module.
+    pub fn id_source(mut self, ids: impl IdSource + 'static) -> Self {
+        self.ids = Some(Arc::new(ids));
+        self
+    }
+
+    /// Read the time from the given clock, rather than the system clock. See
+    /// the [`clock`][crate::clock] module.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Keep the Playspace directory if the test fails, so that it can be
+    /// inspected afterwards.
+    ///
+    /// A test is considered to have failed if the Playspace is dropped while
+    /// the thread is panicking (e.g. a failed assertion), or if any
+    /// [exit checks][Playspace::verify_on_exit] fail. The environment and
+    /// working directory are still restored, but the directory is left in
+    /// place and its path printed to stderr.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::builder().keep_on_failure().build().unwrap();
+    /// let directory = space.directory().to_owned();
+    ///
+    /// // No failure, so the directory is removed as usual
+    /// space.exit().unwrap();
+    /// assert!(!directory.exists());
+    /// ```
+    pub fn keep_on_failure(mut self) -> Self {
+        self.retention.get_or_insert_with(Retention::default);
+        self
+    }
+
+    /// Keep the Playspace directory if the test fails, as for
+    /// [`keep_on_failure`][PlayspaceBuilder::keep_on_failure], and also
+    /// append an entry to a report file.
+    ///
+    /// Each entry records the test name (i.e. the thread name, which is the
+    /// test name under the standard test harness), the path of the retained
+    /// directory, and the reason it was retained. CI systems can then use
+    /// the report to link the directories to the failing tests. Relative
+    /// report paths are evaluated with respect to the original working
+    /// directory, and the file and its parents are created if needed. Errors
+    /// writing the report are printed to stderr.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, RetentionFormat};
+    /// let space = Playspace::builder()
+    ///     .retention_report("target/retained.jsonl", RetentionFormat::Json)
+    ///     .build()
+    ///     .unwrap();
+    /// # space.exit().unwrap();
+    /// ```
+    pub fn retention_report(mut self, path: impl Into<PathBuf>, format: RetentionFormat) -> Self {
+        self.retention.get_or_insert_with(Retention::default).report = Some((path.into(), format));
+        self
+    }
+
+    /// Move the Playspace directory into a quarantine directory on exit,
+    /// rather than removing it.
+    ///
+    /// On filesystems where removing lots of files is slow or flaky, this
+    /// makes exiting fast and reliable: the directory is renamed into
+    /// `quarantine` (which is atomic), and then removed by a background
+    /// thread. Anything that thread doesn't get to before the process ends is
+    /// removed the next time a Playspace is built with the same quarantine.
+    ///
+    /// The quarantine must be on the same filesystem as the Playspace
+    /// directories (e.g. in [`std::env::temp_dir`], or the `root` given to
+    /// [`sequential_names`][PlayspaceBuilder::sequential_names]). If the
+    /// directory can't be moved there, it is removed as usual instead.
+    /// Relative paths are evaluated with respect to the original working
+    /// directory, and the quarantine is created if needed. Everything in it
+    /// is removed, so it must not be used for anything else.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::builder()
+    ///     .quarantine(std::env::temp_dir().join("playspace-quarantine"))
+    ///     .build()
+    ///     .unwrap();
+    /// let directory = space.directory().to_owned();
+    ///
+    /// space.exit().unwrap();
+    /// assert!(!directory.exists());
+    /// ```
+    pub fn quarantine(mut self, quarantine: impl Into<PathBuf>) -> Self {
+        self.quarantine = Some(Quarantine {
+            dir: quarantine.into(),
+        });
+        self
+    }
+
+    /// Try removing the Playspace directory on exit up to `attempts` times,
+    /// waiting `delay` after the first failure and doubling it after each
+    /// subsequent one.
+    ///
+    /// On Windows, antivirus scanners and lingering file handles often make
+    /// the first attempt fail, so by default it is tried 6 times starting
+    /// from 20ms (about 600ms in total). Elsewhere it is tried only once by
+    /// default. If every attempt fails then
+    /// [`ExitError::TempDirRemoveFailed`][crate::ExitError::TempDirRemoveFailed]
+    /// is returned as usual. `attempts` of 0 is treated as 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::time::Duration;
+    /// let space = Playspace::builder()
+    ///     .removal_retries(10, Duration::from_millis(50))
+    ///     .build()
+    ///     .unwrap();
+    /// space.exit().unwrap();
+    /// ```
+    pub fn removal_retries(mut self, attempts: u32, delay: Duration) -> Self {
+        self.removal = Removal { attempts, delay };
+        self
+    }
+
+    /// Actually prevent writing outside of the Playspace, using Linux's
+    /// [Landlock](https://landlock.io).
+    ///
+    /// Once entered, the thread which entered the Playspace (and any threads
+    /// or processes it starts afterwards) may only create, change, or remove
+    /// files beneath the Playspace directory, and write to `/dev/null`.
+    /// Anything else fails with a permission error. Reading is unaffected.
+    ///
+    /// **The restriction can never be lifted**: it outlives the Playspace, and
+    /// applies for the rest of the thread's life. This is fine for tests,
+    /// since the test harness runs each test on its own thread, but otherwise
+    /// use [`Playspace::scoped_process`] or a dedicated thread. Anything
+    /// which writes outside of the Playspace on exit, such as
+    /// [`persist_to`][Playspace::persist_to] or a retention report, fails.
+    ///
+    /// # Errors
+    ///
+    /// When built, returns [`SpaceError::StdIo`] with
+    /// [`Unsupported`][std::io::ErrorKind::Unsupported] if the kernel doesn't
+    /// support Landlock, so a test never silently runs unrestricted.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use playspace::Playspace;
+    /// std::thread::spawn(|| {
+    ///     let space = Playspace::builder()
+    ///         .restrict_filesystem(true)
+    ///         .build()
+    ///         .unwrap();
+    ///     space.write_file("inside.txt", "").unwrap();
+    ///     assert!(std::fs::write("/tmp/outside.txt", "").is_err());
+    ///     space.exit().unwrap();
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// ```
+    #[cfg(all(target_os = "linux", feature = "harden-linux"))]
+    #[cfg_attr(docsrs, doc(cfg(all(target_os = "linux", feature = "harden-linux"))))]
+    pub fn restrict_filesystem(mut self, restrict: bool) -> Self {
+        self.restrict_filesystem = restrict;
+        self
+    }
+
+    /// Choose what happens to errors exiting the Playspace if it is dropped,
+    /// rather than explicitly [`exit`][Playspace::exit]ed.
+    ///
+    /// By default they are [ignored][DropErrorPolicy::Ignore], since there is
+    /// nowhere to return them. In CI, it can be better to be loud about
+    /// leaked temporary directories or failing to restore the working
+    /// directory.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{DropErrorPolicy, Playspace};
+    /// let space = Playspace::builder()
+    ///     .on_drop_error(DropErrorPolicy::Panic)
+    ///     .build()
+    ///     .unwrap();
+    /// drop(space); // Panics if exiting fails
+    /// ```
+    pub fn on_drop_error(mut self, policy: DropErrorPolicy) -> Self {
+        self.on_drop_error = policy;
+        self
+    }
+
+    /// Apply an environment preset on entering the Playspace, after any
+    /// others already added.
+    ///
+    /// See [`EnvPreset`] and [`Playspace::apply`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{EnvPreset, Playspace};
+    /// let space = Playspace::builder()
+    ///     .preset(EnvPreset::no_proxy())
+    ///     .preset(EnvPreset::posix_locale())
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(std::env::var("LANG").unwrap(), "C");
+    /// ```
+    pub fn preset(mut self, preset: EnvPreset) -> Self {
+        self.presets.push(preset);
+        self
+    }
+
+    /// Only save and restore environment variables whose names start with
+    /// one of the given prefixes, in addition to any given before.
+    ///
+    /// By default the whole environment is restored on exit, which undoes any
+    /// changes made meanwhile by other threads to unrelated variables. With
+    /// this, only matching variables are restored (or compared, e.g. by
+    /// [`env_diff`][Playspace::env_diff]), and everything else is left as it
+    /// is on exit. N.B. that includes changes made inside the Playspace, e.g.
+    /// by [presets][PlayspaceBuilder::preset], to non-matching variables.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::builder().snapshot_only(["APP_"]).build().unwrap();
+    /// space.set_envs([("APP_MODE", Some("test")), ("__PLAYSPACE_UNRELATED", Some("kept"))]);
+    /// space.exit().unwrap();
+    ///
+    /// assert!(std::env::var("APP_MODE").is_err());
+    /// assert_eq!(std::env::var("__PLAYSPACE_UNRELATED").unwrap(), "kept");
+    /// # std::env::remove_var("__PLAYSPACE_UNRELATED");
+    /// ```
+    pub fn snapshot_only<I, S>(mut self, prefixes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.env_filter
+            .prefixes
+            .get_or_insert_with(Vec::new)
+            .extend(prefixes.into_iter().map(Into::into));
+        self
+    }
+
+    /// Never save or restore the given environment variables, in addition to
+    /// any given before.
+    ///
+    /// Whatever they are set to on exit, by this thread or any other, they are
+    /// left untouched. Useful for variables which other threads legitimately
+    /// change during a test.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::builder()
+    ///     .protect(["__PLAYSPACE_SHARED"])
+    ///     .build()
+    ///     .unwrap();
+    /// std::env::set_var("__PLAYSPACE_SHARED", "set during the test");
+    /// space.exit().unwrap();
+    ///
+    /// assert_eq!(std::env::var("__PLAYSPACE_SHARED").unwrap(), "set during the test");
+    /// # std::env::remove_var("__PLAYSPACE_SHARED");
+    /// ```
+    pub fn protect<I, S>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.env_filter
+            .protected
+            .extend(vars.into_iter().map(Into::into));
+        self
+    }
+
+    /// Choose whether to change the working directory into the Playspace.
+    ///
+    /// By default it is changed, as for [`Playspace::new`]. With `false`, the
+    /// Playspace directory is created but the working directory is left
+    /// alone, so only paths relative to the Playspace root (as used by its
+    /// helpers, e.g. [`write_file`][Playspace::write_file]) refer to it.
+    ///
+    /// Since nothing process-wide is changed, the Playspace also doesn't take
+    /// the lock, save or restore the environment, or count as
+    /// [active][Playspace::is_active]. Any number of them can exist at once,
+    /// alongside one normal Playspace, for tests which never rely on relative
+    /// paths resolving into the Playspace. Methods which explicitly change
+    /// the environment (e.g. [`set_envs`][Playspace::set_envs]) or working
+    /// directory (e.g. [`dir_scope`][Playspace::dir_scope]) still do so,
+    /// without anything being restored, so should be avoided.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let original = std::env::current_dir().unwrap();
+    /// let first = Playspace::builder().chdir(false).build().unwrap();
+    /// let second = Playspace::builder().chdir(false).build().unwrap();
+    /// first.write_file("file.txt", "first").unwrap();
+    /// second.write_file("file.txt", "second").unwrap();
+    ///
+    /// assert_eq!(std::env::current_dir().unwrap(), original);
+    /// assert_eq!(std::fs::read_to_string(first.directory().join("file.txt")).unwrap(), "first");
+    /// first.exit().unwrap();
+    /// second.exit().unwrap();
+    /// ```
+    pub fn chdir(mut self, chdir: bool) -> Self {
+        self.chdir = chdir;
+        self
+    }
+
+    /// Move to the given directory on exit if the original working directory
+    /// can't be returned to, e.g. because it was deleted.
+    ///
+    /// By default exiting then fails with [`ExitStep::WorkingDir`][crate::ExitStep::WorkingDir],
+    /// and the process is moved to the directory containing the Playspace
+    /// (see [`ExitReport::cwd_left_inside`][crate::ExitReport::cwd_left_inside]).
+    /// With a fallback, that step only fails if the fallback can't be moved to
+    /// either. The fallback should be an absolute path, e.g.
+    /// [`std::env::temp_dir`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let fallback = std::env::temp_dir();
+    /// let space = Playspace::builder()
+    ///     .fallback_directory(&fallback)
+    ///     .build()
+    ///     .unwrap();
+    /// space.exit().unwrap();
+    /// assert!(std::env::current_dir().is_ok());
+    /// ```
+    pub fn fallback_directory(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.fallback_dir = Some(dir.into());
+        self
+    }
+
+    /// Enter the configured Playspace, blocking until any other Playspace has
+    /// exited. Configured version of [`Playspace::new`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Playspace::new`]. Additionally, if any of the options
+    /// could not be applied then the Playspace is exited and the error is
+    /// returned.
+    pub fn build(self) -> Result<Playspace, SpaceError> {
+        let hold = if self.chdir {
+            Hold::take(blocking_lock)?
+        } else {
+            Hold::Unlocked
+        };
+        self.enter(hold)
+    }
+
+    /// Enter the configured Playspace, or fail immediately if already in a
+    /// Playspace. Configured version of [`Playspace::try_new`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Playspace::try_new`], and as for
+    /// [`build`][PlayspaceBuilder::build].
+    pub fn try_build(self) -> Result<Playspace, SpaceError> {
+        let hold = if self.chdir {
+            Hold::take(|| try_lock().ok_or(SpaceError::AlreadyInSpace))?
+        } else {
+            Hold::Unlocked
+        };
+        self.enter(hold)
+    }
+
+    /// Enter the configured Playspace, waiting asynchronously until any other
+    /// Playspace has exited. Configured version of [`Playspace::new_async`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`build`][PlayspaceBuilder::build].
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn build_async(self) -> Result<Playspace, SpaceError> {
+        let hold = match Hold::nested() {
+            _ if !self.chdir => Hold::Unlocked,
+            Some(hold) => hold,
+            None => Hold::Lock(lock_async().await),
+        };
+        self.enter(hold)
+    }
+
+    fn enter(self, hold: Hold) -> Result<Playspace, SpaceError> {
+        if matches!(hold, Hold::Unlocked) && !self.presets.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "environment presets can't be used without changing directory",
+            )
+            .into());
+        }
+
+        let make_directory = || match &self.ids {
+            Some(ids) => {
+                let root = match &self.root {
+                    Some(root) => root.clone(),
+                    None => config::get()
+                        .temp_root
+                        .clone()
+                        .unwrap_or_else(std::env::temp_dir),
+                };
+                named_directory(&root, &ids.next_id())
+            }
+            None => crate::default_directory(),
+        };
+        let mut space = Playspace::from_hold_with(hold, make_directory)?;
+        space.saved_environment.narrow(self.env_filter);
+        space.clock = self.clock;
+        space.on_drop_error = self.on_drop_error;
+        space.removal = self.removal;
+        space.fallback_dir = self.fallback_dir;
+
+        space.origin_link = self.origin_link;
+        space.retention = self.retention.map(|mut retention| {
+            if let (Some((report, _format)), Some(origin)) =
+                (&mut retention.report, &space.saved_current_dir)
+            {
+                *report = origin.join(&*report);
+            }
+            retention
+        });
+        space.quarantine = self.quarantine.map(|mut quarantine| {
+            if let Some(origin) = &space.saved_current_dir {
+                quarantine.dir = origin.join(&quarantine.dir);
+            }
+            // Clear up after any previous runs
+            quarantine::sweep(&quarantine.dir);
+            quarantine
+        });
+        space.presets = self.presets;
+        for preset in &space.presets {
+            space.apply(preset);
+        }
+        space.link_origin()?;
+        #[cfg(all(target_os = "linux", feature = "harden-linux"))]
+        if self.restrict_filesystem {
+            crate::harden::restrict_writes(space.directory())?;
+        }
+        Ok(space)
+    }
+}
+
+fn named_directory(root: &Path, id: &str) -> Result<TempDir, std::io::Error> {
+    let name = format!("playspace-{id}");
+
+    std::fs::create_dir_all(root)?;
+    let stale = root.join(&name);
+    if stale.exists() {
+        std::fs::remove_dir_all(stale)?;
+    }
+
+    tempfile::Builder::new()
+        .prefix(&name)
+        .rand_bytes(0)
+        .tempdir_in(root)
+}
+
+impl Playspace {
+    /// Create the link to the original working directory, if configured
+    pub(crate) fn link_origin(&self) -> Result<(), std::io::Error> {
+        let Some(name) = &self.origin_link else {
+            return Ok(());
+        };
+        let origin = self.saved_current_dir.as_deref().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no original working directory to link",
+            )
+        })?;
+        let link = self.directory().join(name);
+        if let Some(parent) = link.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(origin, link)?;
+        #[cfg(windows)]
+        std::os::windows::fs::symlink_dir(origin, link)?;
+
+        Ok(())
+    }
+
+    /// Configure a Playspace before entering it, see [`PlayspaceBuilder`].
+    pub fn builder() -> PlayspaceBuilder {
+        PlayspaceBuilder::new()
+    }
+}
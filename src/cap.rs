@@ -0,0 +1,38 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::io;
+
+use cap_std::{ambient_authority, fs::Dir};
+
+use crate::Playspace;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "cap-std")))]
+impl Playspace {
+    /// A [`cap_std`] capability for the Playspace root.
+    ///
+    /// Code under test which takes a [`Dir`] can be handed this rather than a
+    /// path. Every path is resolved relative to the Playspace root, whatever
+    /// the current working directory, and paths which would escape it (e.g.
+    /// absolute paths, `..`, or symlinks out) are errors rather than
+    /// accidents. Each call opens a new handle.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let dir = space.dir().unwrap();
+    ///     dir.write("config.toml", "key = 1").unwrap();
+    ///     assert!(dir.read_to_string("../escape.txt").is_err());
+    ///     assert!(space.directory().join("config.toml").exists());
+    /// }).unwrap();
+    /// ```
+    pub fn dir(&self) -> Result<Dir, io::Error> {
+        Dir::open_ambient_dir(self.directory(), ambient_authority())
+    }
+}
@@ -0,0 +1,105 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::Playspace;
+
+/// Directories created by [`Playspace::isolate_cargo`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoDirs {
+    home: PathBuf,
+    target: PathBuf,
+    rustup: PathBuf,
+}
+
+impl CargoDirs {
+    /// `CARGO_HOME`, which is `cargo/home` in the Playspace root.
+    #[must_use]
+    pub fn home(&self) -> &Path {
+        &self.home
+    }
+
+    /// `CARGO_TARGET_DIR`, which is `cargo/target` in the Playspace root.
+    #[must_use]
+    pub fn target(&self) -> &Path {
+        &self.target
+    }
+
+    /// `RUSTUP_HOME`, which is `cargo/rustup` in the Playspace root.
+    #[must_use]
+    pub fn rustup(&self) -> &Path {
+        &self.rustup
+    }
+
+    /// Create the empty directories cargo keeps downloaded crates and git
+    /// dependencies in (`registry/index`, `registry/cache`, `registry/src`,
+    /// `git/db`, and `git/checkouts` in [`home`][CargoDirs::home]), for tools
+    /// which expect them to exist.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    pub fn seed_registry(&self) -> Result<(), io::Error> {
+        for relative in [
+            "registry/index",
+            "registry/cache",
+            "registry/src",
+            "git/db",
+            "git/checkouts",
+        ] {
+            std::fs::create_dir_all(self.home.join(relative))?;
+        }
+        Ok(())
+    }
+}
+
+impl Playspace {
+    /// Create private cargo and rustup directories in the Playspace, and
+    /// point `CARGO_HOME`, `CARGO_TARGET_DIR`, and `RUSTUP_HOME` at them.
+    ///
+    /// Tools which invoke cargo then can't read the user's cargo
+    /// configuration or credentials, or write to their registry cache or
+    /// build directories. See [`CargoDirs`] for where each directory is.
+    ///
+    /// N.B. with an empty `RUSTUP_HOME`, rustup has no toolchains installed,
+    /// so running `cargo` through a rustup proxy fails. Run the toolchain's
+    /// `cargo` directly (e.g. from `rustup which cargo`, before isolating),
+    /// or copy in what's needed.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let cargo = space.isolate_cargo().unwrap();
+    ///     cargo.seed_registry().unwrap();
+    ///     assert_eq!(std::env::var_os("CARGO_HOME").unwrap(), cargo.home());
+    ///     assert!(cargo.home().join("registry/index").is_dir());
+    /// }).unwrap();
+    /// ```
+    pub fn isolate_cargo(&self) -> Result<CargoDirs, io::Error> {
+        let root = self.directory().join("cargo");
+        let dirs = CargoDirs {
+            home: root.join("home"),
+            target: root.join("target"),
+            rustup: root.join("rustup"),
+        };
+        for path in [&dirs.home, &dirs.target, &dirs.rustup] {
+            std::fs::create_dir_all(path)?;
+        }
+        self.set_envs([
+            ("CARGO_HOME", Some(&dirs.home)),
+            ("CARGO_TARGET_DIR", Some(&dirs.target)),
+            ("RUSTUP_HOME", Some(&dirs.rustup)),
+        ]);
+        Ok(dirs)
+    }
+}
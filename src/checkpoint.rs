@@ -0,0 +1,106 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::io;
+
+use tempfile::TempDir;
+
+use crate::{copy, EnvSnapshot, Playspace};
+
+/// A saved state of a Playspace's files and environment.
+///
+/// Created with [`Playspace::checkpoint`], and restored with
+/// [`Playspace::rollback`]. The files are stored outside of the Playspace, and
+/// removed when the `Checkpoint` is dropped.
+#[derive(Debug)]
+pub struct Checkpoint {
+    environment: EnvSnapshot,
+    files: TempDir,
+}
+
+impl Checkpoint {
+    /// The environment saved by the checkpoint.
+    #[must_use]
+    pub fn environment(&self) -> &EnvSnapshot {
+        &self.environment
+    }
+}
+
+impl Playspace {
+    /// Save the current files and environment, so that they can be restored
+    /// later with [`rollback`][Playspace::rollback].
+    ///
+    /// Files are reflinked where the filesystem supports it, and copied
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("state.txt", "initialised").unwrap();
+    ///     let initialised = space.checkpoint().unwrap();
+    ///
+    ///     for phase in ["migrated", "corrupted"] {
+    ///         space.write_file("state.txt", phase).unwrap();
+    ///         // ... assertions ...
+    ///         space.rollback(&initialised).unwrap();
+    ///         assert_eq!(std::fs::read_to_string("state.txt").unwrap(), "initialised");
+    ///     }
+    /// }).unwrap();
+    /// ```
+    pub fn checkpoint(&self) -> Result<Checkpoint, io::Error> {
+        // Alongside the Playspace rather than in the system temporary
+        // directory, which may have been moved inside it
+        let files = match self.directory().parent() {
+            Some(parent) => tempfile::Builder::new()
+                .prefix("playspace-checkpoint-")
+                .tempdir_in(parent)?,
+            None => tempfile::tempdir()?,
+        };
+        copy::copy_tree(self.directory(), files.path(), |_path| false)?;
+
+        Ok(Checkpoint {
+            environment: EnvSnapshot::capture_filtered(self.saved_environment.filter.clone()),
+            files,
+        })
+    }
+
+    /// Restore the files and environment saved in a
+    /// [`checkpoint`][Playspace::checkpoint].
+    ///
+    /// Everything in the Playspace is replaced by the checkpointed files, and
+    /// the environment is made to match the checkpointed one exactly. The
+    /// working directory is kept if it still exists afterwards, otherwise it
+    /// is changed to the Playspace root. A checkpoint can be rolled back to
+    /// any number of times.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up, in which case the files may only
+    /// have been partially restored.
+    ///
+    /// # Example
+    ///
+    /// See [`checkpoint`][Playspace::checkpoint].
+    pub fn rollback(&self, checkpoint: &Checkpoint) -> Result<(), io::Error> {
+        if !self.changes_directory() {
+            copy::clear_dir(self.directory())?;
+            return copy::copy_tree(checkpoint.files.path(), self.directory(), |_path| false);
+        }
+        checkpoint.environment.restore();
+
+        let current_dir = std::env::current_dir().ok();
+        copy::clear_dir(self.directory())?;
+        copy::copy_tree(checkpoint.files.path(), self.directory(), |_path| false)?;
+
+        match current_dir {
+            Some(current_dir) if current_dir.is_dir() => std::env::set_current_dir(current_dir),
+            _ => std::env::set_current_dir(self.directory()),
+        }
+    }
+}
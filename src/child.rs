@@ -0,0 +1,165 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{normalize, Playspace, WriteError};
+
+/// A subdirectory of a Playspace, removed (along with everything in it) when
+/// dropped.
+///
+/// Created with [`Playspace::child`]. Lets the cases of a parameterized test
+/// share one Playspace, and so one wait for the lock, while each still starts
+/// from an empty directory.
+#[must_use = "the child directory is removed as soon as it is dropped"]
+pub struct ChildSpace<'a> {
+    space: &'a Playspace,
+    directory: PathBuf,
+    previous: Option<PathBuf>,
+}
+
+impl Playspace {
+    /// Create an empty subdirectory of the Playspace, which is removed again
+    /// when the returned [`ChildSpace`] is dropped.
+    ///
+    /// Relative paths are evaluated with respect to the Playspace root. If
+    /// the directory already exists, it is emptied first. The working
+    /// directory is left alone, unless the child is
+    /// [`chdir`][ChildSpace::chdir]ed into.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     for case in ["case-1", "case-2"] {
+    ///         let child = space.child(case).unwrap().chdir().unwrap();
+    ///         assert!(!std::path::Path::new("output.txt").exists());
+    ///         std::fs::write("output.txt", case).unwrap();
+    ///     }
+    ///     assert!(!space.exists("case-1"));
+    /// }).unwrap();
+    /// ```
+    pub fn child(&self, path: impl AsRef<Path>) -> Result<ChildSpace<'_>, WriteError> {
+        let directory = self.playspace_path(path)?;
+        if directory == self.directory() {
+            return Err(WriteError::OutsidePlayspace(directory));
+        }
+        match std::fs::remove_dir_all(&directory) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => {
+                return Err(WriteError::io(&directory, error));
+            }
+            _ => (),
+        }
+        std::fs::create_dir_all(&directory).map_err(|source| WriteError::io(&directory, source))?;
+
+        Ok(ChildSpace {
+            space: self,
+            directory,
+            previous: None,
+        })
+    }
+}
+
+impl ChildSpace<'_> {
+    /// Path to the child directory.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Change the working directory to the child directory, until it is
+    /// dropped or [`exit`][ChildSpace::exit]ed, when the previous working
+    /// directory is restored.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    pub fn chdir(mut self) -> Result<Self, io::Error> {
+        let previous = std::env::current_dir()?;
+        std::env::set_current_dir(&self.directory)?;
+        self.previous.get_or_insert(previous);
+        Ok(self)
+    }
+
+    /// Write a file in the child directory, as for
+    /// [`Playspace::write_file`] but relative to the child.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the child directory, an error will be
+    /// returned. Any stardard IO error is bubbled-up.
+    pub fn write_file(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<[u8]>,
+    ) -> Result<(), WriteError> {
+        self.space.write_file(self.child_path(path)?, contents)
+    }
+
+    /// Write files in the child directory, as for
+    /// [`Playspace::write_files`] but relative to the child.
+    ///
+    /// # Errors
+    ///
+    /// If any of the provided paths are not in the child directory, an error
+    /// will be returned. Any stardard IO error is bubbled-up.
+    pub fn write_files<I, P, C>(&self, files: I) -> Result<(), WriteError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        let files = files
+            .into_iter()
+            .map(|(path, contents)| Ok((self.child_path(path)?, contents)))
+            .collect::<Result<Vec<_>, WriteError>>()?;
+        self.space.write_files(files)
+    }
+
+    /// Restore the working directory if needed, and remove the child
+    /// directory, reporting any errors doing so.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    pub fn exit(mut self) -> Result<(), io::Error> {
+        self.cleanup()
+    }
+
+    fn child_path(&self, path: impl AsRef<Path>) -> Result<PathBuf, WriteError> {
+        let path = normalize(&self.directory.join(path));
+        if path.starts_with(&self.directory) {
+            Ok(path)
+        } else {
+            Err(WriteError::OutsidePlayspace(path))
+        }
+    }
+
+    /// Undo everything, safe to call more than once
+    fn cleanup(&mut self) -> Result<(), io::Error> {
+        let restored = match self.previous.take() {
+            Some(previous) => std::env::set_current_dir(previous),
+            None => Ok(()),
+        };
+        let removed = match std::fs::remove_dir_all(&self.directory) {
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            other => other,
+        };
+        restored.and(removed)
+    }
+}
+
+impl Drop for ChildSpace<'_> {
+    fn drop(&mut self) {
+        let _ignored = self.cleanup();
+    }
+}
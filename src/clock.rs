@@ -0,0 +1,118 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Sources of time and IDs, which can be replaced for deterministic tests.
+//!
+//! Everything a Playspace timestamps (e.g. [log lines][crate::Playspace::log]
+//! and [touched files][crate::Playspace::touch]) reads the time from its
+//! [`Clock`], and every directory name it generates comes from its
+//! [`IdSource`]. Both can be set on the [builder][crate::PlayspaceBuilder], so
+//! that artifacts produced by the Playspace itself are reproducible, or to
+//! integrate with a simulated-time test framework. Closures can be used as
+//! either.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use playspace::{clock::{FixedClock, SequentialIds}, Playspace};
+//! # use std::time::{Duration, UNIX_EPOCH};
+//! let space = Playspace::builder()
+//!     .clock(FixedClock(UNIX_EPOCH + Duration::from_secs(1_000_000)))
+//!     .id_source(SequentialIds::new())
+//!     .build()
+//!     .unwrap();
+//!
+//! space.log("deterministic").unwrap();
+//! let log = std::fs::read_to_string(playspace::LOG_FILE).unwrap();
+//! assert_eq!(log, "[1000000.000] deterministic\n");
+//! ```
+
+use std::{
+    fmt::Debug,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::SystemTime,
+};
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+/// A source of unique IDs.
+pub trait IdSource: Send + Sync {
+    /// The next ID. IDs are used in file names, so should only contain
+    /// characters that are valid in them.
+    fn next_id(&self) -> String;
+}
+
+impl<F> IdSource for F
+where
+    F: Fn() -> String + Send + Sync,
+{
+    fn next_id(&self) -> String {
+        self()
+    }
+}
+
+/// The real time, from [`SystemTime::now`]. Used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock which is stopped at the given time.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// IDs counting up from `0001`, `0002`, ...
+#[derive(Debug, Default)]
+pub struct SequentialIds {
+    last: AtomicUsize,
+}
+
+impl SequentialIds {
+    /// Start a new sequence.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdSource for SequentialIds {
+    fn next_id(&self) -> String {
+        let number = self.last.fetch_add(1, Ordering::Relaxed) + 1;
+        format!("{number:04}")
+    }
+}
+
+/// The sequence shared by every builder using `sequential_names`
+pub(crate) struct ProcessSequence;
+
+impl IdSource for ProcessSequence {
+    fn next_id(&self) -> String {
+        static SEQUENCE: SequentialIds = SequentialIds {
+            last: AtomicUsize::new(0),
+        };
+        SEQUENCE.next_id()
+    }
+}
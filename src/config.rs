@@ -0,0 +1,176 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Process-wide defaults for every Playspace.
+//!
+//! The [builder][crate::PlayspaceBuilder] configures one Playspace at a
+//! time. Test harnesses can instead [`configure`] defaults once, which apply
+//! to every Playspace created afterwards, however it is created. Options set
+//! on a builder still take precedence.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use playspace::{config::{self, Config}, DropErrorPolicy, Playspace};
+//! let _ = config::configure(
+//!     Config::new()
+//!         .keep_on_failure()
+//!         .on_drop_error(DropErrorPolicy::Log),
+//! );
+//!
+//! let space = Playspace::new().unwrap();
+//! # space.exit().unwrap();
+//! ```
+
+use std::{
+    path::PathBuf,
+    sync::{LazyLock, OnceLock},
+    time::Duration,
+};
+
+use crate::{removal::Removal, DropErrorPolicy};
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static DEFAULT: LazyLock<Config> = LazyLock::new(Config::default);
+
+/// Process-wide defaults, see the [module documentation][self].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct Config {
+    pub(crate) temp_root: Option<PathBuf>,
+    pub(crate) keep_on_failure: bool,
+    pub(crate) on_drop_error: DropErrorPolicy,
+    pub(crate) removal: Removal,
+    pub(crate) nesting: bool,
+    pub(crate) fair: bool,
+    #[cfg(feature = "tracing")]
+    pub(crate) max_trace_level: Option<tracing::Level>,
+}
+
+impl Config {
+    /// The defaults used if nothing is configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create Playspace directories in `root`, rather than
+    /// [`std::env::temp_dir`].
+    ///
+    /// `root` is created if needed. If relative, it is relative to the
+    /// working directory when [`configure`] is called. Directories named by
+    /// [`sequential_names`][crate::PlayspaceBuilder::sequential_names] are
+    /// still created in the root given there.
+    pub fn temp_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.temp_root = Some(root.into());
+        self
+    }
+
+    /// Keep Playspace directories of failed tests, see
+    /// [`PlayspaceBuilder::keep_on_failure`][crate::PlayspaceBuilder::keep_on_failure].
+    pub fn keep_on_failure(mut self) -> Self {
+        self.keep_on_failure = true;
+        self
+    }
+
+    /// What to do with errors exiting dropped Playspaces, see
+    /// [`PlayspaceBuilder::on_drop_error`][crate::PlayspaceBuilder::on_drop_error].
+    pub fn on_drop_error(mut self, policy: DropErrorPolicy) -> Self {
+        self.on_drop_error = policy;
+        self
+    }
+
+    /// How many times to try removing Playspace directories, see
+    /// [`PlayspaceBuilder::removal_retries`][crate::PlayspaceBuilder::removal_retries].
+    pub fn removal_retries(mut self, attempts: u32, delay: Duration) -> Self {
+        self.removal = Removal { attempts, delay };
+        self
+    }
+
+    /// Let a thread already in a Playspace enter another, nested one, rather
+    /// than deadlocking (or failing for the `try_*` constructors).
+    ///
+    /// The nested Playspace saves the environment and working directory of
+    /// its parent, and restores them on exit, when the parent becomes the
+    /// [current][crate::Playspace::current_root] Playspace again. This lets
+    /// test helpers which use a Playspace of their own be called from tests
+    /// which are already in one. Nested Playspaces must be exited before
+    /// their parents: one exited out of order leaves the environment and
+    /// working directory alone, since they may belong to another Playspace
+    /// by then, and fails with [`StepFailure::OutOfOrder`][crate::StepFailure::OutOfOrder].
+    ///
+    /// Only threads are recognised: an async task which moves between
+    /// threads may not be. Other threads still wait for the outermost
+    /// Playspace to exit, as do [sequences][crate::Playspace::sequence].
+    pub fn allow_nesting(mut self) -> Self {
+        self.nesting = true;
+        self
+    }
+
+    /// Enter Playspaces strictly in the order they started waiting.
+    ///
+    /// By default, a thread which has only just started waiting may enter
+    /// before one which has been waiting for a while, which is faster
+    /// overall but can starve unlucky tests and make their wall-times
+    /// unpredictable. With fair queuing, the lock is handed straight to the
+    /// longest waiter whenever a Playspace (or
+    /// [`ReadOnlySpace`][crate::ReadOnlySpace]) exits, across threads and
+    /// tasks alike. With the `async` feature, the lock is always fair, so
+    /// this has no effect.
+    pub fn fair_queuing(mut self) -> Self {
+        self.fair = true;
+        self
+    }
+
+    /// Only emit [`tracing`] events at `level` or more important. By default,
+    /// every event is emitted and left to the subscriber to filter.
+    #[cfg(feature = "tracing")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+    pub fn max_trace_level(mut self, level: tracing::Level) -> Self {
+        self.max_trace_level = Some(level);
+        self
+    }
+}
+
+/// Set the process-wide defaults.
+///
+/// Only Playspaces created afterwards are affected, so this should be called
+/// before any are (e.g. at the start of every test through a shared helper,
+/// ignoring the error).
+///
+/// # Errors
+///
+/// If the defaults have already been configured, they are left unchanged and
+/// [`ConfigureError::AlreadyConfigured`] is returned. Any error finding the current working
+/// directory to resolve a relative [`temp_root`][Config::temp_root] is
+/// bubbled-up.
+pub fn configure(mut config: Config) -> Result<(), ConfigureError> {
+    if let Some(root) = &mut config.temp_root {
+        *root = std::path::absolute(&*root)?;
+    }
+    CONFIG
+        .set(config)
+        .map_err(|_config| ConfigureError::AlreadyConfigured)
+}
+
+/// The configured defaults
+pub(crate) fn get() -> &'static Config {
+    CONFIG.get().unwrap_or(&DEFAULT)
+}
+
+/// Whether events at `level` should be emitted
+#[cfg(feature = "tracing")]
+pub(crate) fn traces(level: tracing::Level) -> bool {
+    get().max_trace_level.is_none_or(|max| level <= max)
+}
+
+/// Error from [`configure`]
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ConfigureError {
+    /// [`configure`] has already been called.
+    #[error("playspace has already been configured")]
+    AlreadyConfigured,
+    /// A bubbled-up error from [`std::io`] functions.
+    #[error(transparent)]
+    StdIo(#[from] std::io::Error),
+}
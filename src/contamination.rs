@@ -0,0 +1,139 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{fmt::Display, path::PathBuf};
+
+use parking_lot::Mutex;
+
+use crate::{EnvDiff, EnvSnapshot, Playspace, SpaceError};
+
+/// What to do when the process state is found to be contaminated on entering
+/// a Playspace, see [`Playspace::detect_contamination`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OnContamination {
+    /// Print a description of the contamination to stderr, then carry on.
+    Warn,
+    /// Fail to enter the Playspace with [`SpaceError::Contaminated`].
+    Fail,
+}
+
+/// Changes made to the process outside of any Playspace
+///
+/// Found by [`Playspace::detect_contamination`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contamination {
+    /// Changes to the environment since the baseline.
+    pub environment: EnvDiff,
+    /// The working directory of the baseline and the one found instead, if
+    /// it changed.
+    pub working_dir: Option<(Option<PathBuf>, Option<PathBuf>)>,
+}
+
+impl Display for Contamination {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut changes = Vec::new();
+        let environment = &self.environment;
+        for key in environment.added.keys() {
+            changes.push(format!("{} was set", key.to_string_lossy()));
+        }
+        for key in environment.changed.keys() {
+            changes.push(format!("{} was changed", key.to_string_lossy()));
+        }
+        for key in environment.removed.keys() {
+            changes.push(format!("{} was removed", key.to_string_lossy()));
+        }
+        if let Some((before, after)) = &self.working_dir {
+            let show = |dir: &Option<PathBuf>| {
+                dir.as_ref()
+                    .map_or_else(|| "<none>".to_owned(), |dir| dir.display().to_string())
+            };
+            changes.push(format!(
+                "working directory changed from {} to {}",
+                show(before),
+                show(after)
+            ));
+        }
+        write!(f, "{}", changes.join(", "))
+    }
+}
+
+struct Baseline {
+    on_contamination: OnContamination,
+    environment: EnvSnapshot,
+    working_dir: Option<PathBuf>,
+}
+
+static BASELINE: Mutex<Option<Baseline>> = parking_lot::const_mutex(None);
+
+impl Playspace {
+    /// Check, every time a Playspace is entered, that nothing has changed the
+    /// environment or working directory since the last one exited.
+    ///
+    /// Everything a test does inside a Playspace is undone when it exits, but
+    /// changes made outside of any Playspace leak into every test that runs
+    /// afterwards, causing failures that depend on the order tests run in.
+    /// Once enabled, the environment and working directory are recorded as a
+    /// baseline, then compared with it whenever a Playspace is entered, so a
+    /// leak is caught by the very next Playspace.
+    ///
+    /// Call this as early in the process as possible, e.g. at the start of
+    /// every test. Only the first call records the baseline, later calls only
+    /// change `on_contamination`. After contamination is found, the
+    /// contaminated state becomes the new baseline, so each leak is reported
+    /// only once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{OnContamination, Playspace, SpaceError};
+    /// Playspace::detect_contamination(OnContamination::Fail);
+    ///
+    /// std::env::set_var("LEAKED_FROM_A_TEST", "oops");
+    /// assert!(matches!(Playspace::new(), Err(SpaceError::Contaminated(_))));
+    /// ```
+    pub fn detect_contamination(on_contamination: OnContamination) {
+        let mut baseline = BASELINE.lock();
+        match &mut *baseline {
+            Some(baseline) => baseline.on_contamination = on_contamination,
+            None => {
+                *baseline = Some(Baseline {
+                    on_contamination,
+                    environment: EnvSnapshot::capture(),
+                    working_dir: std::env::current_dir().ok(),
+                });
+            }
+        }
+    }
+}
+
+/// Compare the process state with the baseline, if there is one
+pub(crate) fn check() -> Result<(), SpaceError> {
+    let mut baseline = BASELINE.lock();
+    let Some(baseline) = &mut *baseline else {
+        return Ok(());
+    };
+
+    let environment = baseline.environment.diff();
+    let working_dir = std::env::current_dir().ok();
+    let working_dir_changed = working_dir != baseline.working_dir;
+    if environment.is_empty() && !working_dir_changed {
+        return Ok(());
+    }
+
+    let contamination = Contamination {
+        environment,
+        working_dir: working_dir_changed
+            .then(|| (baseline.working_dir.clone(), working_dir.clone())),
+    };
+    baseline.environment = EnvSnapshot::capture();
+    baseline.working_dir = working_dir;
+
+    match baseline.on_contamination {
+        OnContamination::Warn => {
+            eprintln!("playspace: contamination outside of a Playspace: {contamination}");
+            Ok(())
+        }
+        OnContamination::Fail => Err(SpaceError::Contaminated(contamination)),
+    }
+}
@@ -0,0 +1,131 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{io, path::Path};
+
+use crate::walk::walk;
+
+/// Copy everything under `from` into `to`, which is created if necessary.
+///
+/// Files are reflinked where the filesystem supports it, and copied
+/// otherwise. Symlinks are recreated rather than followed. Any entry for
+/// which `skip` returns true (given the path relative to `from`) is skipped,
+/// along with all of its children.
+pub(crate) fn copy_tree(
+    from: &Path,
+    to: &Path,
+    skip: impl Fn(&Path) -> bool,
+) -> Result<(), io::Error> {
+    std::fs::create_dir_all(to)?;
+
+    let mut skipped: Vec<&Path> = Vec::new();
+    let entries = walk(from)?;
+    for path in &entries {
+        if skipped.iter().any(|parent| path.starts_with(parent)) {
+            continue;
+        }
+        if skip(path) {
+            skipped.push(path);
+            continue;
+        }
+
+        let source = from.join(path);
+        let destination = to.join(path);
+        let file_type = std::fs::symlink_metadata(&source)?.file_type();
+        if file_type.is_symlink() {
+            copy_symlink(&source, &destination)?;
+        } else if file_type.is_dir() {
+            std::fs::create_dir_all(&destination)?;
+        } else {
+            reflink_copy::reflink_or_copy(&source, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// Bring the file at `from` to `to` as cheaply as possible: reflinked if the
+/// filesystem supports it, hard-linked if both are on the same filesystem,
+/// and copied otherwise. `to` must not already exist.
+pub(crate) fn link_or_copy(from: &Path, to: &Path) -> Result<(), io::Error> {
+    if std::fs::symlink_metadata(to).is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+
+    if reflink_copy::reflink(from, to).is_ok() || std::fs::hard_link(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to).map(drop)
+}
+
+/// Create a symlink at `link` pointing to `target`, which is found at
+/// `resolved`.
+///
+/// On Windows, creating symlinks needs a privilege that is often missing, so
+/// the target is copied to `link` instead.
+#[cfg(unix)]
+pub(crate) fn symlink(target: &Path, _resolved: &Path, link: &Path) -> Result<(), io::Error> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Create a symlink at `link` pointing to `target`, which is found at
+/// `resolved`.
+///
+/// On Windows, creating symlinks needs a privilege that is often missing, so
+/// the target is copied to `link` instead.
+#[cfg(windows)]
+pub(crate) fn symlink(target: &Path, resolved: &Path, link: &Path) -> Result<(), io::Error> {
+    // ERROR_PRIVILEGE_NOT_HELD
+    const PRIVILEGE_NOT_HELD: i32 = 1314;
+
+    let is_dir = std::fs::metadata(resolved).is_ok_and(|metadata| metadata.is_dir());
+    let linked = if is_dir {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    };
+    match linked {
+        Err(error) if error.raw_os_error() == Some(PRIVILEGE_NOT_HELD) => {
+            if is_dir {
+                copy_tree(resolved, link, |_path| false)
+            } else {
+                std::fs::copy(resolved, link).map(drop)
+            }
+        }
+        linked => linked,
+    }
+}
+
+/// Remove everything inside `root`, but not `root` itself.
+pub(crate) fn clear_dir(root: &Path) -> Result<(), io::Error> {
+    for entry in std::fs::read_dir(root)? {
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if metadata.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else if let Err(error) = std::fs::remove_file(&path) {
+            // Symlinks to directories must be removed as directories on Windows
+            if !metadata.file_type().is_symlink() || std::fs::remove_dir(&path).is_err() {
+                return Err(error);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, destination)
+}
+
+#[cfg(windows)]
+fn copy_symlink(source: &Path, destination: &Path) -> Result<(), io::Error> {
+    let target = std::fs::read_link(source)?;
+    if std::fs::metadata(source)?.is_dir() {
+        std::os::windows::fs::symlink_dir(target, destination)
+    } else {
+        std::os::windows::fs::symlink_file(target, destination)
+    }
+}
@@ -0,0 +1,245 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    thread::ThreadId,
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+use crate::Playspace;
+
+/// Whether a Playspace is currently entered in this process
+///
+/// Returned by [`Playspace::status`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpaceStatus {
+    /// No Playspace is entered, so one can be entered straight away.
+    Free,
+    /// A Playspace is entered, so entering another would wait (or fail).
+    Held(SpaceHolder),
+}
+
+/// Details of the Playspace currently entered, see [`SpaceStatus::Held`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceHolder {
+    root: PathBuf,
+    thread: ThreadId,
+    thread_name: Option<String>,
+    entered: Instant,
+    /// Whether it has been detached, so isn't really held by `thread`
+    detached: bool,
+}
+
+impl SpaceHolder {
+    /// The root directory of the Playspace.
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The thread holding the Playspace: the one that entered it, or last
+    /// [attached][crate::DetachedPlayspace::attach] it.
+    #[must_use]
+    pub fn thread(&self) -> ThreadId {
+        self.thread
+    }
+
+    /// The name of the holding thread, if it has one. Under the standard
+    /// test harness, this is the name of the test.
+    #[must_use]
+    pub fn thread_name(&self) -> Option<&str> {
+        self.thread_name.as_deref()
+    }
+
+    /// When the Playspace was entered.
+    #[must_use]
+    pub fn entered(&self) -> Instant {
+        self.entered
+    }
+}
+
+/// Every Playspace entered, outermost first. Only the outermost holds the
+/// lock, the rest are [nested][crate::config::Config::allow_nesting] in it,
+/// and the last is the current one.
+static ACTIVE: Mutex<Vec<SpaceHolder>> = parking_lot::const_mutex(Vec::new());
+
+/// Record that a Playspace has been entered, nested in any already entered.
+/// Only call while holding the lock, or nested in its holder.
+pub(crate) fn enter(root: &Path) {
+    let thread = std::thread::current();
+    ACTIVE.lock().push(SpaceHolder {
+        root: root.to_owned(),
+        thread: thread.id(),
+        thread_name: thread.name().map(str::to_owned),
+        entered: Instant::now(),
+        detached: false,
+    });
+}
+
+/// Record that the Playspace is now held by the current thread
+pub(crate) fn attach() {
+    let thread = std::thread::current();
+    for holder in &mut *ACTIVE.lock() {
+        holder.thread = thread.id();
+        holder.thread_name = thread.name().map(str::to_owned);
+        holder.detached = false;
+    }
+}
+
+/// Record that the Playspace is no longer held by any thread, until attached
+pub(crate) fn detach() {
+    for holder in &mut *ACTIVE.lock() {
+        holder.detached = true;
+    }
+}
+
+/// Record that the outermost Playspace has been exited, along with anything
+/// still nested in it. Only call while holding the lock.
+pub(crate) fn exit() {
+    ACTIVE.lock().clear();
+}
+
+/// The current Playspace, if it is held by the current thread
+pub(crate) fn held_here() -> Option<SpaceHolder> {
+    ACTIVE
+        .lock()
+        .last()
+        .filter(|holder| !holder.detached && holder.thread == std::thread::current().id())
+        .cloned()
+}
+
+thread_local! {
+    /// How many scopes on this thread are pinned, see [`pin`]
+    static PINS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Record that a scope on this thread holds the lock until the returned
+/// guard is dropped, for scopes which hold it without a Playspace being
+/// [held here][held_here], e.g. between the phases of a sequence. A scope
+/// can't end until this thread returns to it, so this thread waiting for the
+/// lock meanwhile would certainly wait forever.
+///
+/// This goes for shared access too: a waiting Playspace makes new readers
+/// wait behind it, so a second read can wait forever on the first.
+pub(crate) fn pin() -> Pin {
+    PINS.with(|pins| pins.set(pins.get() + 1));
+    Pin(())
+}
+
+/// Unpins a scope when dropped, see [`pin`]
+pub(crate) struct Pin(());
+
+impl Drop for Pin {
+    fn drop(&mut self) {
+        PINS.with(|pins| pins.set(pins.get() - 1));
+    }
+}
+
+/// Whether any scope on this thread holds the lock, shared or not
+pub(crate) fn pinned_here() -> bool {
+    PINS.with(Cell::get) > 0
+}
+
+/// Record that the nested Playspace at `root` has been exited, so its parent
+/// is current again.
+///
+/// Returns whether it was current. If not, it was exited out of order:
+/// either its parent has already exited (and another Playspace may have
+/// been entered since), or a Playspace nested in it is still entered.
+pub(crate) fn exit_nested(root: &Path) -> bool {
+    let mut active = ACTIVE.lock();
+    if active.last().is_some_and(|holder| holder.root == root) {
+        active.pop();
+        true
+    } else {
+        active.retain(|holder| holder.root != root);
+        false
+    }
+}
+
+impl Playspace {
+    /// The root directory of the Playspace currently entered in this process,
+    /// if any.
+    ///
+    /// Lets code deep in the call stack (e.g. test helper crates) find the
+    /// Playspace, without needing the `Playspace` itself to be passed all the
+    /// way down, for example to write artifacts into it.
+    ///
+    /// N.B. by the time the result is used, the Playspace may have exited,
+    /// unless the caller is known to be running inside it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// fn write_artifact(name: &str, contents: &str) {
+    ///     if let Some(root) = Playspace::current_root() {
+    ///         std::fs::write(root.join(name), contents).unwrap();
+    ///     }
+    /// }
+    ///
+    /// assert!(Playspace::current_root().is_none());
+    /// Playspace::scoped(|space| {
+    ///     assert_eq!(Playspace::current_root().as_deref(), Some(space.directory()));
+    ///     write_artifact("artifact.txt", "contents");
+    ///     assert!(space.exists("artifact.txt"));
+    /// }).unwrap();
+    /// assert!(Playspace::current_root().is_none());
+    /// ```
+    #[must_use]
+    pub fn current_root() -> Option<PathBuf> {
+        ACTIVE.lock().last().map(|holder| holder.root.clone())
+    }
+
+    /// Whether a Playspace is currently entered in this process.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// assert!(!Playspace::is_active());
+    /// let space = Playspace::new().unwrap();
+    /// assert!(Playspace::is_active());
+    /// ```
+    #[must_use]
+    pub fn is_active() -> bool {
+        !ACTIVE.lock().is_empty()
+    }
+
+    /// Whether a Playspace is currently entered in this process and, if so,
+    /// which thread holds it and since when.
+    ///
+    /// Useful for test harnesses to decide whether to wait for a Playspace,
+    /// skip a test, or fail fast, before trying to enter one. This is only a
+    /// snapshot: another thread may enter or exit a Playspace at any time.
+    /// Between the phases of a [`SpaceSequence`][crate::SpaceSequence], no
+    /// Playspace is entered, but the lock is still held.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, SpaceStatus};
+    /// let space = Playspace::new().unwrap();
+    /// match Playspace::status() {
+    ///     SpaceStatus::Held(holder) => {
+    ///         assert_eq!(holder.root(), space.directory());
+    ///         assert_eq!(holder.thread(), std::thread::current().id());
+    ///     }
+    ///     _ => panic!("should be held"),
+    /// }
+    /// space.exit().unwrap();
+    /// assert_eq!(Playspace::status(), SpaceStatus::Free);
+    /// ```
+    #[must_use]
+    pub fn status() -> SpaceStatus {
+        match ACTIVE.lock().last() {
+            Some(holder) => SpaceStatus::Held(holder.clone()),
+            None => SpaceStatus::Free,
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use static_assertions::assert_impl_all;
+
+use crate::Playspace;
+
+/// A Playspace in transit between threads.
+///
+/// Created with [`Playspace::detach`]. The Playspace stays entered (and the
+/// Playspace lock stays held) the whole time, but it can't be used until it
+/// is [`attach`][DetachedPlayspace::attach]ed again, by whichever thread or
+/// task finishes with it. Dropping a `DetachedPlayspace` exits the Playspace,
+/// just like dropping a [`Playspace`].
+#[must_use = "dropping a detached Playspace exits it"]
+pub struct DetachedPlayspace {
+    space: Playspace,
+}
+
+assert_impl_all!(DetachedPlayspace: Send);
+
+impl DetachedPlayspace {
+    /// Take over the Playspace, on the current thread.
+    ///
+    /// The current thread becomes the [holder][crate::SpaceHolder::thread]
+    /// of the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let detached = Playspace::new().unwrap().detach();
+    /// let space = detached.attach();
+    /// space.exit().unwrap();
+    /// ```
+    pub fn attach(self) -> Playspace {
+        crate::current::attach();
+        self.space
+    }
+}
+
+impl Playspace {
+    /// Package up the Playspace to be handed to another thread or task.
+    ///
+    /// Useful for harnesses where one thread sets up the Playspace, and a
+    /// worker thread runs the test and exits it. The working directory and
+    /// environment are per-process, so they are unaffected. Nothing is
+    /// released: no other Playspace can be entered until the Playspace is
+    /// [attached][DetachedPlayspace::attach] and exited (or the
+    /// `DetachedPlayspace` is dropped).
+    ///
+    /// Until detached, the Playspace is held by the thread which entered it,
+    /// which gets [`SpaceError::WouldDeadlock`][crate::SpaceError::WouldDeadlock]
+    /// rather than waiting for another Playspace, even if it has moved the
+    /// Playspace to another thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::new().unwrap();
+    /// space.write_file("input.txt", "prepared by setup").unwrap();
+    /// let detached = space.detach();
+    ///
+    /// std::thread::spawn(move || {
+    ///     let space = detached.attach();
+    ///     assert_eq!(std::fs::read_to_string("input.txt").unwrap(), "prepared by setup");
+    ///     space.exit().unwrap();
+    /// })
+    /// .join()
+    /// .unwrap();
+    /// ```
+    pub fn detach(self) -> DetachedPlayspace {
+        crate::current::detach();
+        DetachedPlayspace { space: self }
+    }
+}
@@ -0,0 +1,96 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::{Path, PathBuf};
+
+use crate::{
+    env::{env_file_contents, env_lock},
+    Playspace, WriteError,
+};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "dotenv")))]
+impl Playspace {
+    /// Set environment variables from a dotenv-format file.
+    ///
+    /// Relative paths are _always_ evaluated with respect to the Playspace
+    /// root directory, and the path is checked to be inside the Playspace,
+    /// just as for [`write_file`][Playspace::write_file], so this is usually a
+    /// file just written into the Playspace. Variables already set are
+    /// overwritten, and like any others are restored on exit. The whole file
+    /// is parsed before any variable is set, so a file with an error doesn't
+    /// leave the environment half-changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DotenvError::Parse`] if the file is not valid dotenv, or
+    /// [`DotenvError::File`] for any error reading it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file(".env", "LOG_LEVEL=debug\nAPP_PORT=8080\n").unwrap();
+    ///     space.set_envs_from_file(".env").unwrap();
+    ///     assert_eq!(std::env::var("APP_PORT").unwrap(), "8080");
+    /// }).unwrap();
+    /// ```
+    pub fn set_envs_from_file(&self, path: impl AsRef<Path>) -> Result<(), DotenvError> {
+        let path = self.playspace_path(path)?;
+        let contents = std::fs::read(&path).map_err(|source| WriteError::io(&path, source))?;
+
+        // Held while parsing too, since `${VAR}` substitutions read the environment
+        let _env = env_lock();
+        let vars = dotenvy::from_read_iter(contents.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|error| DotenvError::Parse {
+                path: path.clone(),
+                source: error,
+            })?;
+        self.set_envs(vars.into_iter().map(|(key, value)| (key, Some(value))));
+        Ok(())
+    }
+
+    /// Write every current environment variable to a dotenv-format file in the
+    /// Playspace, to see what a test is really running with.
+    ///
+    /// The format is the same as for
+    /// [`export_env_snapshot`][Playspace::export_env_snapshot], but the path
+    /// is handled exactly as by [`write_file`][Playspace::write_file]. Values
+    /// without tabs or carriage returns can be read back with
+    /// [`set_envs_from_file`][Playspace::set_envs_from_file].
+    ///
+    /// # Errors
+    ///
+    /// Any error writing the file is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("LOG_LEVEL", Some("debug"))]);
+    ///     space.export_env("env.dump").unwrap();
+    ///     let dump = std::fs::read_to_string("env.dump").unwrap();
+    ///     assert!(dump.contains("LOG_LEVEL=\"debug\"\n"));
+    /// }).unwrap();
+    /// ```
+    pub fn export_env(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        self.write_file(path, env_file_contents())
+    }
+}
+
+/// Error setting environment variables from a dotenv file
+#[cfg_attr(docsrs, doc(cfg(feature = "dotenv")))]
+#[derive(Debug, thiserror::Error)]
+pub enum DotenvError {
+    /// Error reading the file in the Playspace.
+    #[error(transparent)]
+    File(#[from] WriteError),
+    /// The file is not valid dotenv.
+    #[error("failed to parse dotenv file {}", .path.display())]
+    Parse {
+        path: PathBuf,
+        source: dotenvy::Error,
+    },
+}
@@ -0,0 +1,819 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    fmt::Write,
+    io,
+    path::{Path, PathBuf},
+};
+
+use parking_lot::{ReentrantMutex, ReentrantMutexGuard};
+
+use crate::{trace, Playspace};
+
+/// Held for every change to (and listing of) the environment by this crate
+static ENV_LOCK: ReentrantMutex<()> = parking_lot::const_reentrant_mutex(());
+
+/// Name of the directory created by [`Playspace::isolate_runtime_dir`], in the
+/// Playspace root.
+pub const RUNTIME_DIR: &str = "runtime";
+
+/// Name of the directory created by [`Playspace::isolate_tempdir`], in the
+/// Playspace root.
+pub const TEMP_DIR: &str = "tmp";
+
+/// Name of the directory created by [`Playspace::isolate_home`], in the
+/// Playspace root.
+pub const HOME_DIR: &str = "home";
+
+impl Playspace {
+    /// Compare the current environment with the one from before the Playspace
+    /// was entered.
+    ///
+    /// Useful for asserting that the code under test set exactly the
+    /// variables it should have. Variables set when creating the Playspace
+    /// (e.g. with [`with_envs`][Playspace::with_envs]) count as changes too.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::ffi::OsString;
+    /// Playspace::scoped(|space| {
+    ///     std::env::set_var("__PLAYSPACE_EXPORTED", "value");
+    ///
+    ///     let diff = space.env_diff();
+    ///     assert_eq!(diff.added.len(), 1);
+    ///     assert_eq!(diff.added[&OsString::from("__PLAYSPACE_EXPORTED")], "value");
+    ///     assert!(diff.changed.is_empty());
+    ///     assert!(diff.removed.is_empty());
+    /// }).unwrap();
+    /// ```
+    #[must_use]
+    pub fn env_diff(&self) -> EnvDiff {
+        self.saved_environment.diff()
+    }
+
+    /// The current value of an environment variable, if it is set.
+    ///
+    /// Equivalent to [`std::env::var_os`], but holding the
+    /// [environment lock][crate::env_lock].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("LOG_LEVEL", Some("debug"))]);
+    ///     assert_eq!(space.var("LOG_LEVEL").unwrap(), "debug");
+    /// }).unwrap();
+    /// ```
+    #[allow(clippy::unused_self)]
+    #[must_use]
+    pub fn var(&self, key: impl AsRef<OsStr>) -> Option<OsString> {
+        let _env = env_lock();
+        std::env::var_os(key)
+    }
+
+    /// The value an environment variable had when the Playspace was entered,
+    /// which is what it is restored to on exit.
+    ///
+    /// Returns `None` if it was not set, or is not saved at all (see
+    /// [`PlayspaceBuilder::snapshot_only`][crate::PlayspaceBuilder::snapshot_only]).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// std::env::set_var("LOG_LEVEL", "info");
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("LOG_LEVEL", Some("debug"))]);
+    ///     assert_eq!(space.saved_var("LOG_LEVEL").unwrap(), "info");
+    /// }).unwrap();
+    /// ```
+    #[must_use]
+    pub fn saved_var(&self, key: impl AsRef<OsStr>) -> Option<&OsStr> {
+        self.saved_environment.get(key)
+    }
+
+    /// Put an environment variable back to its [saved][Playspace::saved_var]
+    /// value (or unset it, if it wasn't set) straight away, rather than
+    /// waiting until exit.
+    ///
+    /// Variables which are not saved, e.g. [protected][crate::PlayspaceBuilder::protect]
+    /// ones, or any in a Playspace which doesn't
+    /// [change directory][crate::PlayspaceBuilder::chdir], are left alone.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// std::env::remove_var("LOG_LEVEL");
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("LOG_LEVEL", Some("debug"))]);
+    ///     space.restore_var("LOG_LEVEL");
+    ///     assert!(std::env::var("LOG_LEVEL").is_err());
+    /// }).unwrap();
+    /// ```
+    pub fn restore_var(&self, key: impl AsRef<OsStr>) {
+        let key = key.as_ref();
+        if self.changes_directory() && self.saved_environment.filter.tracks(key) {
+            self.set_envs([(key, self.saved_var(key))]);
+        }
+    }
+
+    /// Temporarily set or unset several environment variables.
+    ///
+    /// Takes the same arguments as [`set_envs`][Playspace::set_envs], and
+    /// returns a guard which puts those variables back to their previous
+    /// values when it is dropped. Other variables are left alone, so guards
+    /// can be nested to make short-lived tweaks without manual bookkeeping.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("LOG_LEVEL", Some("info"))]);
+    ///     {
+    ///         let _debug = space.env_scope([("LOG_LEVEL", Some("debug"))]);
+    ///         assert_eq!(std::env::var("LOG_LEVEL").unwrap(), "debug");
+    ///     }
+    ///     assert_eq!(std::env::var("LOG_LEVEL").unwrap(), "info");
+    /// }).unwrap();
+    /// ```
+    #[must_use = "the variables are restored as soon as the guard is dropped"]
+    pub fn env_scope<I, K, V>(&self, vars: I) -> EnvScopeGuard<'_>
+    where
+        I: IntoIterator<Item = (K, Option<V>)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        let mut previous = Vec::new();
+        for (key, value) in vars {
+            let key = key.as_ref();
+            let _env = env_lock();
+            previous.push((key.to_owned(), std::env::var_os(key)));
+            self.set_envs([(key, value)]);
+        }
+
+        EnvScopeGuard {
+            _space: self,
+            previous,
+        }
+    }
+
+    /// Create a private runtime directory in the Playspace and point
+    /// `XDG_RUNTIME_DIR` and `TMPDIR` at it (plus `TMP` and `TEMP` on
+    /// Windows).
+    ///
+    /// Tools that create sockets, lock files, or other temporary files in the
+    /// runtime or temporary directory are then fully contained in the
+    /// Playspace, and cleaned up with it. On Unix, the directory is only
+    /// accessible by the current user (mode `0700`), as the XDG spec requires.
+    /// Returns the path of the directory, which is [`RUNTIME_DIR`] in the
+    /// Playspace root.
+    ///
+    /// N.B. this includes temporary files created by this process, e.g. by
+    /// [`std::env::temp_dir`] users, and [`fixtures`][crate::fixtures] built
+    /// for the first time while the runtime directory is isolated.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let runtime = space.isolate_runtime_dir().unwrap();
+    ///     assert_eq!(std::env::var_os("XDG_RUNTIME_DIR").unwrap(), runtime);
+    ///     assert!(runtime.starts_with(space.directory()));
+    /// }).unwrap();
+    /// ```
+    pub fn isolate_runtime_dir(&self) -> Result<PathBuf, io::Error> {
+        let path = self.directory().join(RUNTIME_DIR);
+        std::fs::create_dir_all(&path)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o700))?;
+        }
+
+        self.set_envs([("XDG_RUNTIME_DIR", Some(&path)), ("TMPDIR", Some(&path))]);
+        #[cfg(windows)]
+        self.set_envs([("TMP", Some(&path)), ("TEMP", Some(&path))]);
+
+        Ok(path)
+    }
+
+    /// Create a temporary directory in the Playspace and point `TMPDIR`,
+    /// `TEMP`, and `TMP` at it.
+    ///
+    /// Temporary files created by the code under test (e.g. through
+    /// [`std::env::temp_dir`] or the `tempfile` crate) are then removed with
+    /// the Playspace, rather than littering the real temporary directory.
+    /// Unlike [`isolate_runtime_dir`][Playspace::isolate_runtime_dir], every
+    /// variable is set on every platform, and `XDG_RUNTIME_DIR` is left
+    /// alone. Returns the path of the directory, which is [`TEMP_DIR`] in the
+    /// Playspace root.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let tmp = space.isolate_tempdir().unwrap();
+    ///     let file = tempfile::NamedTempFile::new().unwrap();
+    ///     assert!(file.path().starts_with(&tmp));
+    /// }).unwrap();
+    /// ```
+    pub fn isolate_tempdir(&self) -> Result<PathBuf, io::Error> {
+        let path = self.directory().join(TEMP_DIR);
+        std::fs::create_dir_all(&path)?;
+        self.set_envs([
+            ("TMPDIR", Some(&path)),
+            ("TEMP", Some(&path)),
+            ("TMP", Some(&path)),
+        ]);
+        Ok(path)
+    }
+
+    /// Create a fake home directory in the Playspace, with the usual XDG
+    /// layout, and point `HOME` and the XDG base directory variables at it.
+    ///
+    /// The directories created (relative to [`HOME_DIR`] in the Playspace
+    /// root) are:
+    ///
+    /// | Directory      | Variable          |
+    /// |----------------|-------------------|
+    /// | `.`            | `HOME`            |
+    /// | `.config`      | `XDG_CONFIG_HOME` |
+    /// | `.cache`       | `XDG_CACHE_HOME`  |
+    /// | `.local/share` | `XDG_DATA_HOME`   |
+    /// | `.local/state` | `XDG_STATE_HOME`  |
+    ///
+    /// On Windows, `USERPROFILE` is also pointed at the home directory, and
+    /// `APPDATA` and `LOCALAPPDATA` at `AppData/Roaming` and `AppData/Local`
+    /// in it. Code that loads configuration from the user's home then never
+    /// sees the real one. Returns the path of the home directory.
+    ///
+    /// N.B. functions which don't read the environment (e.g. looking up the
+    /// home directory in the user database) are not affected.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let home = space.isolate_home().unwrap();
+    ///     assert_eq!(std::env::var_os("HOME").unwrap(), home);
+    ///     assert!(home.join(".config").is_dir());
+    /// }).unwrap();
+    /// ```
+    pub fn isolate_home(&self) -> Result<PathBuf, io::Error> {
+        let home = self.directory().join(HOME_DIR);
+        let mut vars = vec![("HOME", home.clone())];
+        for (variable, relative) in [
+            ("XDG_CONFIG_HOME", ".config"),
+            ("XDG_CACHE_HOME", ".cache"),
+            ("XDG_DATA_HOME", ".local/share"),
+            ("XDG_STATE_HOME", ".local/state"),
+            #[cfg(windows)]
+            ("APPDATA", "AppData/Roaming"),
+            #[cfg(windows)]
+            ("LOCALAPPDATA", "AppData/Local"),
+        ] {
+            let path = home.join(relative);
+            std::fs::create_dir_all(&path)?;
+            vars.push((variable, path));
+        }
+        #[cfg(windows)]
+        vars.push(("USERPROFILE", home.clone()));
+
+        self.set_envs(vars.iter().map(|(variable, path)| (variable, Some(path))));
+        Ok(home)
+    }
+
+    /// Write the current environment to a file in a dotenv-style format.
+    ///
+    /// Every variable is written as `NAME="value"`, one per line, sorted by
+    /// name, with quotes, backslashes, `$` and control characters escaped.
+    /// Any names or values that are not valid unicode are converted lossily.
+    /// The file can be re-applied with
+    /// [`apply_env_snapshot`][Playspace::apply_env_snapshot], e.g. in a later
+    /// run to reproduce the environment of a failing test.
+    ///
+    /// Unlike the file helpers, the path is _not_ restricted to the Playspace,
+    /// since a snapshot is usually meant to outlive it.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error writing the file is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # let snapshot = tempfile::NamedTempFile::new().unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("SOME_VARIABLE", Some("some value"))]);
+    ///     space.export_env_snapshot(snapshot.path()).unwrap();
+    /// }).unwrap();
+    ///
+    /// let contents = std::fs::read_to_string(snapshot.path()).unwrap();
+    /// assert!(contents.contains("SOME_VARIABLE=\"some value\""));
+    /// ```
+    #[allow(clippy::unused_self)]
+    pub fn export_env_snapshot(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        std::fs::write(path, env_file_contents())
+    }
+
+    /// Replace the current environment with one saved by
+    /// [`export_env_snapshot`][Playspace::export_env_snapshot].
+    ///
+    /// Every variable in the file is set, and every variable _not_ in the
+    /// file is removed, so that the environment matches the snapshot exactly.
+    /// As with any other changes inside the Playspace, the previous environment
+    /// is restored on exit.
+    ///
+    /// The file may also be hand-written: empty lines and lines starting
+    /// with `#` are ignored, an `export ` prefix is allowed, and values may be
+    /// double-quoted (with escapes), single-quoted (verbatim), or unquoted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::InvalidData`] error if the file could not
+    /// be parsed, in which case the environment is left unchanged. Any other
+    /// stardard IO error reading the file is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # let snapshot = tempfile::NamedTempFile::new().unwrap();
+    /// # std::fs::write(snapshot.path(), "ONLY_VARIABLE=\"value\"\n").unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.apply_env_snapshot(snapshot.path()).unwrap();
+    ///     assert_eq!(std::env::vars().count(), 1);
+    ///     assert_eq!(std::env::var("ONLY_VARIABLE").unwrap(), "value");
+    /// }).unwrap();
+    /// ```
+    pub fn apply_env_snapshot(&self, path: impl AsRef<Path>) -> Result<(), io::Error> {
+        let contents = std::fs::read_to_string(path)?;
+        let variables = parse(&contents)?;
+
+        let _env = env_lock();
+        for (key, _value) in std::env::vars_os() {
+            if !key.to_str().is_some_and(|key| variables.contains_key(key)) {
+                remove_var(key);
+            }
+        }
+        self.set_envs(variables.into_iter().map(|(key, value)| (key, Some(value))));
+
+        Ok(())
+    }
+
+    /// Remove every environment variable, for hermetic tests of code which
+    /// reads the environment.
+    ///
+    /// As with any other changes inside the Playspace, the previous
+    /// environment is restored on exit. Only variables which are restored are
+    /// removed, so any outside [`snapshot_only`][crate::PlayspaceBuilder::snapshot_only]
+    /// or [protected][crate::PlayspaceBuilder::protect] ones are kept.
+    ///
+    /// Most programs need a few variables (e.g. `PATH` to find executables,
+    /// or `SystemRoot` on Windows) to run at all, so use
+    /// [`clear_env_except`][Playspace::clear_env_except] to keep them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.clear_env();
+    ///     assert_eq!(std::env::vars_os().count(), 0);
+    /// }).unwrap();
+    /// ```
+    pub fn clear_env(&self) {
+        self.clear_env_except(std::iter::empty::<&OsStr>());
+    }
+
+    /// Remove every environment variable except those given.
+    ///
+    /// Just like [`clear_env`][Playspace::clear_env], but keeps the given
+    /// variables if they are set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.clear_env_except(["PATH", "HOME", "SystemRoot"]);
+    ///     assert!(std::env::vars_os().all(|(key, _value)| ["PATH", "HOME", "SystemRoot"].map(std::ffi::OsString::from).contains(&key)));
+    /// }).unwrap();
+    /// ```
+    pub fn clear_env_except<I, K>(&self, keep: I)
+    where
+        I: IntoIterator<Item = K>,
+        K: AsRef<OsStr>,
+    {
+        let keep: Vec<OsString> = keep.into_iter().map(|key| env_key(key.as_ref())).collect();
+
+        let _env = env_lock();
+        let removed: Vec<OsString> = std::env::vars_os()
+            .map(|(key, _value)| key)
+            .filter(|key| {
+                self.saved_environment.filter.tracks(key) && !keep.contains(&env_key(key))
+            })
+            .collect();
+        self.set_envs(removed.into_iter().map(|key| (key, None::<OsString>)));
+    }
+}
+
+/// A saved copy of the process environment.
+///
+/// This is what a Playspace uses to restore the environment when it exits,
+/// and is useful on its own where a full Playspace would be overkill, e.g. in
+/// a test that only touches a single variable.
+///
+/// N.B. unlike a Playspace, an `EnvSnapshot` takes no lock, so nothing stops
+/// other threads from changing the environment at the same time.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::EnvSnapshot;
+/// let snapshot = EnvSnapshot::capture();
+/// std::env::set_var("__PLAYSPACE_SNAPSHOT_EXAMPLE", "value");
+/// assert_eq!(snapshot.diff().added.len(), 1);
+///
+/// assert!(snapshot.restore().is_empty());
+/// assert!(std::env::var("__PLAYSPACE_SNAPSHOT_EXAMPLE").is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvSnapshot {
+    /// Keyed by [`env_key`], with each variable's name as it was set. Ordered,
+    /// so that it is always restored in the same order.
+    variables: BTreeMap<OsString, (OsString, OsString)>,
+    pub(crate) filter: EnvFilter,
+}
+
+impl EnvSnapshot {
+    /// Save the current environment.
+    #[must_use]
+    pub fn capture() -> Self {
+        Self::capture_filtered(EnvFilter::default())
+    }
+
+    /// Save only the variables tracked by `filter`, which are then the only
+    /// ones restored or compared
+    pub(crate) fn capture_filtered(filter: EnvFilter) -> Self {
+        let _env = env_lock();
+        Self {
+            variables: std::env::vars_os()
+                .filter(|(key, _value)| filter.tracks(key))
+                .map(|(key, value)| (env_key(&key), (key, value)))
+                .collect(),
+            filter,
+        }
+    }
+
+    /// Forget every variable not tracked by `filter`, as if only those had
+    /// been captured
+    pub(crate) fn narrow(&mut self, filter: EnvFilter) {
+        self.variables
+            .retain(|_lookup, (key, _value)| filter.tracks(key));
+        self.filter = filter;
+    }
+
+    /// The saved value of a variable, if it was set.
+    #[must_use]
+    pub fn get(&self, key: impl AsRef<OsStr>) -> Option<&OsStr> {
+        self.variables
+            .get(&env_key(key.as_ref()))
+            .map(|(_key, value)| value.as_os_str())
+    }
+
+    /// Make the current environment match the snapshot exactly.
+    ///
+    /// Variables that were not set when the snapshot was taken are removed,
+    /// and all others are set back to their saved values. The snapshot can be
+    /// restored any number of times.
+    ///
+    /// Changes are made in a fixed order, so that anything watching them
+    /// (e.g. with the `tracing` feature) sees the same sequence every time:
+    /// first the removals, then the rest, each sorted by name.
+    ///
+    /// On Windows, where variable names are case-insensitive, a variable
+    /// whose name has only changed case (e.g. `Path` to `PATH`) is the same
+    /// variable, and is restored with its saved name.
+    ///
+    /// Returns whatever still differs from the snapshot afterwards, compared
+    /// without releasing the [environment lock][env_lock], so this is empty
+    /// unless the environment was changed at the same time by code which
+    /// doesn't take it.
+    #[allow(clippy::must_use_candidate)]
+    pub fn restore(&self) -> EnvDiff {
+        let _env = env_lock();
+        let current: BTreeMap<_, _> = std::env::vars_os()
+            .filter(|(key, _value)| self.filter.tracks(key))
+            .map(|(key, value)| (env_key(&key), (key, value)))
+            .collect();
+
+        for (lookup, (key, _value)) in &current {
+            if !self.variables.contains_key(lookup) {
+                trace::event!(tracing::Level::DEBUG, key = ?key, set = false, "restore_env");
+                remove_var(key);
+            }
+        }
+        for (lookup, saved) in &self.variables {
+            let (key, value) = saved;
+            match current.get(lookup) {
+                Some(current) if current == saved => continue,
+                Some((current_key, _value)) if current_key != key => {
+                    // Only removing it first restores the case of the name
+                    remove_var(current_key);
+                }
+                _ => (),
+            }
+            trace::event!(tracing::Level::DEBUG, key = ?key, set = true, "restore_env");
+            set_var(key, value);
+        }
+        self.diff()
+    }
+
+    /// Compare the current environment with the snapshot.
+    #[must_use]
+    pub fn diff(&self) -> EnvDiff {
+        let _env = env_lock();
+        let mut diff = EnvDiff::default();
+        for (key, value) in std::env::vars_os().filter(|(key, _value)| self.filter.tracks(key)) {
+            match self.variables.get(&env_key(&key)) {
+                None => {
+                    diff.added.insert(key, value);
+                }
+                Some((_key, saved)) if *saved != value => {
+                    diff.changed.insert(key, (saved.clone(), value));
+                }
+                Some(_) => (),
+            }
+        }
+        for (key, value) in self.variables.values() {
+            if std::env::var_os(key).is_none() {
+                diff.removed.insert(key.clone(), value.clone());
+            }
+        }
+        diff
+    }
+}
+
+/// Which environment variables are saved and restored by a Playspace, see
+/// [`PlayspaceBuilder::snapshot_only`][crate::PlayspaceBuilder::snapshot_only]
+/// and [`PlayspaceBuilder::protect`][crate::PlayspaceBuilder::protect]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct EnvFilter {
+    /// If any, only variables starting with one of these are tracked
+    pub(crate) prefixes: Option<Vec<OsString>>,
+    /// Never tracked
+    pub(crate) protected: Vec<OsString>,
+}
+
+impl EnvFilter {
+    pub(crate) fn tracks(&self, key: &OsStr) -> bool {
+        let key = env_key(key);
+        let included = self.prefixes.as_ref().is_none_or(|prefixes| {
+            prefixes.iter().any(|prefix| {
+                key.as_encoded_bytes()
+                    .starts_with(env_key(prefix).as_encoded_bytes())
+            })
+        });
+        included
+            && !self
+                .protected
+                .iter()
+                .any(|protected| env_key(protected) == key)
+    }
+}
+
+/// The name a variable is looked up by, which is folded to upper case on
+/// Windows where names are case-insensitive
+pub(crate) fn env_key(key: &OsStr) -> OsString {
+    if cfg!(windows) {
+        key.to_ascii_uppercase()
+    } else {
+        key.to_owned()
+    }
+}
+
+/// Restores environment variables changed by [`Playspace::env_scope`] when
+/// dropped.
+pub struct EnvScopeGuard<'a> {
+    _space: &'a Playspace,
+    previous: Vec<(OsString, Option<OsString>)>,
+}
+
+impl Drop for EnvScopeGuard<'_> {
+    fn drop(&mut self) {
+        let _env = env_lock();
+        // In reverse, in case the same variable was given more than once
+        for (key, value) in self.previous.drain(..).rev() {
+            match value {
+                Some(value) => set_var(key, value),
+                None => remove_var(key),
+            }
+        }
+    }
+}
+
+/// Lock the environment against changes by this crate.
+///
+/// Every change to (and listing of) environment variables made by this
+/// crate, e.g. [`Playspace::set_envs`] or restoring the environment on exit,
+/// is made while holding this lock. Code which reads or writes environment
+/// variables itself can hold it too, so that it never sees the environment
+/// half-changed by another thread, and in particular never calls
+/// [`std::env::set_var`] while another thread does (which is `unsafe` in
+/// Rust 2024, since it is undefined behaviour on some platforms).
+///
+/// The lock is re-entrant, so the crate's own functions can still be called
+/// by the thread holding it. It is not related to the lock held by a
+/// Playspace: holding it does not keep other threads from entering one.
+///
+/// # Blocks
+///
+/// Blocks while another thread holds the lock.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::Playspace;
+/// let space = Playspace::new().unwrap();
+/// {
+///     let _env = playspace::env_lock();
+///     // No other thread can change the environment here, via this crate
+///     let path = std::env::var_os("PATH");
+///     space.set_envs([("SAVED_PATH", path)]);
+/// }
+/// # space.exit().unwrap();
+/// ```
+pub fn env_lock() -> EnvLockGuard {
+    EnvLockGuard {
+        _guard: ENV_LOCK.lock(),
+    }
+}
+
+/// Holds the environment lock until dropped, see [`env_lock`].
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct EnvLockGuard {
+    _guard: ReentrantMutexGuard<'static, ()>,
+}
+
+impl std::fmt::Debug for EnvLockGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EnvLockGuard").finish_non_exhaustive()
+    }
+}
+
+/// Set an environment variable, holding the environment lock.
+///
+/// All changes to the environment by this crate go through here and
+/// [`remove_var`], so this is the only place they need to be made safe.
+pub(crate) fn set_var(key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) {
+    let _env = env_lock();
+    std::env::set_var(key, value);
+}
+
+/// Remove an environment variable, holding the environment lock.
+pub(crate) fn remove_var(key: impl AsRef<OsStr>) {
+    let _env = env_lock();
+    std::env::remove_var(key);
+}
+
+/// Differences between a saved environment and the current one.
+///
+/// Returned by [`Playspace::env_diff`] and [`EnvSnapshot::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvDiff {
+    /// Variables that have been set, with their current values.
+    pub added: BTreeMap<OsString, OsString>,
+    /// Variables whose values changed, as `(before, after)`.
+    pub changed: BTreeMap<OsString, (OsString, OsString)>,
+    /// Variables that have been removed, with their previous values.
+    pub removed: BTreeMap<OsString, OsString>,
+}
+
+impl EnvDiff {
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// The current environment in the format written by
+/// [`Playspace::export_env_snapshot`]
+pub(crate) fn env_file_contents() -> String {
+    let _env = env_lock();
+    let variables: BTreeMap<_, _> = std::env::vars_os()
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().into_owned(),
+                value.to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+
+    let mut contents = String::new();
+    for (key, value) in variables {
+        let _infallible = writeln!(contents, "{key}=\"{}\"", escape(&value));
+    }
+    contents
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '$' => escaped.push_str("\\$"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn parse(contents: &str) -> Result<BTreeMap<String, String>, io::Error> {
+    let mut variables = BTreeMap::new();
+    for (index, line) in contents.lines().enumerate() {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid environment snapshot line {}: {reason}", index + 1),
+            )
+        };
+
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line.split_once('=').ok_or_else(|| invalid("missing `=`"))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(invalid("empty variable name"));
+        }
+
+        let value = value.trim();
+        let value = if let Some(quoted) = value.strip_prefix('"') {
+            unescape(quoted).ok_or_else(|| invalid("unterminated or invalid double quotes"))?
+        } else if let Some(quoted) = value.strip_prefix('\'') {
+            quoted
+                .strip_suffix('\'')
+                .ok_or_else(|| invalid("unterminated single quotes"))?
+                .to_owned()
+        } else {
+            value.to_owned()
+        };
+
+        variables.insert(key.to_owned(), value);
+    }
+    Ok(variables)
+}
+
+/// Unescape the remainder of a double-quoted value, which must end with the
+/// closing quote
+fn unescape(quoted: &str) -> Option<String> {
+    let mut value = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => return chars.as_str().is_empty().then_some(value),
+            '\\' => value.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                c @ ('\\' | '"' | '$' | '\'') => c,
+                _ => return None,
+            }),
+            c => value.push(c),
+        }
+    }
+    None
+}
@@ -0,0 +1,131 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::ffi::OsString;
+
+use crate::Playspace;
+
+/// Variables commonly used to detect running in CI, see
+/// [`EnvPreset::clean_ci`]
+const CI_VARS: &[&str] = &[
+    "CI",
+    "CONTINUOUS_INTEGRATION",
+    "BUILD_ID",
+    "BUILD_NUMBER",
+    "RUN_ID",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "TRAVIS",
+    "CIRCLECI",
+    "APPVEYOR",
+    "BUILDKITE",
+    "DRONE",
+    "JENKINS_URL",
+    "TEAMCITY_VERSION",
+    "TF_BUILD",
+    "CODEBUILD_BUILD_ID",
+];
+
+/// Proxy variables, in both the upper and lower case forms in use, see
+/// [`EnvPreset::no_proxy`]
+const PROXY_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "http_proxy",
+    "HTTPS_PROXY",
+    "https_proxy",
+    "FTP_PROXY",
+    "ftp_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "NO_PROXY",
+    "no_proxy",
+];
+
+/// A reusable set of environment changes, applied with
+/// [`Playspace::apply`] or [`PlayspaceBuilder::preset`][crate::PlayspaceBuilder::preset].
+///
+/// Presets compose with [`and`][EnvPreset::and], and later changes take
+/// precedence over earlier ones.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::{EnvPreset, Playspace};
+/// let hermetic = EnvPreset::clean_ci()
+///     .and(EnvPreset::no_proxy())
+///     .and(EnvPreset::posix_locale())
+///     .set("APP_MODE", "test");
+///
+/// Playspace::scoped(|space| {
+///     space.apply(&hermetic);
+///     assert_eq!(std::env::var("LC_ALL").unwrap(), "C");
+///     assert!(std::env::var_os("HTTPS_PROXY").is_none());
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct EnvPreset {
+    vars: Vec<(OsString, Option<OsString>)>,
+}
+
+impl EnvPreset {
+    /// A preset which changes nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Unset the variables CI systems set to identify themselves (e.g. `CI`,
+    /// `GITHUB_ACTIONS`, `GITLAB_CI`), so that code which behaves differently
+    /// in CI behaves the same as it does locally.
+    pub fn clean_ci() -> Self {
+        CI_VARS.iter().fold(Self::new(), Self::unset)
+    }
+
+    /// Unset every proxy variable (e.g. `HTTPS_PROXY`, `no_proxy`), so that
+    /// network access is never routed through a proxy.
+    pub fn no_proxy() -> Self {
+        PROXY_VARS.iter().fold(Self::new(), Self::unset)
+    }
+
+    /// Use the POSIX ("C") locale, so that messages, sorting, and number
+    /// formatting don't depend on the user's language settings.
+    pub fn posix_locale() -> Self {
+        Self::new()
+            .set("LC_ALL", "C")
+            .set("LANG", "C")
+            .unset("LANGUAGE")
+    }
+
+    /// Set a variable.
+    pub fn set(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.vars.push((key.into(), Some(value.into())));
+        self
+    }
+
+    /// Unset a variable.
+    pub fn unset(mut self, key: impl Into<OsString>) -> Self {
+        self.vars.push((key.into(), None));
+        self
+    }
+
+    /// Follow these changes with those in `other`.
+    pub fn and(mut self, other: EnvPreset) -> Self {
+        self.vars.extend(other.vars);
+        self
+    }
+
+    /// Every change in the preset, in order, as for
+    /// [`set_envs`][Playspace::set_envs].
+    pub fn vars(&self) -> impl Iterator<Item = (&OsString, Option<&OsString>)> {
+        self.vars.iter().map(|(key, value)| (key, value.as_ref()))
+    }
+}
+
+impl Playspace {
+    /// Apply every change in a preset to the environment.
+    ///
+    /// See [`EnvPreset`].
+    pub fn apply(&self, preset: &EnvPreset) {
+        self.set_envs(preset.vars());
+    }
+}
@@ -0,0 +1,171 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::ffi::{OsStr, OsString};
+
+use crate::Playspace;
+
+/// Expected state of the environment, for
+/// [`assert_env_matches`][Playspace::assert_env_matches].
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::EnvSpec;
+/// let spec = EnvSpec::new()
+///     .require("APP_MODE", "production")
+///     .require_matching("APP_URL", "https://*.example.com")
+///     .require_set("APP_TOKEN")
+///     .forbid("APP_DEBUG");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct EnvSpec {
+    rules: Vec<(OsString, Rule)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Rule {
+    Set,
+    Exact(OsString),
+    Pattern(String),
+    Unset,
+}
+
+impl EnvSpec {
+    /// An empty specification, which any environment matches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Require a variable to be set to exactly `value`.
+    pub fn require(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.rules.push((key.into(), Rule::Exact(value.into())));
+        self
+    }
+
+    /// Require a variable to be set to a value matching `pattern`, in which
+    /// `*` matches any sequence of characters (including none) and everything
+    /// else must match exactly.
+    pub fn require_matching(
+        mut self,
+        key: impl Into<OsString>,
+        pattern: impl Into<String>,
+    ) -> Self {
+        self.rules.push((key.into(), Rule::Pattern(pattern.into())));
+        self
+    }
+
+    /// Require a variable to be set, to any value.
+    pub fn require_set(mut self, key: impl Into<OsString>) -> Self {
+        self.rules.push((key.into(), Rule::Set));
+        self
+    }
+
+    /// Require a variable not to be set.
+    pub fn forbid(mut self, key: impl Into<OsString>) -> Self {
+        self.rules.push((key.into(), Rule::Unset));
+        self
+    }
+
+    /// Compare the current environment with the specification.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of every mismatch, one per line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{EnvSpec, Playspace};
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("APP_MODE", Some("debug"))]);
+    ///     let spec = EnvSpec::new().require("APP_MODE", "production");
+    ///     assert!(spec.check().is_err());
+    /// }).unwrap();
+    /// ```
+    pub fn check(&self) -> Result<(), String> {
+        let mismatches: Vec<String> = self
+            .rules
+            .iter()
+            .filter_map(|(key, rule)| mismatch(key, rule, std::env::var_os(key).as_deref()))
+            .collect();
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(mismatches.join("\n"))
+        }
+    }
+}
+
+fn mismatch(key: &OsStr, rule: &Rule, value: Option<&OsStr>) -> Option<String> {
+    let key = key.to_string_lossy();
+    let Some(value) = value else {
+        return (*rule != Rule::Unset).then(|| format!("{key} should be set, but is not"));
+    };
+
+    let shown = value.to_string_lossy();
+    match rule {
+        Rule::Set => None,
+        Rule::Unset => Some(format!("{key} should not be set, but is {shown:?}")),
+        Rule::Exact(expected) => (expected != value).then(|| {
+            let expected = expected.to_string_lossy();
+            format!("{key} should be {expected:?}, but is {shown:?}")
+        }),
+        Rule::Pattern(pattern) => (!matches(pattern, &shown))
+            .then(|| format!("{key} should match {pattern:?}, but is {shown:?}")),
+    }
+}
+
+/// Whether `value` matches `pattern`, where `*` is a wildcard
+pub(crate) fn matches(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    // There is always a first part, possibly empty
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = value.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No wildcards at all
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+impl Playspace {
+    /// Assert that the current environment matches a specification,
+    /// reporting every mismatch at once.
+    ///
+    /// # Panics
+    ///
+    /// If the environment doesn't match the specification, with a
+    /// description of every mismatch.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{EnvSpec, Playspace};
+    /// Playspace::scoped(|space| {
+    ///     space.set_envs([("APP_MODE", Some("production")), ("APP_DEBUG", None)]);
+    ///     space.assert_env_matches(
+    ///         &EnvSpec::new()
+    ///             .require("APP_MODE", "production")
+    ///             .forbid("APP_DEBUG"),
+    ///     );
+    /// }).unwrap();
+    /// ```
+    #[track_caller]
+    pub fn assert_env_matches(&self, spec: &EnvSpec) {
+        if let Err(mismatches) = spec.check() {
+            panic!("environment does not match the specification:\n{mismatches}");
+        }
+    }
+}
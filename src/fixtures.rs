@@ -0,0 +1,223 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Read-only fixtures shared between Playspaces.
+//!
+//! Some fixtures are expensive to build (a large corpus of files, a cloned
+//! repository, a populated database, ...). Rather than building them afresh in
+//! every Playspace, [`get`] builds each named fixture at most once per process,
+//! in its own temporary directory outside of any Playspace. Its contents are
+//! then made read-only, and can be linked into any Playspace with
+//! [`Playspace::link_fixture`].
+//!
+//! Fixture directories live for the rest of the process and are not removed
+//! when it exits. Neither building nor using a fixture takes the Playspace
+//! lock, so tests which only need to read a fixture can use it from outside
+//! of any Playspace, all at once. A [`SharedFixture`] can be declared as a
+//! `static` next to the tests which share it.
+//!
+//! # Example
+//!
+//! ```rust
+//! # use playspace::{fixtures, Playspace};
+//! Playspace::scoped(|space| {
+//!     let corpus = fixtures::get("big-corpus", |root| {
+//!         for i in 0..100 {
+//!             std::fs::write(root.join(format!("{i}.txt")), "lorem ipsum")?;
+//!         }
+//!         Ok(())
+//!     }).unwrap();
+//!
+//!     space.link_fixture(&corpus, "corpus").unwrap();
+//!     assert!(std::path::Path::new("corpus/42.txt").is_file());
+//! }).unwrap();
+//! ```
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+use tempfile::TempDir;
+
+use crate::{walk::walk, Playspace, WriteError};
+
+static REGISTRY: Mutex<Option<HashMap<String, Arc<Slot>>>> = parking_lot::const_mutex(None);
+
+/// The materialized directory for a fixture, or `None` until it has been
+/// successfully built
+type Slot = Mutex<Option<Arc<TempDir>>>;
+
+/// Handle to a built, read-only fixture directory.
+///
+/// Returned by [`get`].
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    name: Arc<str>,
+    directory: Arc<TempDir>,
+}
+
+impl Fixture {
+    /// The name the fixture was registered with.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Path to the fixture directory.
+    ///
+    /// This is outside of any Playspace, and its contents are read-only.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.directory.path()
+    }
+}
+
+/// Get the fixture with the given name, building it if this is the first time
+/// it has been requested in this process.
+///
+/// `build` is given an empty directory to populate. It is only called if the
+/// fixture has not already been built; concurrent calls for the same name
+/// block until the first has finished building. Once built, every file and
+/// directory in the fixture is made read-only.
+///
+/// `build` runs in whatever the current directory happens to be, so it should
+/// only ever write through the path it is given.
+///
+/// # Errors
+///
+/// Any error returned by `build` is bubbled-up, in which case the fixture is
+/// not registered and the next call will try to build it again. Any other
+/// stardard IO error is also bubbled-up.
+pub fn get<F>(name: &str, build: F) -> Result<Fixture, io::Error>
+where
+    F: FnOnce(&Path) -> Result<(), io::Error>,
+{
+    let slot = {
+        let mut registry = REGISTRY.lock();
+        let registry = registry.get_or_insert_with(HashMap::new);
+        Arc::clone(registry.entry(name.to_owned()).or_default())
+    };
+
+    // Only this fixture is locked while it builds, so other fixtures can be
+    // fetched in the meantime
+    let mut slot = slot.lock();
+    let directory = if let Some(directory) = slot.as_ref() {
+        Arc::clone(directory)
+    } else {
+        let directory = tempfile::Builder::new()
+            .prefix("playspace-fixture-")
+            .tempdir()?;
+        build(directory.path())?;
+        make_read_only(directory.path())?;
+        Arc::clone(slot.insert(Arc::new(directory)))
+    };
+
+    Ok(Fixture {
+        name: name.into(),
+        directory,
+    })
+}
+
+/// A fixture declared once, e.g. as a `static`, and built the first time it
+/// is used in this process.
+///
+/// Just a name and a build function for [`get`], so that every test uses the
+/// same ones.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::fixtures::SharedFixture;
+/// static PROJECT: SharedFixture = SharedFixture::new("compiled-project", |root| {
+///     std::fs::write(root.join("Cargo.toml"), "[package]")
+/// });
+///
+/// // No Playspace needed just to read it
+/// let project = PROJECT.get().unwrap();
+/// assert!(project.path().join("Cargo.toml").is_file());
+/// assert_eq!(PROJECT.get().unwrap().path(), project.path());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SharedFixture {
+    name: &'static str,
+    build: fn(&Path) -> Result<(), io::Error>,
+}
+
+impl SharedFixture {
+    /// Declare a fixture named `name`, which is populated by `build` as for
+    /// [`get`].
+    #[must_use]
+    pub const fn new(name: &'static str, build: fn(&Path) -> Result<(), io::Error>) -> Self {
+        Self { name, build }
+    }
+
+    /// The fixture, built if this is the first time it has been requested.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`get`].
+    pub fn get(&self) -> Result<Fixture, io::Error> {
+        get(self.name, self.build)
+    }
+}
+
+fn make_read_only(root: &Path) -> Result<(), io::Error> {
+    // Children first, so that directories are still writable while their
+    // contents are being changed
+    let mut paths: Vec<PathBuf> = walk(root)?
+        .into_iter()
+        .map(|path| root.join(path))
+        .collect();
+    paths.reverse();
+    paths.push(root.to_owned());
+
+    for path in paths {
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if metadata.file_type().is_symlink() {
+            continue;
+        }
+        let mut permissions = metadata.permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&path, permissions)?;
+    }
+    Ok(())
+}
+
+impl Playspace {
+    /// Link a shared [`Fixture`] into the Playspace.
+    ///
+    /// A symlink to the fixture directory is created at `path`, along with any
+    /// missing parent directories. The path is checked to be inside the
+    /// Playspace in the same way as [`write_file`][Playspace::write_file].
+    /// Since fixtures are read-only, tests that need to modify the contents
+    /// should copy them instead.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// See the [`fixtures`][crate::fixtures] module.
+    pub fn link_fixture(
+        &self,
+        fixture: &Fixture,
+        path: impl AsRef<Path>,
+    ) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| WriteError::io(parent, source))?;
+        }
+
+        #[cfg(unix)]
+        let linked = std::os::unix::fs::symlink(fixture.path(), &path);
+        #[cfg(windows)]
+        let linked = std::os::windows::fs::symlink_dir(fixture.path(), &path);
+        linked.map_err(|source| WriteError::io(&path, source))
+    }
+}
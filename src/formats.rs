@@ -0,0 +1,224 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::Path;
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{Playspace, WriteError};
+
+impl Playspace {
+    /// Serialize a value as (pretty-printed) JSON into a file in the
+    /// Playspace.
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Json`] if the value could not be serialized, or
+    /// [`FormatError::File`] for any error writing the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     let config = BTreeMap::from([("verbose", true), ("color", false)]);
+    ///     space.write_json("config.json", &config).unwrap();
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "serde-json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde-json")))]
+    pub fn write_json<T>(&self, path: impl AsRef<Path>, value: &T) -> Result<(), FormatError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let contents = serde_json::to_string_pretty(value)?;
+        Ok(self.write_file(path, contents)?)
+    }
+
+    /// Deserialize a value from a JSON file in the Playspace.
+    ///
+    /// Relative paths are _always_ evaluated with respect to the Playspace
+    /// root directory, and the path is checked to be inside the Playspace,
+    /// just as for [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Json`] if the contents could not be
+    /// deserialized, or [`FormatError::File`] for any error reading the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("out.json", r#"{"status": "ok"}"#).unwrap();
+    ///     let output: BTreeMap<String, String> = space.read_json("out.json").unwrap();
+    ///     assert_eq!(output["status"], "ok");
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "serde-json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde-json")))]
+    pub fn read_json<T>(&self, path: impl AsRef<Path>) -> Result<T, FormatError>
+    where
+        T: DeserializeOwned,
+    {
+        let contents = self.read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Serialize a value as TOML into a file in the Playspace.
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::TomlSerialize`] if the value could not be
+    /// serialized, or [`FormatError::File`] for any error writing the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     let table = BTreeMap::from([("option1", 1), ("option2", 2)]);
+    ///     let config = BTreeMap::from([("table", table)]);
+    ///     space.write_toml("config.toml", &config).unwrap();
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn write_toml<T>(&self, path: impl AsRef<Path>, value: &T) -> Result<(), FormatError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let contents = toml::to_string_pretty(value)?;
+        Ok(self.write_file(path, contents)?)
+    }
+
+    /// Deserialize a value from a TOML file in the Playspace.
+    ///
+    /// The path is handled exactly as by [`read_json`][Playspace::read_json].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::TomlDeserialize`] if the contents could not be
+    /// deserialized, or [`FormatError::File`] for any error reading the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("out.toml", "count = 3").unwrap();
+    ///     let output: BTreeMap<String, i64> = space.read_toml("out.toml").unwrap();
+    ///     assert_eq!(output["count"], 3);
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "toml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "toml")))]
+    pub fn read_toml<T>(&self, path: impl AsRef<Path>) -> Result<T, FormatError>
+    where
+        T: DeserializeOwned,
+    {
+        let contents = self.read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Serialize a value as YAML into a file in the Playspace.
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Yaml`] if the value could not be serialized, or
+    /// [`FormatError::File`] for any error writing the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     let config = BTreeMap::from([("retries", 3), ("timeout", 10)]);
+    ///     space.write_yaml("config.yaml", &config).unwrap();
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn write_yaml<T>(&self, path: impl AsRef<Path>, value: &T) -> Result<(), FormatError>
+    where
+        T: Serialize + ?Sized,
+    {
+        let contents = serde_yaml::to_string(value)?;
+        Ok(self.write_file(path, contents)?)
+    }
+
+    /// Deserialize a value from a YAML file in the Playspace.
+    ///
+    /// The path is handled exactly as by [`read_json`][Playspace::read_json].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FormatError::Yaml`] if the contents could not be
+    /// deserialized, or [`FormatError::File`] for any error reading the file.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::collections::BTreeMap;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("out.yaml", "retries: 3").unwrap();
+    ///     let output: BTreeMap<String, i64> = space.read_yaml("out.yaml").unwrap();
+    ///     assert_eq!(output["retries"], 3);
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "yaml")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "yaml")))]
+    pub fn read_yaml<T>(&self, path: impl AsRef<Path>) -> Result<T, FormatError>
+    where
+        T: DeserializeOwned,
+    {
+        let contents = self.read_to_string(path)?;
+        Ok(serde_yaml::from_str(&contents)?)
+    }
+
+    fn read_to_string(&self, path: impl AsRef<Path>) -> Result<String, WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::read_to_string(&path).map_err(|source| WriteError::io(&path, source))
+    }
+}
+
+/// Error reading or writing structured data in a file in a Playspace
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "serde-json", feature = "toml", feature = "yaml")))
+)]
+#[derive(Debug, thiserror::Error)]
+pub enum FormatError {
+    /// Error reading or writing the file in the Playspace.
+    #[error(transparent)]
+    File(#[from] WriteError),
+    /// A bubbled-up error from [`serde_json`].
+    #[cfg(feature = "serde-json")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// A bubbled-up serialization error from [`toml`].
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlSerialize(#[from] toml::ser::Error),
+    /// A bubbled-up deserialization error from [`toml`].
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlDeserialize(#[from] toml::de::Error),
+    /// A bubbled-up error from [`serde_yaml`].
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+}
@@ -0,0 +1,65 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::PathBuf;
+
+use glob::{MatchOptions, Pattern};
+
+use crate::{walk::walk, Playspace};
+
+#[cfg_attr(docsrs, doc(cfg(feature = "glob")))]
+impl Playspace {
+    /// Find every path in the Playspace matching a glob pattern.
+    ///
+    /// The pattern is matched against paths relative to the Playspace root,
+    /// and the matching paths are returned relative to it too, in sorted
+    /// order. `*` and `?` don't match path separators, while `**` matches
+    /// any number of directories. See [`glob::Pattern`] for the full syntax.
+    /// Both files and directories are matched, and symlinks are not followed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`GlobError::Pattern`] if the pattern is invalid, or
+    /// [`GlobError::Io`] for any error walking the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([
+    ///         ("out/a.toml", ""),
+    ///         ("out/nested/b.toml", ""),
+    ///         ("out/c.json", ""),
+    ///     ]).unwrap();
+    ///
+    ///     assert_eq!(space.glob("out/**/*.toml").unwrap().len(), 2);
+    ///     assert_eq!(space.glob("out/*.toml").unwrap().len(), 1);
+    /// }).unwrap();
+    /// ```
+    pub fn glob(&self, pattern: &str) -> Result<Vec<PathBuf>, GlobError> {
+        let pattern = Pattern::new(pattern)?;
+        let options = MatchOptions {
+            case_sensitive: true,
+            require_literal_separator: true,
+            require_literal_leading_dot: false,
+        };
+
+        Ok(walk(self.directory())?
+            .into_iter()
+            .filter(|path| pattern.matches_path_with(path, options))
+            .collect())
+    }
+}
+
+/// Error matching a glob pattern in a Playspace
+#[cfg_attr(docsrs, doc(cfg(feature = "glob")))]
+#[derive(Debug, thiserror::Error)]
+pub enum GlobError {
+    /// The pattern was invalid.
+    #[error(transparent)]
+    Pattern(#[from] glob::PatternError),
+    /// A bubbled-up error from [`std::io`] functions.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
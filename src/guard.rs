@@ -0,0 +1,206 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::mpsc,
+};
+
+use parking_lot::Mutex;
+
+use crate::{ExitError, Playspace, SpaceError};
+
+/// Sends abandoned Playspaces to the reaper thread, started on first use
+static REAPER: Mutex<Option<mpsc::Sender<Playspace>>> = parking_lot::const_mutex(None);
+/// Errors exiting abandoned Playspaces, waiting to be collected
+static DEFERRED_ERRORS: Mutex<Vec<ExitError>> = parking_lot::const_mutex(Vec::new());
+
+/// Exit a Playspace in the background, on the reaper thread.
+///
+/// Any error exiting it is kept, to be collected with
+/// [`AsyncPlayspaceGuard::deferred_errors`].
+pub(crate) fn reap(space: Playspace) {
+    let mut reaper = REAPER.lock();
+    let sender = reaper.get_or_insert_with(|| {
+        let (sender, receiver) = mpsc::channel::<Playspace>();
+        // If the thread can't be spawned then the receiver is dropped, so
+        // sending fails and the space is exited on this thread instead
+        let _spawned = std::thread::Builder::new()
+            .name("playspace-reaper".to_owned())
+            .spawn(move || {
+                for space in receiver {
+                    if let Err(error) = space.exit() {
+                        DEFERRED_ERRORS.lock().push(error);
+                    }
+                }
+            });
+        sender
+    });
+
+    if let Err(mpsc::SendError(space)) = sender.send(space) {
+        // The reaper thread is gone, so exit here rather than lose the space
+        *reaper = None;
+        drop(reaper);
+        if let Err(error) = space.exit() {
+            DEFERRED_ERRORS.lock().push(error);
+        }
+    }
+}
+
+/// A Playspace for async code which never blocks when it goes out of scope.
+///
+/// Dropping a [`Playspace`] exits it then and there, which blocks while the
+/// temporary directory is removed. Instead, an `AsyncPlayspaceGuard` should be
+/// explicitly [`close`][AsyncPlayspaceGuard::close]d, which exits on a
+/// separate thread and waits for it asynchronously. If it is dropped without
+/// being closed, it is handed to a background "reaper" thread to exit. Any
+/// errors doing so can be collected later with
+/// [`deferred_errors`][AsyncPlayspaceGuard::deferred_errors].
+///
+/// N.B. the Playspace is only released once the reaper has exited it, so a
+/// dropped guard does not immediately free the way for the next Playspace.
+///
+/// Dereferences to the [`Playspace`], so all the usual helpers are available.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::Playspace;
+/// # async {
+/// let space = Playspace::guarded_async().await.unwrap();
+/// space.write_file("some_file.txt", "contents").unwrap();
+///
+/// // ... await some things ...
+///
+/// space.close().await.unwrap();
+/// # };
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub struct AsyncPlayspaceGuard {
+    space: Option<Playspace>,
+}
+
+impl AsyncPlayspaceGuard {
+    /// Exit the Playspace without blocking the current task.
+    ///
+    /// See [`Playspace::exit_async`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Playspace::exit`].
+    ///
+    /// # Panics
+    ///
+    /// If exiting the Playspace panics on the other thread.
+    pub async fn close(mut self) -> Result<(), ExitError> {
+        match self.space.take() {
+            Some(space) => space.exit_async().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Take all errors from exiting dropped guards so far.
+    ///
+    /// Errors from every dropped guard in the process are collected together,
+    /// so this is most useful at the end of a test run, to check that nothing
+    /// went wrong silently.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::AsyncPlayspaceGuard;
+    /// for error in AsyncPlayspaceGuard::deferred_errors() {
+    ///     eprintln!("Error exiting Playspace: {error}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn deferred_errors() -> Vec<ExitError> {
+        std::mem::take(&mut *DEFERRED_ERRORS.lock())
+    }
+}
+
+impl Deref for AsyncPlayspaceGuard {
+    type Target = Playspace;
+
+    fn deref(&self) -> &Playspace {
+        self.space.as_ref().expect("Playspace already closed")
+    }
+}
+
+impl DerefMut for AsyncPlayspaceGuard {
+    fn deref_mut(&mut self) -> &mut Playspace {
+        self.space.as_mut().expect("Playspace already closed")
+    }
+}
+
+impl Drop for AsyncPlayspaceGuard {
+    fn drop(&mut self) {
+        if let Some(space) = self.space.take() {
+            reap(space);
+        }
+    }
+}
+
+impl Playspace {
+    /// Enter a Playspace wrapped in an [`AsyncPlayspaceGuard`], which never
+    /// blocks on exit.
+    ///
+    /// # Waits
+    ///
+    /// The same as [`new_async`][Playspace::new_async].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`new_async`][Playspace::new_async].
+    pub async fn guarded_async() -> Result<AsyncPlayspaceGuard, SpaceError> {
+        Ok(AsyncPlayspaceGuard {
+            space: Some(Self::new_async().await?),
+        })
+    }
+
+    /// Exit the Playspace without blocking the current task.
+    ///
+    /// Just like [`exit`][Playspace::exit], but the blocking work (mostly
+    /// removing the temporary directory) is done elsewhere and the returned
+    /// future completes once it has finished. Within a tokio runtime this is
+    /// done with `spawn_blocking`, otherwise on a new thread, so it does not
+    /// depend on any particular async runtime.
+    ///
+    /// If the future is dropped before it completes, the Playspace is still
+    /// exited, just without waiting for it.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`exit`][Playspace::exit].
+    ///
+    /// # Panics
+    ///
+    /// If exiting the Playspace panics on the other thread.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # async {
+    /// let space = Playspace::new_async().await.unwrap();
+    ///
+    /// // ... use the Playspace ...
+    ///
+    /// space.exit_async().await.unwrap();
+    /// # };
+    /// ```
+    pub async fn exit_async(self) -> Result<(), ExitError> {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            return match runtime.spawn_blocking(move || self.exit()).await {
+                Ok(result) => result,
+                Err(error) => std::panic::resume_unwind(error.into_panic()),
+            };
+        }
+
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ignored = sender.send(self.exit());
+        });
+        receiver.await.expect("Playspace exit thread panicked")
+    }
+}
@@ -0,0 +1,36 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{io, path::Path};
+
+use landlock::{
+    path_beneath_rules, AccessFs, Ruleset, RulesetAttr, RulesetCreatedAttr, RulesetStatus, ABI,
+};
+
+/// Forbid the current thread (and anything it later starts) from writing
+/// anywhere but beneath `root`, using Landlock
+///
+/// Removing `root` itself needs `RemoveDir` on its parent, and `/dev/null` is
+/// writable so that `Stdio::null()` still works.
+pub(crate) fn restrict_writes(root: &Path) -> Result<(), io::Error> {
+    let abi = ABI::V3;
+    let write = AccessFs::from_write(abi);
+    let restrict = || -> Result<_, landlock::RulesetError> {
+        Ruleset::default()
+            .handle_access(write)?
+            .create()?
+            .add_rules(path_beneath_rules([root], write))?
+            .add_rules(path_beneath_rules(root.parent(), AccessFs::RemoveDir))?
+            .add_rules(path_beneath_rules(["/dev/null"], write))?
+            .restrict_self()
+    };
+    let status = restrict().map_err(io::Error::other)?;
+
+    if status.ruleset == RulesetStatus::NotEnforced {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Landlock is not supported by this kernel",
+        ));
+    }
+    Ok(())
+}
@@ -18,7 +18,8 @@
 //! - Runtime-independent async support
 //!
 //! Playspaces **do not** currently provide:
-//! - Actual sandboxing of any meaningful kind
+//! - Actual sandboxing of any meaningful kind, unless opted into on Linux
+//!   with the `harden-linux` feature
 //! - Any limits on being able to "break out" of the sandbox
 //! - Hard guarantees on abusing multiple Playspaces at a time
 //!
@@ -88,23 +89,113 @@
 //!
 
 use std::{
-    collections::HashMap,
-    ffi::{OsStr, OsString},
+    ffi::OsStr,
     fmt::Display,
     fs::File,
+    io::Write,
     mem::ManuallyDrop,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 #[cfg(feature = "async")]
 use std::{future::Future, pin::Pin};
 
+#[cfg(all(windows, feature = "windows-acl"))]
+mod acl;
+#[cfg(feature = "archive")]
+mod archive;
+mod assertions;
+mod builder;
+#[cfg(feature = "cap-std")]
+mod cap;
+mod cargo;
+mod checkpoint;
+mod child;
+pub mod clock;
+pub mod config;
+mod contamination;
+mod copy;
+mod current;
+mod detach;
+#[cfg(feature = "dotenv")]
+mod dotenv;
+mod env;
+mod env_preset;
+mod env_spec;
+pub mod fixtures;
+#[cfg(any(feature = "serde-json", feature = "toml", feature = "yaml"))]
+mod formats;
+#[cfg(feature = "glob")]
+mod glob;
+#[cfg(feature = "async")]
+mod guard;
+#[cfg(all(target_os = "linux", feature = "harden-linux"))]
+mod harden;
+mod log;
+pub mod metrics;
 mod mutex;
+mod open;
+mod permissions;
+mod persist;
+mod process;
+mod quarantine;
+mod read_only;
+mod removal;
+mod retention;
+mod run;
+mod scratchpad;
+mod sequence;
+mod shim;
+mod snapshot;
+mod sqlite;
+pub mod sync;
+mod tools;
+mod trace;
+mod tree;
+mod walk;
+mod workspace;
 
+#[cfg(feature = "archive")]
+pub use archive::ArchiveError;
+pub use builder::PlayspaceBuilder;
+pub use cargo::CargoDirs;
+pub use checkpoint::Checkpoint;
+pub use child::ChildSpace;
+pub use contamination::{Contamination, OnContamination};
+pub use current::{SpaceHolder, SpaceStatus};
+pub use detach::DetachedPlayspace;
+#[cfg(feature = "dotenv")]
+pub use dotenv::DotenvError;
+pub use env::{
+    env_lock, EnvDiff, EnvLockGuard, EnvScopeGuard, EnvSnapshot, HOME_DIR, RUNTIME_DIR, TEMP_DIR,
+};
+pub use env_preset::EnvPreset;
+pub use env_spec::EnvSpec;
+#[cfg(any(feature = "serde-json", feature = "toml", feature = "yaml"))]
+pub use formats::FormatError;
+#[cfg(feature = "glob")]
+pub use glob::GlobError;
 #[cfg(feature = "async")]
-use mutex::MUTEX;
-use mutex::{blocking_lock, try_lock, Lock};
+pub use guard::AsyncPlayspaceGuard;
+pub use log::LOG_FILE;
+use mutex::{blocking_lock, try_lock, Hold, Lock};
+#[cfg(feature = "async")]
+use mutex::{lock_async, read_async};
+pub use open::OpenBuilder;
+use parking_lot::Mutex;
+pub use process::ProcessError;
+pub use read_only::ReadOnlySpace;
+pub use retention::RetentionFormat;
+pub use run::RunOutput;
+pub use sequence::SpaceSequence;
+pub use shim::{ShimBuilder, ShimCall, BIN_DIR};
+pub use snapshot::{DirDiff, DirSnapshot};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteError;
 use static_assertions::assert_impl_all;
 use tempfile::{tempdir, TempDir};
+pub use tools::{Tool, TOOL_CONFIG_DIR};
+pub use tree::Tree;
+pub use workspace::Workspace;
 
 /// Playspace, while the object exists you are "in" the playspace.
 ///
@@ -146,6 +237,7 @@ use tempfile::{tempdir, TempDir};
 /// enabled, regardless of which methods are used.
 ///
 /// ```rust
+/// # #[cfg(feature = "async")] {
 /// # use playspace::Playspace;
 /// # let path = std::path::Path::new("___playspace_test_file___.txt").to_owned();
 /// use futures::FutureExt; // Needed for `FutureExt::boxed`
@@ -164,6 +256,7 @@ use tempfile::{tempdir, TempDir};
 /// };
 ///
 /// assert!(!path.exists());
+/// # }
 /// ```
 ///
 /// # As an RAII-guard
@@ -211,14 +304,34 @@ use tempfile::{tempdir, TempDir};
 /// [spawn]: std::thread::spawn
 pub struct Playspace {
     // N.B. field order matters! See `exit_internal`
-    saved_environment: HashMap<OsString, OsString>,
+    saved_environment: EnvSnapshot,
     saved_current_dir: Option<PathBuf>,
+    fallback_dir: Option<PathBuf>,
+    presets: Vec<EnvPreset>,
+    exit_barriers: Mutex<Vec<sync::Barrier>>,
+    exit_checks: Mutex<Vec<ExitCheck>>,
+    persisted: Mutex<Vec<persist::Persist>>,
+    #[cfg(all(windows, feature = "windows-acl"))]
+    acl_denied: Mutex<Vec<(PathBuf, String)>>,
+    origin_link: Option<PathBuf>,
+    scratchpad: Mutex<scratchpad::Scratchpad>,
+    retention: Option<retention::Retention>,
+    quarantine: Option<quarantine::Quarantine>,
+    removal: removal::Removal,
+    clock: Option<std::sync::Arc<dyn clock::Clock>>,
+    panicked: bool,
+    persist: bool,
+    on_drop_error: DropErrorPolicy,
+    lock_wait: std::time::Duration,
+    canonical_directory: PathBuf,
     directory: ManuallyDrop<TempDir>,
-    lock: ManuallyDrop<Lock>,
+    lock: ManuallyDrop<Hold>,
 }
 
 assert_impl_all!(Playspace: Send);
 
+type ExitCheck = Box<dyn FnOnce(&Playspace) -> Result<(), String> + Send>;
+
 impl Playspace {
     /// Preferred way to use a `Playspace` in non-async code.
     ///
@@ -227,17 +340,24 @@ impl Playspace {
     /// Returns whatever the closure returns. The semantics of Playspace
     /// construction are the same as [`new`][Playspace::new].
     ///
+    /// If the closure panics, the Playspace is exited before the panic
+    /// continues, so the environment and working directory are already
+    /// restored for anything that catches it (e.g. the test harness).
+    ///
     /// In async code, use [`scoped_async`][Playspace::scoped_async].
     ///
     /// # Blocks
     ///
-    /// Blocks until the current process is not in a Playspace. May deadlock
-    /// if called from a thread holding a `Playspace`.
+    /// Blocks until the current process is not in a Playspace. If this
+    /// thread already holds one, which can't exit until this returns, returns
+    /// [`SpaceError::WouldDeadlock`] rather than blocking forever, unless
+    /// [nesting][config::Config::allow_nesting] is allowed.
     ///
     /// # Errors
     ///
-    /// Returns [`SpaceError::StdIo`] if there were any system IO errors
-    /// entering the Playspace, or [`SpaceError::ExitError`] for errors when
+    /// Returns [`SpaceError::WouldBlockRuntime`] if called from within a tokio
+    /// runtime with the `async` feature enabled, [`SpaceError::StdIo`] if
+    /// there were any system IO errors entering the Playspace, or [`SpaceError::ExitError`] for errors when
     /// exiting the Playspace.
     ///
     /// # Example
@@ -253,13 +373,124 @@ impl Playspace {
     where
         F: FnOnce(&mut Self) -> R,
     {
-        let mut space = Self::new()?;
-        let out = f(&mut space);
-        space.exit()?;
+        let (out, exited) = Self::new()?.run_scoped(f);
+        exited?;
+
+        Ok(out)
+    }
+
+    /// Like [`scoped`][Playspace::scoped], but for closures returning a
+    /// `Result`, so `?` can be used inside them.
+    ///
+    /// The closure's error and any error entering or exiting the Playspace
+    /// are combined into a [`ScopedError`]. The Playspace is always exited,
+    /// even if the closure fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ScopedError::Closure`] if the closure returned an error, even
+    /// if exiting also failed. Otherwise returns [`ScopedError::Space`] for
+    /// any error entering or exiting the Playspace, as for
+    /// [`scoped`][Playspace::scoped].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, ScopedError, WriteError};
+    /// let contents = Playspace::scoped_fallible(|space| {
+    ///     space.write_file("some_file.txt", "some contents")?;
+    ///     Ok::<_, WriteError>(std::fs::read_to_string("some_file.txt")?)
+    /// })
+    /// .unwrap();
+    /// assert_eq!(contents, "some contents");
+    ///
+    /// let result = Playspace::scoped_fallible(|_space| std::fs::read_to_string("missing.txt"));
+    /// assert!(matches!(result, Err(ScopedError::Closure(_))));
+    /// ```
+    pub fn scoped_fallible<R, E, F>(f: F) -> Result<R, ScopedError<E>>
+    where
+        F: FnOnce(&mut Self) -> Result<R, E>,
+    {
+        let (out, exited) = Self::new()?.run_scoped(f);
 
+        let out = out.map_err(ScopedError::Closure)?;
+        exited.map_err(SpaceError::from)?;
         Ok(out)
     }
 
+    /// Like [`scoped`][Playspace::scoped], but runs the closure on a new
+    /// thread with the default stack size, see
+    /// [`scoped_in_thread_with_stack_size`][Playspace::scoped_in_thread_with_stack_size].
+    #[allow(clippy::missing_errors_doc)]
+    pub fn scoped_in_thread<R, F>(f: F) -> Result<R, SpaceError>
+    where
+        R: Send,
+        F: FnOnce(&mut Self) -> R + Send,
+    {
+        Self::in_thread(std::thread::Builder::new(), f)
+    }
+
+    /// Like [`scoped`][Playspace::scoped], but the Playspace is entered, used,
+    /// and exited on a new thread with a stack of `stack_size` bytes, which
+    /// is joined before returning.
+    ///
+    /// The closure starts with fresh thread-locals, and deeply recursive code
+    /// can be given the stack it needs without changing `RUST_MIN_STACK` for
+    /// everything else. Since the calling thread only waits, this may also be
+    /// called from within a tokio runtime with the `async` feature enabled.
+    ///
+    /// If the closure panics, the Playspace is exited and the panic is
+    /// continued on the calling thread.
+    ///
+    /// # Blocks
+    ///
+    /// Blocks until the current process is not in a Playspace. If this
+    /// thread already holds one, returns [`SpaceError::WouldDeadlock`], even with
+    /// [nesting][config::Config::allow_nesting] allowed, since the closure
+    /// runs on another thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpaceError::StdIo`] if the thread could not be spawned, or
+    /// any error from [`scoped`][Playspace::scoped].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// fn depth(n: u64) -> u64 {
+    ///     if n == 0 { 0 } else { 1 + depth(n - 1) }
+    /// }
+    ///
+    /// let out = Playspace::scoped_in_thread_with_stack_size(64 * 1024 * 1024, |space| {
+    ///     space.write_file("depth.txt", depth(100_000).to_string()).unwrap();
+    ///     std::fs::read_to_string("depth.txt").unwrap()
+    /// }).unwrap();
+    /// assert_eq!(out, "100000");
+    /// ```
+    pub fn scoped_in_thread_with_stack_size<R, F>(stack_size: usize, f: F) -> Result<R, SpaceError>
+    where
+        R: Send,
+        F: FnOnce(&mut Self) -> R + Send,
+    {
+        Self::in_thread(std::thread::Builder::new().stack_size(stack_size), f)
+    }
+
+    fn in_thread<R, F>(thread: std::thread::Builder, f: F) -> Result<R, SpaceError>
+    where
+        R: Send,
+        F: FnOnce(&mut Self) -> R + Send,
+    {
+        // The new thread would wait for this one, which waits for it
+        mutex::check_deadlock()?;
+        std::thread::scope(|scope| {
+            let handle = thread.spawn_scoped(scope, || Self::scoped(f))?;
+            handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })
+    }
+
     /// A scoped Playspace that doesn't block if already in one.
     ///
     /// Behaves exactly like [`scoped`][Playspace::scoped], but never blocks and
@@ -291,9 +522,8 @@ impl Playspace {
     where
         F: FnOnce(&mut Self) -> R,
     {
-        let mut space = Self::try_new()?;
-        let out = f(&mut space);
-        space.exit()?;
+        let (out, exited) = Self::try_new()?.run_scoped(f);
+        exited?;
 
         Ok(out)
     }
@@ -310,9 +540,38 @@ impl Playspace {
         V: AsRef<OsStr>,
         F: FnOnce(&mut Self) -> R,
     {
-        let mut space = Self::with_envs(vars)?;
-        let out = f(&mut space);
-        space.exit()?;
+        let (out, exited) = Self::with_envs(vars)?.run_scoped(f);
+        exited?;
+
+        Ok(out)
+    }
+
+    /// Convenience combination of [`scoped`][Playspace::scoped] with implicit
+    /// [`write_files`][Playspace::write_files].
+    ///
+    /// In async code, use [`scoped_with_files_async`][Playspace::scoped_with_files_async].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped_with_files([
+    ///     ("Cargo.toml", "[package]"),
+    ///     ("src/main.rs", "fn main() {}"),
+    /// ], |space| {
+    ///     assert!(space.directory().join("src/main.rs").exists());
+    /// }).unwrap();
+    /// ```
+    #[allow(clippy::missing_errors_doc)]
+    pub fn scoped_with_files<I, P, C, R, F>(files: I, f: F) -> Result<R, SpaceError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+        F: FnOnce(&mut Self) -> R,
+    {
+        let (out, exited) = Self::with_files(files)?.run_scoped(f);
+        exited?;
 
         Ok(out)
     }
@@ -325,26 +584,29 @@ impl Playspace {
     ///
     /// # Blocks
     ///
-    /// Blocks until the current process is not in a Playspace. May deadlock
-    /// if called from a thread holding a `Playspace`.
+    /// Blocks until the current process is not in a Playspace. If this
+    /// thread already holds one, which can't exit until this returns, returns
+    /// [`SpaceError::WouldDeadlock`] rather than blocking forever, unless
+    /// [nesting][config::Config::allow_nesting] is allowed.
     ///
     /// # Errors
     ///
-    /// Returns [`SpaceError::StdIo`] if there were any system IO errors
-    /// entering the Playspace.
+    /// Returns [`SpaceError::WouldBlockRuntime`] if called from within a tokio
+    /// runtime with the `async` feature enabled, [`SpaceError::StdIo`] if
+    /// there were any system IO errors entering the Playspace.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use playspace::Playspace;
     /// let space = Playspace::new().unwrap();
-    /// // let space2 = Playspace::new();  // <-- This would deadlock
-    /// let space2 = Playspace::try_new(); // <-- This will be an error, but not deadlock
+    /// let space2 = Playspace::new();     // <-- Error, rather than deadlock
+    /// let space2 = Playspace::try_new(); // <-- Error, since already in a Playspace
     /// // Cleanly exit and handle any errors
     /// let exit_result = space.exit();
     /// ```
     pub fn new() -> Result<Self, SpaceError> {
-        Ok(Self::from_lock(blocking_lock())?)
+        Self::from_hold(Hold::take(blocking_lock)?)
     }
 
     /// Convenience combination of [`new`][Playspace::new] followed by
@@ -364,6 +626,23 @@ impl Playspace {
         Ok(out)
     }
 
+    /// Convenience combination of [`new`][Playspace::new] followed by
+    /// [`write_files`][Playspace::write_files]. Prefer [`scoped_with_files`][Playspace::scoped_with_files]
+    /// where possible.
+    ///
+    /// In async code, use [`with_files_async`][Playspace::with_files_async].
+    #[allow(clippy::missing_errors_doc)]
+    pub fn with_files<I, P, C>(files: I) -> Result<Self, SpaceError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        let out = Self::new()?;
+        out.write_files(files)?;
+        Ok(out)
+    }
+
     /// Create a `Playspace` for use as an RAII-guard, do not block if already
     /// in a Playspace. Prefer [`try_scoped`][Playspace::try_scoped] or
     /// [`try_scoped_async`][Playspace::try_scoped] where possible.
@@ -380,33 +659,125 @@ impl Playspace {
     /// ```rust
     /// # use playspace::Playspace;
     /// let space = Playspace::try_new().unwrap();
-    /// // let space2 = Playspace::new();  // <-- This would deadlock
-    /// let space2 = Playspace::try_new(); // <-- This will be an error, but not deadlock
+    /// let space2 = Playspace::new();     // <-- Error, rather than deadlock
+    /// let space2 = Playspace::try_new(); // <-- Error, since already in a Playspace
     /// // Cleanly exit and handle any errors
     /// let exit_result = space.exit();
     /// ```
     pub fn try_new() -> Result<Self, SpaceError> {
-        let lock = try_lock().ok_or(SpaceError::AlreadyInSpace)?;
-        Ok(Self::from_lock(lock)?)
+        Self::from_hold(Hold::take(|| try_lock().ok_or(SpaceError::AlreadyInSpace))?)
+    }
+
+    fn from_lock(lock: Lock) -> Result<Self, SpaceError> {
+        Self::from_hold(Hold::Lock(lock))
+    }
+
+    fn from_hold(hold: Hold) -> Result<Self, SpaceError> {
+        Self::from_hold_with(hold, default_directory)
     }
 
-    fn from_lock(lock: Lock) -> Result<Self, std::io::Error> {
-        // Lock has been taken, good.
+    pub(crate) fn from_hold_with<F>(hold: Hold, make_directory: F) -> Result<Self, SpaceError>
+    where
+        F: FnOnce() -> Result<TempDir, std::io::Error>,
+    {
+        let lock_wait = match &hold {
+            Hold::Lock(_lock) => {
+                // Lock has been taken, good. Claim how long that took before
+                // anything can fail
+                let lock_wait = mutex::take_wait();
+
+                // Nothing else can be in a Playspace, so any differences from
+                // the baseline came from outside of one
+                contamination::check()?;
+                lock_wait
+            }
+            // The parent's state is saved just like the state outside
+            Hold::Nested => std::time::Duration::ZERO,
+            Hold::Unlocked => return Self::without_chdir(make_directory),
+        };
+
         // Then save the environment and dir, since they're infallibe
-        let saved_environment = std::env::vars_os().collect();
+        let saved_environment = EnvSnapshot::capture();
         let saved_current_dir = std::env::current_dir().ok();
         // This is safe to fail, no cleanup
-        let directory = tempdir()?;
+        let directory = make_directory()?;
+        // As is this, `directory` is removed when dropped
+        let canonical_directory = directory.path().canonicalize()?;
 
         // This is safe to fail, no cleanup required
         std::env::set_current_dir(directory.path())?;
+        current::enter(directory.path());
+        trace::event!(tracing::Level::INFO, root = %directory.path().display(), "entered Playspace");
+
+        Ok(Self::from_parts(
+            hold,
+            lock_wait,
+            directory,
+            canonical_directory,
+            saved_environment,
+            saved_current_dir,
+        ))
+    }
 
-        Ok(Self {
+    /// Create a Playspace which touches nothing process-wide: the working
+    /// directory, environment, and lock are all left alone
+    pub(crate) fn without_chdir<F>(make_directory: F) -> Result<Self, SpaceError>
+    where
+        F: FnOnce() -> Result<TempDir, std::io::Error>,
+    {
+        let directory = make_directory()?;
+        let canonical_directory = directory.path().canonicalize()?;
+        trace::event!(tracing::Level::INFO, root = %directory.path().display(), "created Playspace without changing directory");
+
+        Ok(Self::from_parts(
+            Hold::Unlocked,
+            std::time::Duration::ZERO,
+            directory,
+            canonical_directory,
+            EnvSnapshot::default(),
+            std::env::current_dir().ok(),
+        ))
+    }
+
+    fn from_parts(
+        lock: Hold,
+        lock_wait: std::time::Duration,
+        directory: TempDir,
+        canonical_directory: PathBuf,
+        saved_environment: EnvSnapshot,
+        saved_current_dir: Option<PathBuf>,
+    ) -> Self {
+        let config = config::get();
+        Self {
             lock: ManuallyDrop::new(lock),
             directory: ManuallyDrop::new(directory),
+            canonical_directory,
             saved_environment,
             saved_current_dir,
-        })
+            fallback_dir: None,
+            presets: Vec::new(),
+            exit_barriers: Mutex::default(),
+            exit_checks: Mutex::default(),
+            persisted: Mutex::default(),
+            #[cfg(all(windows, feature = "windows-acl"))]
+            acl_denied: Mutex::default(),
+            origin_link: None,
+            scratchpad: Mutex::default(),
+            retention: config.keep_on_failure.then(retention::Retention::default),
+            quarantine: None,
+            removal: config.removal,
+            clock: None,
+            panicked: false,
+            persist: false,
+            on_drop_error: config.on_drop_error,
+            lock_wait,
+        }
+    }
+
+    /// Whether this Playspace changed the working directory, and so restores
+    /// it and the environment on exit
+    pub(crate) fn changes_directory(&self) -> bool {
+        !matches!(*self.lock, Hold::Unlocked)
     }
 
     /// Returns path to the directory root of the Playspace.
@@ -429,13 +800,81 @@ impl Playspace {
         self.directory.path()
     }
 
+    /// The working directory from before the Playspace was entered, which is
+    /// returned to on exit.
+    ///
+    /// Useful for reaching real project files (e.g. `tests/data`) from inside
+    /// the Playspace. Returns `None` if the working directory was invalid
+    /// (e.g. deleted) when the Playspace was entered.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let manifest = space.original_directory().unwrap().join("Cargo.toml");
+    ///     assert!(manifest.exists());
+    ///     assert!(!std::path::Path::new("Cargo.toml").exists());
+    /// }).unwrap();
+    /// ```
+    #[must_use]
+    pub fn original_directory(&self) -> Option<&Path> {
+        self.saved_current_dir.as_deref()
+    }
+
+    /// How long this Playspace waited for any other Playspace to exit
+    /// before it could be entered.
+    ///
+    /// Zero for Playspaces which didn't wait, e.g. from
+    /// [`try_new`][Playspace::try_new] or after the first in a
+    /// [`SpaceSequence`]. See the [`metrics`] module for a hook to collect
+    /// these.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     eprintln!("waited {:?} for the Playspace", space.lock_wait());
+    /// }).unwrap();
+    /// ```
+    #[allow(clippy::must_use_candidate)]
+    pub fn lock_wait(&self) -> std::time::Duration {
+        self.lock_wait
+    }
+
+    /// Returns the canonical path to the directory root of the Playspace,
+    /// with all symlinks resolved.
+    ///
+    /// The system temporary directory is often reached through a symlink
+    /// (e.g. `/var` is a symlink to `/private/var` on macOS), so paths from
+    /// [`directory`][Playspace::directory] and canonicalized paths (e.g. from
+    /// [`std::env::current_dir`]) may not compare as expected. Compare
+    /// canonical paths with this instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let current = std::env::current_dir().unwrap();
+    ///     assert!(current.starts_with(space.directory_canonical()));
+    /// }).unwrap();
+    /// ```
+    #[allow(clippy::must_use_candidate)]
+    pub fn directory_canonical(&self) -> &Path {
+        &self.canonical_directory
+    }
+
     /// Set or unset several environment variables.
     ///
     /// Pass an iterable of `(environmentvariable, value)` pairs. If the value
     /// is `None` the variable is unset, otherwise it is set to the value.
     ///
     /// Equivalent to repeated calls to `std::env::set_var` and
-    /// `std::env::remove_var`.
+    /// `std::env::remove_var`, but all made while holding the
+    /// [environment lock][env_lock], so other threads holding it never see
+    /// only some of them.
     ///
     /// # Example
     ///
@@ -455,11 +894,13 @@ impl Playspace {
         K: AsRef<OsStr>,
         V: AsRef<OsStr>,
     {
+        let _env = env_lock();
         for (key, value) in vars {
+            trace::event!(tracing::Level::DEBUG, key = ?key.as_ref(), set = value.is_some(), "set_envs");
             match value {
-                Some(value) => std::env::set_var(key, value),
-                None => std::env::remove_var(key),
-            };
+                Some(value) => env::set_var(key, value),
+                None => env::remove_var(key),
+            }
         }
     }
 
@@ -489,7 +930,83 @@ impl Playspace {
         C: AsRef<[u8]>,
     {
         let path = self.playspace_path(path)?;
-        Ok(std::fs::write(path, contents)?)
+        trace::event!(tracing::Level::DEBUG, path = %path.display(), "write_file");
+        std::fs::write(&path, contents).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Append to a file in the Playspace, creating it if it doesn't exist.
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.append_file("server.log", "starting\n").unwrap();
+    ///     space.append_file("server.log", "listening\n").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("server.log").unwrap(), "starting\nlistening\n");
+    /// }).unwrap();
+    /// ```
+    pub fn append_file<P, C>(&self, path: P, contents: C) -> Result<(), WriteError>
+    where
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        let path = self.playspace_path(path)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|source| WriteError::io(&path, source))?;
+        file.write_all(contents.as_ref())
+            .map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Write several files to the Playspace, creating parent directories as
+    /// needed.
+    ///
+    /// Pass an iterable of `(path, contents)` pairs. Each path is handled
+    /// exactly as by [`write_file`][Playspace::write_file], except that any
+    /// missing parent directories are created first.
+    ///
+    /// # Errors
+    ///
+    /// If any of the provided paths is not in the Playspace, an error will be
+    /// returned. Any stardard IO error is bubbled-up. Files are written in
+    /// order, and writing stops at the first error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([
+    ///         ("config/app.toml", "verbose = true"),
+    ///         ("data/input.csv", "a,b,c"),
+    ///     ]).unwrap();
+    /// }).unwrap();
+    /// ```
+    pub fn write_files<I, P, C>(&self, files: I) -> Result<(), WriteError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        for (path, contents) in files {
+            let path = self.playspace_path(path)?;
+            trace::event!(tracing::Level::DEBUG, path = %path.display(), "write_files");
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|source| WriteError::io(parent, source))?;
+            }
+            std::fs::write(&path, contents).map_err(|source| WriteError::io(&path, source))?;
+        }
+        Ok(())
     }
 
     /// Create a file in the Playspace, returning the [`File`][std::fs::File]
@@ -515,15 +1032,16 @@ impl Playspace {
     /// ```
     pub fn create_file(&self, path: impl AsRef<Path>) -> Result<File, WriteError> {
         let path = self.playspace_path(path)?;
-        Ok(std::fs::File::create(path)?)
+        std::fs::File::create(&path).map_err(|source| WriteError::io(&path, source))
     }
 
-    /// Create one or more directories in the Playspace, similar to [`std::fs::create_dir_all`].
+    /// Create an empty file in the Playspace, or update its modification time
+    /// to now if it already exists, like the `touch` command.
     ///
-    /// Relative paths are _always_ evaluated with respect to the Playspace
-    /// root directory, even if the current directory has since changed. Whether
-    /// the given path is relative or absolute, this checks that the given
-    /// path is inside the Playspace.
+    /// The time is taken from the Playspace's [clock][Playspace::now].
+    /// The contents of existing files are left untouched. The path is handled
+    /// exactly as by [`write_file`][Playspace::write_file]. To also create any
+    /// missing parent directories, use [`touch_all`][Playspace::touch_all].
     ///
     /// # Errors
     ///
@@ -535,119 +1053,1144 @@ impl Playspace {
     /// ```rust
     /// # use playspace::Playspace;
     /// Playspace::scoped(|space| {
-    ///     space.create_dir_all("some/non/existent/dirs").unwrap();
+    ///     space.write_file("cache.json", "{}").unwrap();
+    ///     let before = std::fs::metadata("cache.json").unwrap().modified().unwrap();
+    ///
+    ///     space.touch("cache.json").unwrap();
+    ///     let after = std::fs::metadata("cache.json").unwrap().modified().unwrap();
+    ///     assert!(after >= before);
+    ///     assert_eq!(std::fs::read_to_string("cache.json").unwrap(), "{}");
     /// }).unwrap();
     /// ```
-    pub fn create_dir_all(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+    pub fn touch(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
         let path = self.playspace_path(path)?;
-        Ok(std::fs::create_dir_all(path)?)
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|source| WriteError::io(&path, source))?;
+        file.set_modified(self.now())
+            .map_err(|source| WriteError::io(&path, source))
     }
 
-    fn playspace_path(&self, path: impl AsRef<Path>) -> Result<PathBuf, WriteError> {
-        if path.as_ref().is_relative() {
-            // Simple case, just assume it was meant to be relative to the of the space
-            Ok(self.directory().join(path))
-        } else {
-            // Ensure that the absolute path given is actually in the playspace
-            for ancestor in path.as_ref().ancestors() {
-                if ancestor.exists() {
-                    // Found a parent
-                    let canonical_ancestor = ancestor.canonicalize()?;
-                    if !canonical_ancestor.starts_with(self.directory().canonicalize()?) {
-                        // Not in the playspace
-                        return Err(WriteError::OutsidePlayspace(path.as_ref().into()));
-                    }
-                    return Ok(path.as_ref().into());
-                }
-            }
-
-            // Couldn't find a parent in the playspace
-            Err(WriteError::OutsidePlayspace(path.as_ref().into()))
+    /// Like [`touch`][Playspace::touch], but also creates any missing parent
+    /// directories.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.touch_all("target/debug/.fingerprint").unwrap();
+    ///     assert!(std::path::Path::new("target/debug/.fingerprint").is_file());
+    /// }).unwrap();
+    /// ```
+    pub fn touch_all(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| WriteError::io(parent, source))?;
         }
+        self.touch(path)
     }
 
-    /// Leave the Playspace cleanly, reporting any errors doing so. Preferred
-    /// explicit destructor over simply allowing `drop()` to be called.
+    /// Set the access and modification times of a file or directory in the
+    /// Playspace.
+    ///
+    /// Useful for setting up precise timestamp relationships between fixture
+    /// files, e.g. for testing cache invalidation. The path is handled
+    /// exactly as by [`write_file`][Playspace::write_file]. Symlinks are
+    /// followed.
     ///
     /// # Errors
     ///
-    /// Returns any errors in either returning to the previous working directory
-    /// or removing the temporary Playspace directory. Always attempts both
-    /// operations and will report both errors if both fail.
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
     ///
     /// # Example
     ///
     /// ```rust
     /// # use playspace::Playspace;
-    /// {
-    ///     let space = Playspace::new().unwrap();
-    ///
-    ///     // ... use the Playspace ...
+    /// # use std::time::{Duration, SystemTime};
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("input.txt", ""), ("output.txt", "")]).unwrap();
+    ///     let then = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    ///     space.set_file_times("output.txt", then, then).unwrap();
     ///
-    ///     // If this is omitted, then any errors exiting the Playspace would
-    ///     // be silently ignored in `drop()`.
-    ///     if let Err(error) = space.exit() {
-    ///         // handle the error
-    ///     }
-    /// }
+    ///     let modified = |path| std::fs::metadata(path).unwrap().modified().unwrap();
+    ///     assert!(modified("output.txt") < modified("input.txt"));
+    /// }).unwrap();
     /// ```
-    pub fn exit(mut self) -> Result<(), ExitError> {
-        let result = unsafe { self.exit_internal() };
-
-        // At this point, no fields own heap memory or has been manually
-        // dropped, so we can prevent `drop` from being called again
-        std::mem::forget(self);
-
-        result
+    pub fn set_file_times(
+        &self,
+        path: impl AsRef<Path>,
+        accessed: std::time::SystemTime,
+        modified: std::time::SystemTime,
+    ) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        filetime::set_file_times(
+            &path,
+            filetime::FileTime::from_system_time(accessed),
+            filetime::FileTime::from_system_time(modified),
+        )
+        .map_err(|source| WriteError::io(&path, source))
     }
 
-    unsafe fn exit_internal(&mut self) -> Result<(), ExitError> {
-        // Infallible, do this first
-        self.restore_environment();
-        drop(std::mem::take(&mut self.saved_environment));
-
-        let saved_current_dir = self.saved_current_dir.take();
-        let working_dir_result = Self::restore_directory(saved_current_dir);
-
-        // N.B. `ManuallyDrop::take` makes a bitwise copy, but since `directory` only
-        // contains a `Box` this is fine.
-        let temp_dir_result = ManuallyDrop::take(&mut self.directory).close();
-
-        // This must be done last
-        ManuallyDrop::drop(&mut self.lock);
-
-        match working_dir_result {
-            Ok(()) => match temp_dir_result {
-                Ok(()) => Ok(()),
-                Err(temp) => Err(ExitError::TempDirRemoveFailed { source: temp }),
-            },
-            Err(working) => Err(ExitError::WorkingDirChangeFailed {
-                source: working,
-                temp_dir: temp_dir_result.err(),
-            }),
-        }
-    }
+    /// Create one or more directories in the Playspace, similar to [`std::fs::create_dir_all`].
+    ///
+    /// Relative paths are _always_ evaluated with respect to the Playspace
+    /// root directory, even if the current directory has since changed. Whether
+    /// the given path is relative or absolute, this checks that the given
+    /// path is inside the Playspace.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.create_dir_all("some/non/existent/dirs").unwrap();
+    /// }).unwrap();
+    /// ```
+    pub fn create_dir_all(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::create_dir_all(&path).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Whether a path in the Playspace exists, similar to [`Path::exists`].
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file],
+    /// so relative paths are evaluated with respect to the Playspace root
+    /// even if the working directory has since changed. Paths outside the
+    /// Playspace, and paths which can't be accessed, never exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("sub/file.txt", "")]).unwrap();
+    ///     std::env::set_current_dir("sub").unwrap();
+    ///     assert!(space.exists("sub/file.txt"));
+    ///     assert!(!space.exists("file.txt"));
+    /// }).unwrap();
+    /// ```
+    #[must_use]
+    pub fn exists(&self, path: impl AsRef<Path>) -> bool {
+        self.playspace_path(path)
+            .is_ok_and(|path| std::fs::symlink_metadata(path).is_ok())
+    }
+
+    /// Query the metadata of a path in the Playspace, similar to
+    /// [`std::fs::metadata`] (symlinks are followed).
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("file.txt", "contents").unwrap();
+    ///     let metadata = space.metadata("file.txt").unwrap();
+    ///     assert!(metadata.is_file());
+    ///     assert_eq!(metadata.len(), 8);
+    /// }).unwrap();
+    /// ```
+    pub fn metadata(&self, path: impl AsRef<Path>) -> Result<std::fs::Metadata, WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::metadata(&path).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// List the entries of a directory in the Playspace, relative to the
+    /// Playspace root.
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    /// Only immediate children are listed, in sorted order, with paths
+    /// relative to the Playspace root (not the listed directory) so they can
+    /// be compared directly in assertions.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::path::PathBuf;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("out/b.txt", ""), ("out/a.txt", "")]).unwrap();
+    ///     assert_eq!(
+    ///         space.list("out").unwrap(),
+    ///         [PathBuf::from("out/a.txt"), PathBuf::from("out/b.txt")]
+    ///     );
+    /// }).unwrap();
+    /// ```
+    pub fn list(&self, path: impl AsRef<Path>) -> Result<Vec<PathBuf>, WriteError> {
+        let path = self.playspace_path(path)?;
+        let relative = match path.strip_prefix(self.directory()) {
+            Ok(relative) => relative.to_owned(),
+            // An absolute path may have been written differently, e.g. via a
+            // symlink in the temporary directory's path
+            Err(_) => path
+                .canonicalize()
+                .map_err(|source| WriteError::io(&path, source))?
+                .strip_prefix(self.directory_canonical())
+                .map_err(|_prefix| WriteError::OutsidePlayspace(path.clone()))?
+                .to_owned(),
+        };
+
+        let mut entries = std::fs::read_dir(&path)
+            .map_err(|source| WriteError::io(&path, source))?
+            .map(|entry| Ok(relative.join(entry?.file_name())))
+            .collect::<Result<Vec<_>, std::io::Error>>()
+            .map_err(|source| WriteError::io(&path, source))?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    /// Remove a file from the Playspace, similar to [`std::fs::remove_file`].
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file],
+    /// so a stray absolute path can't remove anything outside the Playspace.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("fixture.txt", "").unwrap();
+    ///     space.remove_file("fixture.txt").unwrap();
+    ///     assert!(!std::path::Path::new("fixture.txt").exists());
+    /// }).unwrap();
+    /// ```
+    pub fn remove_file(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::remove_file(&path).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Remove a directory and all of its contents from the Playspace, similar
+    /// to [`std::fs::remove_dir_all`].
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file],
+    /// so a stray absolute path can't remove anything outside the Playspace.
+    /// Symlinks inside the directory are removed, not followed.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("fixtures/a.txt", ""), ("fixtures/b.txt", "")]).unwrap();
+    ///     space.remove_dir_all("fixtures").unwrap();
+    ///     assert!(!std::path::Path::new("fixtures").exists());
+    /// }).unwrap();
+    /// ```
+    pub fn remove_dir_all(&self, path: impl AsRef<Path>) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::remove_dir_all(&path).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Rename a file or directory within the Playspace, similar to
+    /// [`std::fs::rename`].
+    ///
+    /// Both paths are handled exactly as by [`write_file`][Playspace::write_file],
+    /// so relative paths are evaluated with respect to the Playspace root and
+    /// both must be inside the Playspace.
+    ///
+    /// # Errors
+    ///
+    /// If either of the provided paths is not in the Playspace, an error will
+    /// be returned. Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("draft.txt", "contents").unwrap();
+    ///     space.rename("draft.txt", "final.txt").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("final.txt").unwrap(), "contents");
+    /// }).unwrap();
+    /// ```
+    pub fn rename(&self, from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<(), WriteError> {
+        let from = self.playspace_path(from)?;
+        let to = self.playspace_path(to)?;
+        std::fs::rename(from, &to).map_err(|source| WriteError::io(&to, source))
+    }
+
+    /// Copy a file within the Playspace, similar to [`std::fs::copy`].
+    ///
+    /// Both paths are handled exactly as by [`write_file`][Playspace::write_file],
+    /// so relative paths are evaluated with respect to the Playspace root and
+    /// both must be inside the Playspace. Returns the number of bytes copied.
+    ///
+    /// # Errors
+    ///
+    /// If either of the provided paths is not in the Playspace, an error will
+    /// be returned. Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("original.txt", "contents").unwrap();
+    ///     space.copy("original.txt", "copy.txt").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("copy.txt").unwrap(), "contents");
+    /// }).unwrap();
+    /// ```
+    pub fn copy(&self, from: impl AsRef<Path>, to: impl AsRef<Path>) -> Result<u64, WriteError> {
+        let from = self.playspace_path(from)?;
+        let to = self.playspace_path(to)?;
+        std::fs::copy(from, &to).map_err(|source| WriteError::io(&to, source))
+    }
+
+    /// Create a symlink in the Playspace, similar to
+    /// `std::os::unix::fs::symlink`.
+    ///
+    /// `link` is handled exactly as by [`write_file`][Playspace::write_file].
+    /// `target` is stored in the link as given, so (as usual for symlinks) a
+    /// relative `target` is relative to the directory containing `link`.
+    /// Either way, the target must also be inside the Playspace, though it
+    /// doesn't need to exist yet.
+    ///
+    /// On Windows, a directory symlink is created if the target is an
+    /// existing directory, and a file symlink otherwise. Creating symlinks on
+    /// Windows needs a privilege which is often missing (e.g. without
+    /// Developer Mode), in which case the target is copied instead, so tests
+    /// relying on the link itself (rather than its contents) should be
+    /// Unix-only.
+    ///
+    /// # Errors
+    ///
+    /// If either the link or its target is not in the Playspace, an error will
+    /// be returned. Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_files([("data/v2/input.txt", "contents")]).unwrap();
+    ///     space.symlink("v2", "data/current").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("data/current/input.txt").unwrap(), "contents");
+    /// }).unwrap();
+    /// ```
+    pub fn symlink(
+        &self,
+        target: impl AsRef<Path>,
+        link: impl AsRef<Path>,
+    ) -> Result<(), WriteError> {
+        let link = self.playspace_path(link)?;
+        let target = target.as_ref();
+        let resolved = match link.parent() {
+            Some(parent) => parent.join(target),
+            None => target.to_owned(),
+        };
+        let resolved = self.playspace_path(resolved)?;
+        copy::symlink(target, &resolved, &link).map_err(|source| WriteError::io(&link, source))
+    }
+
+    /// Create a hard link in the Playspace, similar to [`std::fs::hard_link`].
+    ///
+    /// Both paths are handled exactly as by [`write_file`][Playspace::write_file],
+    /// so relative paths are evaluated with respect to the Playspace root and
+    /// both must be inside the Playspace.
+    ///
+    /// # Errors
+    ///
+    /// If either of the provided paths is not in the Playspace, an error will
+    /// be returned. Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("original.txt", "contents").unwrap();
+    ///     space.hard_link("original.txt", "link.txt").unwrap();
+    ///     std::fs::write("link.txt", "changed").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("original.txt").unwrap(), "changed");
+    /// }).unwrap();
+    /// ```
+    pub fn hard_link(
+        &self,
+        original: impl AsRef<Path>,
+        link: impl AsRef<Path>,
+    ) -> Result<(), WriteError> {
+        let original = self.playspace_path(original)?;
+        let link = self.playspace_path(link)?;
+        std::fs::hard_link(original, &link).map_err(|source| WriteError::io(&link, source))
+    }
+
+    /// Import a file from outside the Playspace as cheaply as possible.
+    ///
+    /// Intended for large fixtures, where a full copy would be slow. The file
+    /// is reflinked (copy-on-write) if the filesystem supports it, otherwise
+    /// hard-linked if `dest` is on the same filesystem as `src`, and only
+    /// copied as a last resort. Either way, removing the Playspace removes
+    /// the imported file but never the original.
+    ///
+    /// N.B. a hard-linked file *is* the original, so writing to it changes
+    /// the original too. Treat imported files as read-only, or use
+    /// [`std::fs::copy`] for files that the code under test will modify.
+    ///
+    /// `src` is evaluated as usual, with respect to the current working
+    /// directory. `dest` is handled exactly as by
+    /// [`write_file`][Playspace::write_file], and must not already exist.
+    ///
+    /// # Errors
+    ///
+    /// If `dest` is not in the Playspace, an error will be returned. Any
+    /// stardard IO error is bubbled-up, including if `dest` already exists.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # let fixture = std::env::temp_dir().join("link-or-copy-in-example.bin");
+    /// # std::fs::write(&fixture, "large contents").unwrap();
+    /// Playspace::scoped(|space| {
+    ///     space.link_or_copy_in(&fixture, "input.bin").unwrap();
+    ///     assert_eq!(std::fs::read_to_string("input.bin").unwrap(), "large contents");
+    /// }).unwrap();
+    /// # std::fs::remove_file(&fixture).unwrap();
+    /// ```
+    pub fn link_or_copy_in(
+        &self,
+        src: impl AsRef<Path>,
+        dest: impl AsRef<Path>,
+    ) -> Result<(), WriteError> {
+        let dest = self.playspace_path(dest)?;
+        copy::link_or_copy(src.as_ref(), &dest).map_err(|source| WriteError::io(&dest, source))
+    }
+
+    /// Temporarily change the working directory to a directory in the
+    /// Playspace.
+    ///
+    /// The directory is created if it doesn't exist, with relative paths
+    /// evaluated with respect to the Playspace root, just like
+    /// [`create_dir_all`][Playspace::create_dir_all]. Returns a guard which
+    /// changes back to the previous working directory when it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     {
+    ///         let _subdir = space.dir_scope("sub/dir").unwrap();
+    ///         std::fs::write("file.txt", "").unwrap();
+    ///     }
+    ///     assert!(std::path::Path::new("sub/dir/file.txt").exists());
+    /// }).unwrap();
+    /// ```
+    pub fn dir_scope(&self, path: impl AsRef<Path>) -> Result<DirScopeGuard<'_>, WriteError> {
+        let path = self.playspace_path(path)?;
+        std::fs::create_dir_all(&path).map_err(|source| WriteError::io(&path, source))?;
+
+        let previous = std::env::current_dir()?;
+        std::env::set_current_dir(path)?;
+        Ok(DirScopeGuard {
+            _space: self,
+            previous,
+        })
+    }
+
+    /// Duplicate the entire contents of the Playspace into another directory.
+    ///
+    /// Useful for A/B tests: run two variants of the code under test against
+    /// identical inputs, then compare the results with
+    /// [`DirSnapshot`]s. The destination is created if it doesn't exist.
+    /// Relative destinations are evaluated with respect to the Playspace root,
+    /// and a destination inside the Playspace is itself skipped while copying.
+    /// Unlike the file helpers, the destination may also be outside the
+    /// Playspace (e.g. a separate temporary directory).
+    ///
+    /// Files are reflinked (copy-on-write) where the filesystem supports it,
+    /// and copied otherwise. They are never hard-linked, since then changes
+    /// made by one variant would be visible to the other. Symlinks are
+    /// recreated as-is.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{DirSnapshot, Playspace};
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("input.txt", "shared input").unwrap();
+    ///     space.clone_contents_to("variant-b").unwrap();
+    ///
+    ///     // ... run variant A in the root and variant B in `variant-b` ...
+    ///
+    ///     let b = DirSnapshot::capture("variant-b").unwrap();
+    ///     assert!(b.contains("input.txt"));
+    /// }).unwrap();
+    /// ```
+    pub fn clone_contents_to(&self, destination: impl AsRef<Path>) -> Result<(), std::io::Error> {
+        let destination = self.directory().join(destination);
+        std::fs::create_dir_all(&destination)?;
+
+        let canonical_destination = destination.canonicalize()?;
+        let skip = canonical_destination
+            .strip_prefix(self.directory_canonical())
+            .ok()
+            .map(Path::to_owned);
+
+        copy::copy_tree(self.directory(), &destination, |path| {
+            skip.as_deref() == Some(path)
+        })
+    }
+
+    /// Resolve a path against the Playspace, checking that it is inside it.
+    ///
+    /// This is the same check used by the file helpers (e.g.
+    /// [`write_file`][Playspace::write_file]), so it can be used to validate
+    /// paths produced by the code under test without attempting a write.
+    /// Relative paths are evaluated with respect to the Playspace root
+    /// directory.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let path = space.normalize("some_file.txt").unwrap();
+    ///     assert_eq!(path, space.directory().join("some_file.txt"));
+    /// }).unwrap();
+    /// ```
+    pub fn normalize(&self, path: impl AsRef<Path>) -> Result<PathBuf, WriteError> {
+        self.playspace_path(path)
+    }
+
+    /// Whether a path is inside the Playspace.
+    ///
+    /// Convenience for [`normalize`][Playspace::normalize] when the resolved
+    /// path isn't needed. Any error checking the path is treated as being
+    /// outside the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     assert!(space.contains("some_file.txt"));
+    ///     assert!(!space.contains(std::env::temp_dir()));
+    /// }).unwrap();
+    /// ```
+    #[allow(clippy::must_use_candidate)]
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.playspace_path(path).is_ok()
+    }
+
+    fn playspace_path(&self, path: impl AsRef<Path>) -> Result<PathBuf, WriteError> {
+        // Relative paths are also checked, since they may lead out of the
+        // Playspace through `..` or a symlink. `..` is resolved up-front, since
+        // a missing directory before it (e.g. `new/../../outside.txt`) would
+        // otherwise hide it from the check, and be created on writing.
+        let path = normalize(&self.directory().join(path));
+
+        // Ensure that the path given is actually in the playspace
+        if resolves_inside(&path, self.directory(), self.directory_canonical())? {
+            Ok(path)
+        } else {
+            Err(WriteError::OutsidePlayspace(path))
+        }
+    }
+
+    /// Arrive at a [`Barrier`][sync::Barrier] once this Playspace has exited.
+    ///
+    /// The arrival happens after the environment and working directory have
+    /// been restored and the Playspace has been released, whether it exits
+    /// via [`exit`][Playspace::exit] or by being dropped, so anything waiting
+    /// on the barrier can immediately enter a Playspace of its own.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, sync::Barrier};
+    /// let done = Barrier::new("arrive-on-exit-example", 1);
+    /// let space = Playspace::new().unwrap();
+    /// space.arrive_on_exit(&done);
+    /// space.exit().unwrap();
+    /// ```
+    pub fn arrive_on_exit(&self, barrier: &sync::Barrier) {
+        self.exit_barriers.lock().push(barrier.clone());
+    }
+
+    /// Return the Playspace to the state it was in when it was entered,
+    /// without exiting it.
+    ///
+    /// Everything in the Playspace directory is removed, the environment is
+    /// restored to how it was before entering, and the working directory is
+    /// changed back to the Playspace root. This is much cheaper than exiting
+    /// and entering a new Playspace, e.g. between the cases of a data-driven
+    /// test. Anything set up by the [builder][Playspace::builder] is set up
+    /// again, but variables and files given to constructors such as
+    /// [`with_envs`][Playspace::with_envs] are not.
+    ///
+    /// Exit checks and barriers are kept.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let mut space = Playspace::new().unwrap();
+    /// for case in ["first", "second"] {
+    ///     space.write_file("input.txt", case).unwrap();
+    ///     space.set_envs([("CASE", Some(case))]);
+    ///
+    ///     // ... run the test case ...
+    ///
+    ///     space.reset().unwrap();
+    ///     assert!(!std::path::Path::new("input.txt").exists());
+    ///     assert!(std::env::var("CASE").is_err());
+    /// }
+    /// ```
+    pub fn reset(&mut self) -> Result<(), std::io::Error> {
+        if self.changes_directory() {
+            self.saved_environment.restore();
+            for preset in &self.presets {
+                self.apply(preset);
+            }
+            std::env::set_current_dir(self.directory())?;
+        }
+        copy::clear_dir(self.directory())?;
+        self.link_origin()
+    }
+
+    /// The current time, according to the Playspace's [clock][clock::Clock].
+    ///
+    /// This is the real time unless a different clock was set with
+    /// [`PlayspaceBuilder::clock`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{clock::FixedClock, Playspace};
+    /// # use std::time::UNIX_EPOCH;
+    /// let space = Playspace::builder().clock(FixedClock(UNIX_EPOCH)).build().unwrap();
+    /// assert_eq!(space.now(), UNIX_EPOCH);
+    /// ```
+    #[must_use]
+    pub fn now(&self) -> std::time::SystemTime {
+        match &self.clock {
+            Some(clock) => clock.now(),
+            None => std::time::SystemTime::now(),
+        }
+    }
+
+    /// Run a check when this Playspace exits, before anything is cleaned up.
+    ///
+    /// Checks run in the order they were added, whether the Playspace exits
+    /// via [`exit`][Playspace::exit] or by being dropped, and see the
+    /// Playspace exactly as the code under test left it: the environment and
+    /// working directory have not yet been restored. This lets shared harness
+    /// code enforce the same invariants across every test (e.g. that no
+    /// temporary files are left behind).
+    ///
+    /// Every check is run even if earlier ones fail, and the failures are
+    /// returned by `exit` as [`ExitError::VerificationFailed`]. They are
+    /// ignored if the Playspace is dropped instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{ExitError, Playspace};
+    /// let space = Playspace::new().unwrap();
+    /// space.verify_on_exit(|space| {
+    ///     if space.directory().join("server.lock").exists() {
+    ///         Err("lock file was not removed".to_owned())
+    ///     } else {
+    ///         Ok(())
+    ///     }
+    /// });
+    ///
+    /// space.write_file("server.lock", "").unwrap();
+    /// match space.exit() {
+    ///     Err(ExitError::VerificationFailed { failures }) => {
+    ///         assert_eq!(failures, ["lock file was not removed"]);
+    ///     }
+    ///     _ => panic!("Should have failed verification"),
+    /// }
+    /// ```
+    pub fn verify_on_exit<F>(&self, check: F)
+    where
+        F: FnOnce(&Playspace) -> Result<(), String> + Send + 'static,
+    {
+        self.exit_checks.lock().push(Box::new(check));
+    }
+
+    /// Copy files matching any of `patterns` out of the Playspace to `dest`
+    /// when it exits, before anything is cleaned up.
+    ///
+    /// Patterns are `/`-separated paths relative to the Playspace root, where
+    /// `*` matches anything within a name and `**` matches any number of
+    /// directories. Everything in a matched directory is copied too. Paths
+    /// relative to the root are kept under `dest`, which is created if needed
+    /// and (if relative) is relative to the original working directory.
+    ///
+    /// This happens however the Playspace exits, including when it is dropped
+    /// while panicking, so CI can archive the outputs of failed tests.
+    /// Failures are reported as [`ExitStep::Persist`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let artifacts = tempfile::tempdir().unwrap();
+    /// let space = Playspace::new().unwrap();
+    /// space.persist_to(artifacts.path(), ["out/report.xml", "logs/**"]);
+    ///
+    /// space
+    ///     .write_files([
+    ///         ("out/report.xml", "<testsuites/>"),
+    ///         ("out/scratch.bin", ""),
+    ///         ("logs/today/run.log", "ok"),
+    ///     ])
+    ///     .unwrap();
+    /// space.exit().unwrap();
+    ///
+    /// assert!(artifacts.path().join("out/report.xml").exists());
+    /// assert!(!artifacts.path().join("out/scratch.bin").exists());
+    /// assert!(artifacts.path().join("logs/today/run.log").exists());
+    /// ```
+    pub fn persist_to<S>(&self, dest: impl AsRef<Path>, patterns: impl IntoIterator<Item = S>)
+    where
+        S: Into<String>,
+    {
+        let dest = match &self.saved_current_dir {
+            Some(origin) => origin.join(dest),
+            None => dest.as_ref().to_owned(),
+        };
+        self.persisted.lock().push(persist::Persist {
+            dest,
+            patterns: patterns.into_iter().map(Into::into).collect(),
+        });
+    }
+
+    /// Leave the Playspace cleanly, reporting any errors doing so. Preferred
+    /// explicit destructor over simply allowing `drop()` to be called.
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors in either returning to the previous working directory
+    /// or removing the temporary Playspace directory. Always attempts both
+    /// operations and will report both errors if both fail.
+    ///
+    /// If any [exit checks][Playspace::verify_on_exit] failed, those failures
+    /// are returned instead, though cleanup is still always attempted.
+    ///
+    /// Every step of exiting is always attempted, but only the most important
+    /// failure is returned. Use [`exit_report`][Playspace::exit_report] to
+    /// see all of them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// {
+    ///     let space = Playspace::new().unwrap();
+    ///
+    ///     // ... use the Playspace ...
+    ///
+    ///     // If this is omitted, then any errors exiting the Playspace would
+    ///     // be silently ignored in `drop()`.
+    ///     if let Err(error) = space.exit() {
+    ///         // handle the error
+    ///     }
+    /// }
+    /// ```
+    pub fn exit(mut self) -> Result<(), ExitError> {
+        let report = unsafe { self.exit_internal() };
 
-    fn restore_directory(saved_current_dir: Option<PathBuf>) -> Result<(), std::io::Error> {
-        if let Some(working_dir) = saved_current_dir {
-            std::env::set_current_dir(working_dir)
-        } else {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "no previous working directory",
-            ))
+        // At this point, no fields own heap memory or has been manually
+        // dropped, so we can prevent `drop` from being called again
+        std::mem::forget(self);
+
+        report.into_result().map(drop)
+    }
+
+    /// Exit the Playspace, reporting what was left in it.
+    ///
+    /// Just like [`exit`][Playspace::exit], but first records every file and
+    /// directory still in the Playspace, so that tests can assert that the
+    /// code under test cleaned up after itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`exit`][Playspace::exit]. Any error listing
+    /// the contents of the Playspace is reported as
+    /// [`ExitError::TempDirRemoveFailed`], after exiting.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::new().unwrap();
+    /// space.write_files([("output/result.txt", "")]).unwrap();
+    ///
+    /// let report = space.exit_with_report().unwrap();
+    /// assert!(!report.is_clean());
+    /// assert_eq!(report.leftover(), ["output", "output/result.txt"].map(std::path::Path::new));
+    /// ```
+    pub fn exit_with_report(self) -> Result<ExitReport, ExitError> {
+        self.exit_report().into_result()
+    }
+
+    /// Exit the Playspace, reporting the outcome of every step of doing so.
+    ///
+    /// Unlike [`exit`][Playspace::exit], this never stops at the first
+    /// failure: the returned [`ExitReport`] has the status of each
+    /// [`ExitStep`] that was taken, along with everything that was
+    /// [left in the Playspace][ExitReport::leftover].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{ExitStep, Playspace};
+    /// let space = Playspace::new().unwrap();
+    /// space.verify_on_exit(|_space| Err("not done yet".to_owned()));
+    ///
+    /// let report = space.exit_report();
+    /// assert!(!report.is_ok());
+    /// for (step, failure) in report.failures() {
+    ///     assert_eq!(step, ExitStep::Checks);
+    ///     eprintln!("{step:?} failed: {failure}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn exit_report(mut self) -> ExitReport {
+        let leftover = walk::walk(self.directory()).map(|mut paths| {
+            paths.retain(|path| path != Path::new(LOG_FILE));
+            paths
+        });
+        let mut report = unsafe { self.exit_internal() };
+
+        // As in `exit`, nothing is left to drop
+        std::mem::forget(self);
+
+        match leftover {
+            Ok(leftover) => {
+                report.leftover = leftover;
+                report.steps.push((ExitStep::Leftover, Ok(())));
+            }
+            Err(source) => report
+                .steps
+                .push((ExitStep::Leftover, Err(StepFailure::Io(source)))),
+        }
+        report
+    }
+
+    /// Exit the Playspace, but leave its directory on disk and return the
+    /// path to it.
+    ///
+    /// Everything else is restored just as for [`exit`][Playspace::exit], and
+    /// the lock is released. The directory is never removed afterwards: that
+    /// is up to the caller. Useful for inspecting the results of a debugging
+    /// session, or handing them to a later stage.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`exit`][Playspace::exit], except that the directory is
+    /// never removed so can't fail to be.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let space = Playspace::new().unwrap();
+    /// space.write_file("output.txt", "results").unwrap();
+    ///
+    /// let path = space.into_path().unwrap();
+    /// assert_eq!(std::fs::read_to_string(path.join("output.txt")).unwrap(), "results");
+    /// # std::fs::remove_dir_all(path).unwrap();
+    /// ```
+    pub fn into_path(mut self) -> Result<PathBuf, ExitError> {
+        let path = self.directory().to_owned();
+        self.persist = true;
+        self.exit()?;
+        Ok(path)
+    }
+
+    /// Exit, but keep hold of the lock so that another Playspace can be
+    /// entered straight away
+    pub(crate) fn exit_retaining_lock(mut self) -> (ExitReport, Lock) {
+        let report = unsafe { self.exit_cleanup() };
+        let Hold::Lock(lock) = (unsafe { ManuallyDrop::take(&mut self.lock) }) else {
+            unreachable!("sequence phases always hold the lock")
+        };
+        self.arrive_at_barriers();
+
+        // As in `exit`, nothing is left to drop
+        std::mem::forget(self);
+
+        (report, lock)
+    }
+
+    unsafe fn exit_internal(&mut self) -> ExitReport {
+        let report = self.exit_cleanup();
+
+        // This must be done last
+        if let Hold::Lock(lock) = ManuallyDrop::take(&mut self.lock) {
+            mutex::release(lock);
+        }
+
+        // Now that the Playspace is free, anyone waiting can be released
+        self.arrive_at_barriers();
+
+        report
+    }
+
+    fn arrive_at_barriers(&mut self) {
+        for barrier in std::mem::take(self.exit_barriers.get_mut()) {
+            barrier.arrive();
+        }
+    }
+
+    /// Run `f` in the Playspace, then exit it.
+    ///
+    /// If `f` panics, the Playspace is exited (as for a panicking thread)
+    /// before the panic is resumed, so that the environment and working
+    /// directory are already restored for anything that catches it.
+    fn run_scoped<R>(mut self, f: impl FnOnce(&mut Self) -> R) -> (R, Result<(), ExitError>) {
+        let pin = self.changes_directory().then(current::pin);
+        // The Playspace is never used again after a panic, so can't be seen
+        // in a broken state
+        let out = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut self)));
+        drop(pin);
+        match out {
+            Ok(out) => (out, self.exit()),
+            Err(panic) => {
+                self.panicked = true;
+                let _ignored = self.exit();
+                std::panic::resume_unwind(panic)
+            }
+        }
+    }
+
+    /// Everything involved in exiting, except releasing the lock
+    unsafe fn exit_cleanup(&mut self) -> ExitReport {
+        let mut report = ExitReport::default();
+
+        // Checks must see the Playspace as it was left, so run them first
+        let checks = std::mem::take(self.exit_checks.get_mut());
+        let failures: Vec<String> = checks
+            .into_iter()
+            .filter_map(|check| check(self).err())
+            .collect();
+        let checks_failed = !failures.is_empty();
+        report.push(ExitStep::Checks, || {
+            if checks_failed {
+                Err(StepFailure::Checks(failures))
+            } else {
+                Ok(())
+            }
+        });
+
+        let persisted = std::mem::take(self.persisted.get_mut());
+        if !persisted.is_empty() {
+            report.push(ExitStep::Persist, || {
+                persisted
+                    .iter()
+                    .try_for_each(|persist| persist.copy_from(self.directory()))
+                    .map_err(StepFailure::Io)
+            });
+        }
+
+        let changes_directory = self.changes_directory();
+        let in_order = match &*self.lock {
+            Hold::Lock(_lock) => {
+                current::exit();
+                true
+            }
+            Hold::Nested => current::exit_nested(self.directory()),
+            Hold::Unlocked => true,
+        };
+        drop(std::mem::take(self.scratchpad.get_mut()));
+        drop(self.origin_link.take());
+        let retention = self.retention.take();
+        let quarantine = self.quarantine.take();
+        drop(self.clock.take());
+        let canonical_directory = std::mem::take(&mut self.canonical_directory);
+
+        // Infallible, do this first
+        let saved_environment = std::mem::take(&mut self.saved_environment);
+        let saved_current_dir = self.saved_current_dir.take();
+        let fallback_dir = self.fallback_dir.take();
+        let outside = fallback_dir
+            .clone()
+            .or_else(|| canonical_directory.parent().map(Path::to_owned));
+        // Without changing directory, neither was ever changed
+        if changes_directory {
+            Self::restore_state(
+                &mut report,
+                in_order,
+                &saved_environment,
+                saved_current_dir,
+                fallback_dir,
+            );
         }
+
+        #[cfg(all(windows, feature = "windows-acl"))]
+        report.push(ExitStep::Permissions, || {
+            acl::restore(std::mem::take(self.acl_denied.get_mut())).map_err(StepFailure::Io)
+        });
+
+        // A working directory inside the Playspace keeps it from being removed
+        // on Windows, e.g. if it couldn't be restored or was never changed
+        report.cwd_inside = Self::move_out(&canonical_directory, outside);
+        drop(canonical_directory);
+
+        // N.B. `ManuallyDrop::take` makes a bitwise copy, but since `directory` only
+        // contains a `Box` this is fine.
+        let directory = ManuallyDrop::take(&mut self.directory);
+        let retain_because = if std::thread::panicking() || self.panicked {
+            Some("panicked")
+        } else if checks_failed {
+            Some("exit checks failed")
+        } else {
+            None
+        };
+        report.push(ExitStep::TempDir, || {
+            let path = directory.path().to_owned();
+            match (retention, retain_because) {
+                _ if self.persist => {
+                    let _path = directory.keep();
+                    Ok(())
+                }
+                (Some(retention), Some(reason)) => {
+                    retention.record(&directory.keep(), reason);
+                    Ok(())
+                }
+                _ => match quarantine {
+                    Some(quarantine) => quarantine.dispose(directory, self.removal),
+                    None => self.removal.remove_dir_all(&directory.keep()),
+                },
+            }
+            .map_err(|source| StepFailure::Remove {
+                blocking: walk::blocking(&path),
+                source,
+            })
+        });
+
+        trace::event!(
+            tracing::Level::INFO,
+            clean = report.is_ok(),
+            "exited Playspace"
+        );
+        report
     }
 
-    fn restore_environment(&mut self) {
-        for (variable, _value) in std::env::vars_os() {
-            match self.saved_environment.remove(&variable) {
-                Some(saved_value) => std::env::set_var(&variable, saved_value),
-                None => std::env::remove_var(&variable),
+    /// Restore the environment and working directory saved on entry, unless
+    /// exited out of order, when they now belong to another Playspace
+    fn restore_state(
+        report: &mut ExitReport,
+        in_order: bool,
+        saved_environment: &EnvSnapshot,
+        saved_current_dir: Option<PathBuf>,
+        fallback_dir: Option<PathBuf>,
+    ) {
+        if !in_order {
+            report.push(ExitStep::Environment, || Err(StepFailure::OutOfOrder));
+            return;
+        }
+        let diff = saved_environment.restore();
+        report.push(ExitStep::Environment, || {
+            if diff.is_empty() {
+                Ok(())
+            } else {
+                Err(StepFailure::Environment(diff))
             }
+        });
+
+        report.push(ExitStep::WorkingDir, || {
+            Self::restore_directory(saved_current_dir, fallback_dir).map_err(StepFailure::Io)
+        });
+    }
+
+    /// Move the working directory to `outside` if it is inside the Playspace,
+    /// returning where it was
+    fn move_out(canonical_directory: &Path, outside: Option<PathBuf>) -> Option<PathBuf> {
+        let cwd = std::env::current_dir()
+            .and_then(|cwd| cwd.canonicalize())
+            .ok()
+            .filter(|cwd| cwd.starts_with(canonical_directory))?;
+        if let Some(outside) = outside {
+            trace::event!(
+                tracing::Level::WARN,
+                cwd = %cwd.display(),
+                outside = %outside.display(),
+                "working directory still inside Playspace, moving out"
+            );
+            let _ignored = std::env::set_current_dir(outside);
         }
-        for (removed_variable, value) in self.saved_environment.drain() {
-            std::env::set_var(removed_variable, value);
+        Some(cwd)
+    }
+
+    fn restore_directory(
+        saved_current_dir: Option<PathBuf>,
+        fallback_dir: Option<PathBuf>,
+    ) -> Result<(), std::io::Error> {
+        let restored = if let Some(working_dir) = saved_current_dir {
+            std::env::set_current_dir(working_dir)
+        } else {
+            Err(std::io::Error::other("no previous working directory"))
+        };
+        match (restored, fallback_dir) {
+            (Err(_error), Some(fallback_dir)) => {
+                trace::event!(
+                    tracing::Level::WARN,
+                    fallback = %fallback_dir.display(),
+                    "previous working directory is gone, using fallback"
+                );
+                std::env::set_current_dir(fallback_dir)
+            }
+            (restored, _fallback_dir) => restored,
         }
     }
 }
@@ -755,6 +2298,23 @@ impl Playspace {
         Ok(out)
     }
 
+    /// Convenience combination of [`scoped_async`][Playspace::scoped_async]
+    /// with implicit [`write_files`][Playspace::write_files].
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn scoped_with_files_async<I, P, C, R, F>(files: I, f: F) -> Result<R, SpaceError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+        F: for<'a> FnOnce(&'a mut Self) -> Pin<Box<dyn Future<Output = R> + 'a>>,
+    {
+        let mut space = Self::with_files_async(files).await?;
+        let out = f(&mut space).await;
+        space.exit()?;
+
+        Ok(out)
+    }
+
     /// Async version of [`new`][Playspace::new]. Prefer
     /// [`scoped_async`][Playspace::scoped_async] where possible.
     ///
@@ -768,6 +2328,13 @@ impl Playspace {
     /// Returns [`SpaceError::StdIo`] if there were any system IO errors
     /// entering the Playspace.
     ///
+    /// # Cancellation
+    ///
+    /// Cancel-safe: if the future is dropped before it completes (e.g. by a
+    /// timeout or `select!`), the Playspace was never entered and its place
+    /// in the queue is given up, so nothing needs cleaning up. Once it has
+    /// completed, dropping the returned `Playspace` exits it as usual.
+    ///
     /// # Example
     ///
     /// ```rust
@@ -781,7 +2348,34 @@ impl Playspace {
     /// # };
     /// ```
     pub async fn new_async() -> Result<Self, SpaceError> {
-        Ok(Self::from_lock(MUTEX.lock().await)?)
+        let hold = match Hold::nested() {
+            Some(hold) => hold,
+            None => Hold::Lock(lock_async().await),
+        };
+        Self::from_hold(hold)
+    }
+
+    /// Wait until no Playspace is entered, without entering one.
+    ///
+    /// Resolves once every Playspace entered (or waiting to be) when it was
+    /// called has exited, so harness code can sequence work around them. By
+    /// the time the caller runs, another Playspace may have been entered, so
+    /// use [`new_async`][Playspace::new_async] to actually keep others out.
+    ///
+    /// Cancel-safe, just like [`new_async`][Playspace::new_async].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # async {
+    /// Playspace::wait_until_free().await;
+    /// assert!(!Playspace::is_active());
+    /// # };
+    /// ```
+    pub async fn wait_until_free() {
+        // Shared access is only given once no Playspace holds the lock
+        drop(read_async().await);
     }
 
     /// Convenience combination of [`new_async`][Playspace::new_async] followed
@@ -794,16 +2388,135 @@ impl Playspace {
         K: AsRef<OsStr>,
         V: AsRef<OsStr>,
     {
-        let out = Self::new()?;
+        let out = Self::new_async().await?;
         out.set_envs(vars);
         Ok(out)
     }
+
+    /// Convenience combination of [`new_async`][Playspace::new_async] followed
+    /// by [`write_files`][Playspace::write_files]. Prefer [`scoped_with_files_async`][Playspace::scoped_with_files_async]
+    /// where possible.
+    #[allow(clippy::missing_errors_doc)]
+    pub async fn with_files_async<I, P, C>(files: I) -> Result<Self, SpaceError>
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<Path>,
+        C: AsRef<[u8]>,
+    {
+        let out = Self::new_async().await?;
+        out.write_files(files)?;
+        Ok(out)
+    }
+}
+
+/// Create the directory for a Playspace, in the configured root if any
+pub(crate) fn default_directory() -> Result<TempDir, std::io::Error> {
+    match &config::get().temp_root {
+        Some(root) => {
+            std::fs::create_dir_all(root)?;
+            tempfile::tempdir_in(root)
+        }
+        None => tempdir(),
+    }
 }
 
 impl Drop for Playspace {
     fn drop(&mut self) {
-        let _result = unsafe { self.exit_internal() };
+        let on_drop_error = self.on_drop_error;
+        let report = unsafe { self.exit_internal() };
+        if let Err(error) = report.into_result() {
+            on_drop_error.handle(&error);
+        }
+    }
+}
+
+/// What to do with errors exiting a Playspace which is dropped, rather than
+/// [`exit`][Playspace::exit]ed
+///
+/// Set with [`PlayspaceBuilder::on_drop_error`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DropErrorPolicy {
+    /// Silently ignore the error.
+    #[default]
+    Ignore,
+    /// Print the error to stderr.
+    Log,
+    /// Panic with the error, unless the thread is already panicking (in which
+    /// case it is printed to stderr instead).
+    Panic,
+}
+
+impl DropErrorPolicy {
+    fn handle(self, error: &ExitError) {
+        match self {
+            Self::Ignore => (),
+            Self::Panic if !std::thread::panicking() => {
+                panic!("error exiting dropped Playspace: {error}")
+            }
+            Self::Log | Self::Panic => {
+                eprintln!("playspace: error exiting dropped Playspace: {error}");
+            }
+        }
+    }
+}
+
+/// Lexically resolve `.` and `..` components
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Whether an absolute, normalized `path` resolves to somewhere inside the
+/// Playspace `root` (whose canonical form is `canonical_root`), once any
+/// symlinks are followed
+fn resolves_inside(
+    path: &Path,
+    root: &Path,
+    canonical_root: &Path,
+) -> Result<bool, std::io::Error> {
+    // Bounded in case of symlink loops, as the OS does
+    const MAX_SYMLINKS: usize = 40;
+
+    let mut path = path.to_owned();
+    for _ in 0..MAX_SYMLINKS {
+        // The closest ancestor which exists (or is a symlink, even if broken)
+        let Some((ancestor, metadata)) = path.ancestors().find_map(|ancestor| {
+            let metadata = ancestor.symlink_metadata().ok()?;
+            Some((ancestor, metadata))
+        }) else {
+            return Ok(false);
+        };
+
+        // Fast path for the common cases of (new) files and directories
+        // directly in the root, which avoids canonicalizing
+        if ancestor == root || (!metadata.is_symlink() && ancestor.parent() == Some(root)) {
+            return Ok(true);
+        }
+
+        match ancestor.canonicalize() {
+            Ok(canonical) => return Ok(canonical.starts_with(canonical_root)),
+            // A broken symlink: writing through it would create its target,
+            // so check where that is
+            Err(_) if metadata.is_symlink() => {
+                let target = std::fs::read_link(ancestor)?;
+                let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+                let parent = ancestor.parent().unwrap_or(ancestor);
+                path = normalize(&parent.join(target).join(rest));
+            }
+            Err(error) => return Err(error),
+        }
     }
+    Ok(false)
 }
 
 /// General error
@@ -813,13 +2526,58 @@ pub enum SpaceError {
     /// Creating either flavour while any other space exists is an error.
     #[error("already in a Playspace")]
     AlreadyInSpace,
+    /// Attempted to block waiting for a Playspace from within an async
+    /// runtime, which would stall (or deadlock) the runtime. Use the `*_async`
+    /// constructors (e.g. [`new_async`][Playspace::new_async]) instead.
+    ///
+    /// Only returned when the `async` feature is enabled and the blocking
+    /// constructor is called from a thread inside a tokio runtime.
+    #[error("cannot block waiting for a Playspace from within an async runtime, use the async constructors instead")]
+    WouldBlockRuntime,
+    /// Attempted to block waiting for a Playspace on a thread which already
+    /// holds one, which would wait forever.
+    ///
+    /// A Playspace is held by the thread which entered it, until
+    /// [detached][Playspace::detach]. `Playspace` is `Send`, but one moved
+    /// to another thread without being detached is still held by the thread
+    /// which entered it, so waiting there is reported even though the other
+    /// thread might have exited it in time. Use
+    /// [`detach`][Playspace::detach] and
+    /// [`attach`][DetachedPlayspace::attach] to hand a Playspace between
+    /// threads.
+    ///
+    /// Either exit the first Playspace, or allow
+    /// [nesting][config::Config::allow_nesting].
+    #[error("cannot wait for a Playspace from a thread holding one, it would deadlock")]
+    WouldDeadlock,
+    /// The environment or working directory was changed outside of any
+    /// Playspace, see [`Playspace::detect_contamination`].
+    #[error("contamination outside of a Playspace: {0}")]
+    Contaminated(Contamination),
     #[error("error exiting Playspace")]
     ExitError(#[from] ExitError),
+    /// Error writing files to the Playspace while entering it.
+    #[error("error writing files to Playspace")]
+    WriteError(#[from] WriteError),
     /// A bubbled-up error from [`std::io`] functions.
     #[error(transparent)]
     StdIo(#[from] std::io::Error),
 }
 
+/// Error from a fallible closure run in a Playspace, or from the Playspace
+/// itself
+///
+/// Returned by [`scoped_fallible`][Playspace::scoped_fallible].
+#[derive(Debug, thiserror::Error)]
+pub enum ScopedError<E> {
+    /// Error entering or exiting the Playspace.
+    #[error(transparent)]
+    Space(#[from] SpaceError),
+    /// Error returned by the closure.
+    #[error(transparent)]
+    Closure(E),
+}
+
 /// Error writing to filesystem in Playspace
 #[derive(Debug, thiserror::Error)]
 pub enum WriteError {
@@ -827,11 +2585,268 @@ pub enum WriteError {
     /// The inner value is the path that was attempted to write to.
     #[error("attempt to write outside Playspace ({0})")]
     OutsidePlayspace(PathBuf),
-    /// A bubbled-up error from [`std::io`] functions.
+    /// The parent directory of the path does not exist.
+    #[error("parent directory of {} does not exist", .path.display())]
+    ParentMissing {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Not permitted to access the path.
+    #[error("permission denied for {}", .path.display())]
+    PermissionDenied {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The path already exists.
+    #[error("{} already exists", .path.display())]
+    AlreadyExists {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Any other error from [`std::io`] functions on the path.
+    #[error("IO error on {}", .path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A bubbled-up error from [`std::io`] functions, not about any one path.
     #[error(transparent)]
     StdIo(#[from] std::io::Error),
 }
 
+impl WriteError {
+    /// Classify an error from an operation on `path`
+    fn io(path: &Path, source: std::io::Error) -> Self {
+        let path = path.to_owned();
+        match source.kind() {
+            std::io::ErrorKind::PermissionDenied => Self::PermissionDenied { path, source },
+            std::io::ErrorKind::AlreadyExists => Self::AlreadyExists { path, source },
+            std::io::ErrorKind::NotFound
+                if path.parent().is_some_and(|parent| !parent.exists()) =>
+            {
+                Self::ParentMissing { path, source }
+            }
+            _ => Self::Io { path, source },
+        }
+    }
+}
+
+/// Changes back to the previous working directory when dropped
+///
+/// Returned by [`Playspace::dir_scope`].
+#[must_use = "the working directory is restored as soon as the guard is dropped"]
+pub struct DirScopeGuard<'a> {
+    _space: &'a Playspace,
+    previous: PathBuf,
+}
+
+impl Drop for DirScopeGuard<'_> {
+    fn drop(&mut self) {
+        let _ignored = std::env::set_current_dir(&self.previous);
+    }
+}
+
+/// Summary of the state of a Playspace when it exited
+///
+/// Returned by [`exit_report`][Playspace::exit_report] and
+/// [`exit_with_report`][Playspace::exit_with_report].
+#[derive(Debug, Default)]
+pub struct ExitReport {
+    leftover: Vec<PathBuf>,
+    cwd_inside: Option<PathBuf>,
+    steps: Vec<(ExitStep, Result<(), StepFailure>)>,
+}
+
+impl ExitReport {
+    /// Every file, directory and symlink that remained in the Playspace,
+    /// relative to its root, in sorted order.
+    #[must_use]
+    pub fn leftover(&self) -> &[PathBuf] {
+        &self.leftover
+    }
+
+    /// The working directory, if it was still inside the Playspace just
+    /// before the Playspace was removed.
+    ///
+    /// This happens if the previous working directory couldn't be returned
+    /// to, or if a Playspace which doesn't [change directory][PlayspaceBuilder::chdir]
+    /// was moved into anyway. Since it would keep the Playspace from being
+    /// removed on Windows, the working directory is then moved out first: to
+    /// the [fallback directory][PlayspaceBuilder::fallback_directory] if
+    /// there is one, or else to the directory containing the Playspace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// let original = std::env::current_dir().unwrap();
+    /// let space = Playspace::builder().chdir(false).build().unwrap();
+    /// std::env::set_current_dir(space.directory()).unwrap();
+    ///
+    /// let report = space.exit_report();
+    /// assert!(report.is_ok());
+    /// assert!(report.cwd_left_inside().is_some());
+    /// # std::env::set_current_dir(original).unwrap();
+    /// ```
+    #[must_use]
+    pub fn cwd_left_inside(&self) -> Option<&Path> {
+        self.cwd_inside.as_deref()
+    }
+
+    /// Whether the Playspace was empty when it exited.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.leftover.is_empty()
+    }
+
+    /// The outcome of every step taken when exiting, in the order they were
+    /// taken.
+    pub fn steps(&self) -> &[(ExitStep, Result<(), StepFailure>)] {
+        &self.steps
+    }
+
+    /// Every step that failed, in the order they were taken.
+    pub fn failures(&self) -> impl Iterator<Item = (ExitStep, &StepFailure)> {
+        self.steps
+            .iter()
+            .filter_map(|(step, result)| result.as_ref().err().map(|failure| (*step, failure)))
+    }
+
+    /// Whether every step succeeded.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.steps.iter().all(|(_step, result)| result.is_ok())
+    }
+
+    /// Convert into the single most important [`ExitError`], if any step
+    /// failed.
+    ///
+    /// # Errors
+    ///
+    /// Failed [exit checks][ExitStep::Checks] take precedence, then failing
+    /// to return to the previous working directory (along with any failure
+    /// removing the temporary directory), then removing the temporary
+    /// directory alone, then any other step.
+    pub fn into_result(self) -> Result<Self, ExitError> {
+        let mut passed = Vec::with_capacity(self.steps.len());
+        let mut checks = None;
+        let mut working_dir = None;
+        let mut temp_dir = None;
+        let mut listing = None;
+        let mut other = None;
+        for (step, result) in self.steps {
+            match (step, result) {
+                (_, Ok(())) => passed.push((step, Ok(()))),
+                (_, Err(StepFailure::Checks(failures))) => checks = Some(failures),
+                (ExitStep::WorkingDir, Err(StepFailure::Io(source))) => working_dir = Some(source),
+                (ExitStep::TempDir, Err(StepFailure::Remove { source, blocking })) => {
+                    temp_dir = Some((source, blocking));
+                }
+                (ExitStep::Leftover, Err(StepFailure::Io(source))) => {
+                    listing = Some((source, Vec::new()));
+                }
+                (step, Err(source)) => {
+                    other.get_or_insert((step, source));
+                }
+            }
+        }
+        let temp_dir = temp_dir.or(listing);
+
+        Err(if let Some(failures) = checks {
+            ExitError::VerificationFailed { failures }
+        } else if let Some(source) = working_dir {
+            ExitError::WorkingDirChangeFailed {
+                source,
+                temp_dir: temp_dir.map(|(temp_dir, _blocking)| temp_dir),
+            }
+        } else if let Some((source, blocking)) = temp_dir {
+            ExitError::TempDirRemoveFailed { source, blocking }
+        } else if let Some((step, source)) = other {
+            ExitError::StepFailed { step, source }
+        } else {
+            return Ok(Self {
+                leftover: self.leftover,
+                cwd_inside: self.cwd_inside,
+                steps: passed,
+            });
+        })
+    }
+
+    fn push(&mut self, step: ExitStep, run: impl FnOnce() -> Result<(), StepFailure>) {
+        let result = run();
+        #[cfg(feature = "tracing")]
+        if let Err(failure) = &result {
+            trace::event!(tracing::Level::WARN, ?step, %failure, "exit step failed");
+        }
+        self.steps.push((step, result));
+    }
+}
+
+/// A step taken when exiting a Playspace
+///
+/// See [`ExitReport::steps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ExitStep {
+    /// Running the [exit checks][Playspace::verify_on_exit].
+    Checks,
+    /// Copying files out with [`persist_to`][Playspace::persist_to].
+    Persist,
+    /// Restoring the environment variables.
+    Environment,
+    /// Returning to the previous working directory.
+    WorkingDir,
+    /// Restoring write permissions denied with `deny_write`.
+    Permissions,
+    /// Removing (or retaining) the temporary directory.
+    TempDir,
+    /// Listing what was [left in the Playspace][ExitReport::leftover].
+    Leftover,
+}
+
+/// Why a step of exiting a Playspace failed
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum StepFailure {
+    /// One or more [exit checks][Playspace::verify_on_exit] failed.
+    #[error("exit checks failed: {}", .0.join("; "))]
+    Checks(Vec<String>),
+    /// The environment still differed from the one saved on entry after it
+    /// was restored.
+    #[error("environment was not fully restored")]
+    Environment(EnvDiff),
+    /// A [nested][config::Config::allow_nesting] Playspace was exited after its
+    /// parent or before a Playspace nested in it, so the environment and
+    /// working directory were left as they were rather than restored over
+    /// whichever Playspace is current.
+    #[error(
+        "nested Playspace exited out of order, environment and working directory not restored"
+    )]
+    OutOfOrder,
+    /// The temporary directory could not be removed.
+    #[error("{}", remove_failed_message(blocking))]
+    Remove {
+        source: std::io::Error,
+        /// What was left in the directory, see
+        /// [`ExitError::TempDirRemoveFailed`].
+        blocking: Vec<PathBuf>,
+    },
+    /// A bubbled-up error from [`std::io`] functions.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+fn remove_failed_message(blocking: &[PathBuf]) -> String {
+    if blocking.is_empty() {
+        return "could not remove temporary directory".to_owned();
+    }
+    let paths: Vec<_> = blocking.iter().map(|path| path.to_string_lossy()).collect();
+    format!(
+        "could not remove temporary directory, blocked by: {}",
+        paths.join(", ")
+    )
+}
+
 #[derive(Debug)]
 pub enum ExitError {
     WorkingDirChangeFailed {
@@ -840,6 +2855,17 @@ pub enum ExitError {
     },
     TempDirRemoveFailed {
         source: std::io::Error,
+        /// Everything left in the temporary directory which has nothing left
+        /// inside it, e.g. the files still held open. Empty if that couldn't
+        /// be found out.
+        blocking: Vec<PathBuf>,
+    },
+    VerificationFailed {
+        failures: Vec<String>,
+    },
+    StepFailed {
+        step: ExitStep,
+        source: StepFailure,
     },
 }
 
@@ -848,19 +2874,26 @@ impl Display for ExitError {
         match self {
             Self::WorkingDirChangeFailed { temp_dir, .. } => match temp_dir {
                 None => write!(f, "could not change working directory"),
-                Some(temp) => write!(f, "could not change working directory and also encoutered an error removing temporary directory ({})", temp)
+                Some(temp) => write!(f, "could not change working directory and also encoutered an error removing temporary directory ({temp})")
             },
-            Self::TempDirRemoveFailed { .. } => write!(f, "could not remove temporary directory"),
+            Self::TempDirRemoveFailed { blocking, .. } => {
+                f.write_str(&remove_failed_message(blocking))
+            }
+            Self::VerificationFailed { failures } => {
+                write!(f, "exit checks failed: {}", failures.join("; "))
+            }
+            Self::StepFailed { step, .. } => write!(f, "exit step {step:?} failed"),
         }
     }
 }
 
 impl std::error::Error for ExitError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(match self {
-            Self::WorkingDirChangeFailed { source, .. } | Self::TempDirRemoveFailed { source } => {
-                source
-            }
-        })
+        match self {
+            Self::WorkingDirChangeFailed { source, .. }
+            | Self::TempDirRemoveFailed { source, .. } => Some(source),
+            Self::StepFailed { source, .. } => Some(source),
+            Self::VerificationFailed { .. } => None,
+        }
     }
 }
@@ -0,0 +1,78 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    fmt::Display,
+    io::{self, Write},
+    time::UNIX_EPOCH,
+};
+
+use crate::Playspace;
+
+/// Name of the log file written by [`Playspace::log`], in the Playspace root.
+pub const LOG_FILE: &str = "playspace.log";
+
+/// Append a formatted line to the Playspace's log, see [`Playspace::log`].
+///
+/// Takes the Playspace followed by the same arguments as [`format!`], and
+/// evaluates to the result of the write.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::{space_log, Playspace};
+/// Playspace::scoped(|space| {
+///     let attempts = 3;
+///     space_log!(space, "retrying after {attempts} attempts").unwrap();
+/// }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! space_log {
+    ($space:expr, $($arg:tt)*) => {
+        $space.log(format_args!($($arg)*))
+    };
+}
+
+impl Playspace {
+    /// Append a timestamped line to [`playspace.log`][LOG_FILE] in the
+    /// Playspace root.
+    ///
+    /// This is a simple breadcrumb trail that lives alongside the files a test
+    /// produces, so it is kept along with them. Each line is prefixed with the
+    /// time since the Unix epoch, in seconds, according to the
+    /// Playspace's [clock][Playspace::now]. Lines are written with a single
+    /// append, so logging from several threads at once won't interleave them.
+    /// The log file is not counted as left over by
+    /// [`exit_with_report`][Playspace::exit_with_report].
+    ///
+    /// Usually called via the [`space_log!`] macro.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.log("starting server").unwrap();
+    ///     let log = std::fs::read_to_string(playspace::LOG_FILE).unwrap();
+    ///     assert!(log.ends_with("starting server\n"));
+    /// }).unwrap();
+    /// ```
+    pub fn log(&self, message: impl Display) -> Result<(), io::Error> {
+        let timestamp = self.now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let line = format!(
+            "[{}.{:03}] {message}\n",
+            timestamp.as_secs(),
+            timestamp.subsec_millis()
+        );
+
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.directory().join(LOG_FILE))?
+            .write_all(line.as_bytes())
+    }
+}
@@ -0,0 +1,61 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Metrics about how Playspaces are used.
+//!
+//! Since only one Playspace can exist at a time, tests using them run one
+//! after another. If a test suite slows down, a hook set with
+//! [`on_lock_wait`] can find which tests spend longest waiting for others.
+//! Each Playspace also records its own wait, see
+//! [`Playspace::lock_wait`][crate::Playspace::lock_wait].
+//!
+//! # Example
+//!
+//! ```rust
+//! # use playspace::{metrics, Playspace};
+//! # use std::{sync::atomic::{AtomicU64, Ordering}, time::Duration};
+//! static TOTAL_MICROS: AtomicU64 = AtomicU64::new(0);
+//!
+//! metrics::on_lock_wait(|waited| {
+//!     let micros = u64::try_from(waited.as_micros()).unwrap_or(u64::MAX);
+//!     TOTAL_MICROS.fetch_add(micros, Ordering::Relaxed);
+//!     if waited > Duration::from_secs(1) {
+//!         let thread = std::thread::current();
+//!         eprintln!("{:?} waited {waited:?} for a Playspace", thread.name());
+//!     }
+//! });
+//!
+//! Playspace::scoped(|_space| {}).unwrap();
+//! ```
+
+use std::{sync::Arc, time::Duration};
+
+use parking_lot::RwLock;
+
+type Hook = Arc<dyn Fn(Duration) + Send + Sync>;
+
+static LOCK_WAIT_HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Call `hook` with how long every Playspace waited for the global lock,
+/// replacing any previous hook.
+///
+/// The hook is called on the waiting thread (so can use e.g.
+/// [`std::thread::current`] to find the test's name) just after the lock is
+/// taken, and is not called if the wait fails. It must not create a
+/// Playspace.
+pub fn on_lock_wait(hook: impl Fn(Duration) + Send + Sync + 'static) {
+    *LOCK_WAIT_HOOK.write() = Some(Arc::new(hook));
+}
+
+/// Stop calling any hook set with [`on_lock_wait`].
+pub fn clear_lock_wait_hook() {
+    *LOCK_WAIT_HOOK.write() = None;
+}
+
+pub(crate) fn report_lock_wait(waited: Duration) {
+    // Don't hold the lock while calling the hook, in case it replaces itself
+    let hook = LOCK_WAIT_HOOK.read().clone();
+    if let Some(hook) = hook {
+        hook(waited);
+    }
+}
@@ -1,49 +1,204 @@
 //  SPDX-License-Identifier: MIT OR Apache-2.0
 //  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
 
+use std::time::{Duration, Instant};
+
+use parking_lot::const_mutex;
+
 pub(crate) use internal::*;
 
+use crate::SpaceError;
+
+/// What a Playspace holds, which lets it change the working directory and
+/// environment
+pub(crate) enum Hold {
+    /// The lock itself
+    Lock(Lock),
+    /// Nothing, since it is nested in the Playspace held by this thread,
+    /// which becomes the current one again on exit
+    Nested,
+    /// Nothing, since nothing process-wide is changed
+    Unlocked,
+}
+
+impl Hold {
+    /// Take the lock with `lock`, unless nesting is allowed and this thread
+    /// already holds it
+    pub(crate) fn take(
+        lock: impl FnOnce() -> Result<Lock, SpaceError>,
+    ) -> Result<Self, SpaceError> {
+        match Self::nested() {
+            Some(hold) => Ok(hold),
+            None => lock().map(Self::Lock),
+        }
+    }
+
+    /// Nest in the current Playspace, if nesting is allowed and this thread
+    /// holds it
+    pub(crate) fn nested() -> Option<Self> {
+        if !crate::config::get().nesting {
+            return None;
+        }
+        crate::current::held_here().map(|_parent| Self::Nested)
+    }
+}
+
+/// Blocking for the lock would never finish if this thread holds it: either
+/// a Playspace entered here and not detached, or a scope, see
+/// [`pin`][crate::current::pin]
+pub(crate) fn check_deadlock() -> Result<(), SpaceError> {
+    if crate::current::pinned_here() || crate::current::held_here().is_some() {
+        Err(SpaceError::WouldDeadlock)
+    } else {
+        Ok(())
+    }
+}
+
 /// Type used to guarantee that locked are only creatable from this crate
 pub(crate) struct LockType();
 
-#[cfg(all(not(feature = "async")))]
+/// How long the current holder of the lock waited for it, until taken by the
+/// Playspace it was for
+static WAITED: parking_lot::Mutex<Duration> = const_mutex(Duration::ZERO);
+
+/// Take the lock with `lock`, recording how long it took
+fn timed<L>(lock: impl FnOnce() -> L) -> L {
+    let start = Instant::now();
+    let out = lock();
+    record_wait(start.elapsed());
+    out
+}
+
+fn record_wait(waited: Duration) {
+    *WAITED.lock() = waited;
+    crate::metrics::report_lock_wait(waited);
+}
+
+/// How long the lock just taken was waited for. Zero if it wasn't waited
+/// for, or this was already called since.
+pub(crate) fn take_wait() -> Duration {
+    std::mem::take(&mut *WAITED.lock())
+}
+
+#[cfg(not(feature = "async"))]
 mod internal {
-    use parking_lot::const_mutex;
+    use parking_lot::const_rwlock;
 
-    use super::LockType;
+    use super::{check_deadlock, timed, LockType};
+    use crate::SpaceError;
 
-    pub(crate) static MUTEX: Mutex = const_mutex(LockType());
+    /// Taken exclusively by a Playspace, and shared by read-only spaces
+    pub(crate) static LOCK: RwLock = const_rwlock(LockType());
 
-    pub(crate) type Mutex = parking_lot::Mutex<LockType>;
-    pub(crate) type Lock = parking_lot::MutexGuard<'static, LockType>;
+    pub(crate) type RwLock = parking_lot::RwLock<LockType>;
+    pub(crate) type Lock = parking_lot::RwLockWriteGuard<'static, LockType>;
+    pub(crate) type ReadLock = parking_lot::RwLockReadGuard<'static, LockType>;
 
     #[inline]
-    pub(crate) fn blocking_lock() -> Lock {
-        MUTEX.lock()
+    pub(crate) fn blocking_lock() -> Result<Lock, SpaceError> {
+        check_deadlock()?;
+        Ok(timed(|| crate::trace::lock(|| LOCK.write())))
     }
 
     #[inline]
     pub(crate) fn try_lock() -> Option<Lock> {
-        MUTEX.try_lock()
+        LOCK.try_write()
+    }
+
+    /// Release the lock, handing it straight to the longest waiter if
+    /// [configured][crate::config::Config::fair_queuing]
+    pub(crate) fn release(lock: Lock) {
+        if crate::config::get().fair {
+            parking_lot::RwLockWriteGuard::unlock_fair(lock);
+        }
+        // Otherwise just dropped
+    }
+
+    /// As for [`release`], for shared access
+    pub(crate) fn release_read(lock: ReadLock) {
+        if crate::config::get().fair {
+            parking_lot::RwLockReadGuard::unlock_fair(lock);
+        }
+        // Otherwise just dropped
+    }
+
+    #[inline]
+    pub(crate) fn blocking_read() -> Result<ReadLock, SpaceError> {
+        check_deadlock()?;
+        Ok(crate::trace::lock(|| LOCK.read()))
+    }
+
+    #[inline]
+    pub(crate) fn try_read() -> Option<ReadLock> {
+        LOCK.try_read()
     }
 }
 
 #[cfg(feature = "async")]
 mod internal {
-    use super::LockType;
+    use super::{check_deadlock, record_wait, timed, LockType};
+    use crate::SpaceError;
 
-    pub(crate) static MUTEX: Mutex = Mutex::const_new(LockType());
+    /// Taken exclusively by a Playspace, and shared by read-only spaces
+    pub(crate) static LOCK: RwLock = RwLock::const_new(LockType());
 
-    pub(crate) type Mutex = tokio::sync::Mutex<LockType>;
-    pub(crate) type Lock = tokio::sync::MutexGuard<'static, LockType>;
+    pub(crate) type RwLock = tokio::sync::RwLock<LockType>;
+    pub(crate) type Lock = tokio::sync::RwLockWriteGuard<'static, LockType>;
+    pub(crate) type ReadLock = tokio::sync::RwLockReadGuard<'static, LockType>;
 
     #[inline]
-    pub(crate) fn blocking_lock() -> Lock {
-        MUTEX.blocking_lock()
+    pub(crate) fn blocking_lock() -> Result<Lock, SpaceError> {
+        check_runtime()?;
+        check_deadlock()?;
+        Ok(timed(|| crate::trace::lock(|| LOCK.blocking_write())))
     }
 
     #[inline]
     pub(crate) fn try_lock() -> Option<Lock> {
-        MUTEX.try_lock().ok()
+        LOCK.try_write().ok()
+    }
+
+    pub(crate) async fn lock_async() -> Lock {
+        let start = std::time::Instant::now();
+        let out = crate::trace::lock_async(LOCK.write()).await;
+        record_wait(start.elapsed());
+        out
+    }
+
+    /// Release the lock. Tokio's lock is always handed to the longest waiter.
+    #[inline]
+    pub(crate) fn release(lock: Lock) {
+        drop(lock);
+    }
+
+    /// As for [`release`], for shared access
+    #[inline]
+    pub(crate) fn release_read(lock: ReadLock) {
+        drop(lock);
+    }
+
+    #[inline]
+    pub(crate) fn blocking_read() -> Result<ReadLock, SpaceError> {
+        check_runtime()?;
+        check_deadlock()?;
+        Ok(crate::trace::lock(|| LOCK.blocking_read()))
+    }
+
+    #[inline]
+    pub(crate) fn try_read() -> Option<ReadLock> {
+        LOCK.try_read().ok()
+    }
+
+    pub(crate) async fn read_async() -> ReadLock {
+        crate::trace::lock_async(LOCK.read()).await
+    }
+
+    /// Blocking panics if called from within a tokio runtime
+    fn check_runtime() -> Result<(), SpaceError> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            Err(SpaceError::WouldBlockRuntime)
+        } else {
+            Ok(())
+        }
     }
 }
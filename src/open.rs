@@ -0,0 +1,108 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+};
+
+use crate::{Playspace, WriteError};
+
+/// Options for opening a file in a Playspace, similar to
+/// [`std::fs::OpenOptions`].
+///
+/// Created with [`Playspace::open`]. No access is requested by default, so at
+/// least one of [`read`][OpenBuilder::read], [`write`][OpenBuilder::write] or
+/// [`append`][OpenBuilder::append] must be set.
+#[must_use = "the file is only opened by calling `open`"]
+pub struct OpenBuilder<'a> {
+    space: &'a Playspace,
+    path: PathBuf,
+    options: OpenOptions,
+}
+
+impl OpenBuilder<'_> {
+    /// Open for reading, see [`OpenOptions::read`].
+    pub fn read(mut self, read: bool) -> Self {
+        self.options.read(read);
+        self
+    }
+
+    /// Open for writing, see [`OpenOptions::write`].
+    pub fn write(mut self, write: bool) -> Self {
+        self.options.write(write);
+        self
+    }
+
+    /// Open for appending, see [`OpenOptions::append`].
+    pub fn append(mut self, append: bool) -> Self {
+        self.options.append(append);
+        self
+    }
+
+    /// Truncate the file when it is opened, see [`OpenOptions::truncate`].
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.options.truncate(truncate);
+        self
+    }
+
+    /// Create the file if it doesn't exist, see [`OpenOptions::create`].
+    pub fn create(mut self, create: bool) -> Self {
+        self.options.create(create);
+        self
+    }
+
+    /// Create the file, failing if it already exists, see
+    /// [`OpenOptions::create_new`].
+    pub fn create_new(mut self, create_new: bool) -> Self {
+        self.options.create_new(create_new);
+        self
+    }
+
+    /// Open the file with the chosen options.
+    ///
+    /// # Errors
+    ///
+    /// If the path is not in the Playspace, an error will be returned. Any
+    /// stardard IO error is bubbled-up.
+    pub fn open(self) -> Result<File, WriteError> {
+        let path = self.space.playspace_path(self.path)?;
+        self.options
+            .open(&path)
+            .map_err(|source| WriteError::io(&path, source))
+    }
+}
+
+impl Playspace {
+    /// Open a file in the Playspace with any combination of options, similar
+    /// to [`std::fs::OpenOptions`].
+    ///
+    /// The path is handled exactly as by [`create_file`][Playspace::create_file],
+    /// which is equivalent to `open(path).write(true).create(true).truncate(true)`.
+    /// The check that the path is inside the Playspace is made when the file
+    /// is finally [`open`][OpenBuilder::open]ed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::io::{Read, Write};
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("log.txt", "first\n").unwrap();
+    ///
+    ///     let mut file = space.open("log.txt").append(true).open().unwrap();
+    ///     file.write_all(b"second\n").unwrap();
+    ///
+    ///     let mut contents = String::new();
+    ///     space.open("log.txt").read(true).open().unwrap().read_to_string(&mut contents).unwrap();
+    ///     assert_eq!(contents, "first\nsecond\n");
+    /// }).unwrap();
+    /// ```
+    pub fn open(&self, path: impl AsRef<Path>) -> OpenBuilder<'_> {
+        OpenBuilder {
+            space: self,
+            path: path.as_ref().to_owned(),
+            options: OpenOptions::new(),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::Path;
+
+use crate::{Playspace, WriteError};
+
+impl Playspace {
+    /// Make a file or directory in the Playspace read-only, or writable again,
+    /// similar to [`std::fs::Permissions::set_readonly`].
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// N.B. on Unix, nothing can be removed from a read-only directory, so
+    /// make any read-only directories writable again before exiting, or the
+    /// Playspace can't be removed.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("config.toml", "").unwrap();
+    ///     space.set_readonly("config.toml", true).unwrap();
+    ///     assert!(space.metadata("config.toml").unwrap().permissions().readonly());
+    /// }).unwrap();
+    /// ```
+    pub fn set_readonly(&self, path: impl AsRef<Path>, readonly: bool) -> Result<(), WriteError> {
+        let path = self.playspace_path(path)?;
+        let mut permissions = std::fs::metadata(&path)
+            .map_err(|source| WriteError::io(&path, source))?
+            .permissions();
+        permissions.set_readonly(readonly);
+        std::fs::set_permissions(&path, permissions).map_err(|source| WriteError::io(&path, source))
+    }
+
+    /// Set the Unix permission bits of a file or directory in the Playspace,
+    /// similar to [`std::os::unix::fs::PermissionsExt::set_mode`].
+    ///
+    /// The path is handled exactly as by [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// use std::os::unix::fs::PermissionsExt;
+    ///
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("script.sh", "#!/bin/sh\n").unwrap();
+    ///     space.set_mode("script.sh", 0o755).unwrap();
+    ///     assert_eq!(space.metadata("script.sh").unwrap().permissions().mode() & 0o777, 0o755);
+    /// }).unwrap();
+    /// ```
+    #[cfg(unix)]
+    #[cfg_attr(docsrs, doc(cfg(unix)))]
+    pub fn set_mode(&self, path: impl AsRef<Path>, mode: u32) -> Result<(), WriteError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = self.playspace_path(path)?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode))
+            .map_err(|source| WriteError::io(&path, source))
+    }
+}
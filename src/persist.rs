@@ -0,0 +1,60 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{copy::copy_tree, env_spec::matches, walk::walk};
+
+/// Files to copy out of a Playspace before it is cleaned up, see
+/// [`Playspace::persist_to`][crate::Playspace::persist_to].
+#[derive(Debug, Clone)]
+pub(crate) struct Persist {
+    pub(crate) dest: PathBuf,
+    pub(crate) patterns: Vec<String>,
+}
+
+impl Persist {
+    /// Copy everything in `root` matching any of the patterns to `dest`,
+    /// keeping their paths relative to `root`
+    pub(crate) fn copy_from(&self, root: &Path) -> Result<(), io::Error> {
+        let patterns: Vec<Vec<&str>> = self
+            .patterns
+            .iter()
+            .map(|pattern| pattern.split('/').filter(|part| !part.is_empty()).collect())
+            .collect();
+        let matched: Vec<PathBuf> = walk(root)?
+            .into_iter()
+            .filter(|path| {
+                let parts: Vec<_> = path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect();
+                patterns.iter().any(|pattern| matches_path(pattern, &parts))
+            })
+            .collect();
+
+        // Keep whatever is matched, everything inside it, and the directories
+        // leading to it
+        copy_tree(root, &self.dest, |path| {
+            !matched
+                .iter()
+                .any(|kept| path.starts_with(kept) || kept.starts_with(path))
+        })
+    }
+}
+
+/// Whether the components of a path match those of a pattern, where `**`
+/// matches any number of components and `*` is a wildcard within one
+fn matches_path<S: AsRef<str>>(pattern: &[&str], path: &[S]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| matches_path(rest, &path[skip..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((part, path)) => matches(first, part.as_ref()) && matches_path(rest, path),
+            None => false,
+        },
+    }
+}
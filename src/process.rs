@@ -0,0 +1,130 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    process::{Command, ExitStatus},
+};
+
+use crate::Playspace;
+
+/// Set to the name of the test in the child process of
+/// [`scoped_process`][Playspace::scoped_process]
+const CHILD_VAR: &str = "PLAYSPACE_SCOPED_PROCESS";
+
+/// Printed by the child once the closure has run and the Playspace has exited,
+/// so a filter which matched nothing isn't mistaken for success
+const DONE_MARKER: &str = "playspace: scoped process done";
+
+impl Playspace {
+    /// Run `f` in a scoped Playspace in a child process, so none of its
+    /// changes to the working directory or environment can be seen by any
+    /// other thread of this process.
+    ///
+    /// This must be called from a test. The test binary is re-executed with
+    /// a filter for just the current test (as named by the test harness), so
+    /// in the child the same test runs up to this call again. There the
+    /// closure runs as in [`scoped`][Playspace::scoped], while here nothing
+    /// is run and this only waits for the child. Anything before the call is
+    /// therefore run twice, and anything after it only here, so it is best
+    /// kept as the whole body of the test.
+    ///
+    /// # Blocks
+    ///
+    /// Blocks until the child process exits. The child process waits for no
+    /// other Playspace, since it has its own.
+    ///
+    /// # Panics
+    ///
+    /// Only in the child process, if there is an error entering or exiting
+    /// the Playspace, so that the child's test fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessError::NotATest`] if not called from a test thread,
+    /// [`ProcessError::Failed`] (with its output) if the child failed, e.g.
+    /// because the closure panicked, and [`ProcessError::NotRun`] if the
+    /// child didn't run the closure. Any stardard IO error starting the child
+    /// is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use playspace::Playspace;
+    /// #[test]
+    /// fn changes_directory() {
+    ///     Playspace::scoped_process(|_space| {
+    ///         std::env::set_var("ONLY_IN_CHILD", "1");
+    ///         std::fs::create_dir("nested").unwrap();
+    ///         std::env::set_current_dir("nested").unwrap();
+    ///     })
+    ///     .unwrap();
+    ///     assert!(std::env::var_os("ONLY_IN_CHILD").is_none());
+    /// }
+    /// ```
+    pub fn scoped_process<F>(f: F) -> Result<(), ProcessError>
+    where
+        F: FnOnce(&mut Self),
+    {
+        let thread = std::thread::current();
+        let test = match thread.name() {
+            Some(name) if name != "main" => name,
+            _ => return Err(ProcessError::NotATest),
+        };
+
+        if std::env::var_os(CHILD_VAR).is_some_and(|child| child == test) {
+            // Panicking fails the test, which the parent reports
+            Self::scoped(f).expect("Failed to use Playspace in child process");
+            println!("{DONE_MARKER}");
+            return Ok(());
+        }
+
+        let output = Command::new(std::env::current_exe()?)
+            .args([test, "--exact", "--include-ignored", "--nocapture"])
+            .arg("--test-threads=1")
+            .env(CHILD_VAR, test)
+            .output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+        if !output.status.success() {
+            return Err(ProcessError::Failed {
+                status: output.status,
+                stdout,
+                stderr,
+            });
+        }
+        if !stdout.contains(DONE_MARKER) {
+            return Err(ProcessError::NotRun { stdout });
+        }
+        Ok(())
+    }
+}
+
+/// Error running a closure with [`Playspace::scoped_process`]
+#[derive(Debug, thiserror::Error)]
+pub enum ProcessError {
+    /// Not called from a test, so there is no test to re-execute.
+    #[error("scoped_process must be called from a test")]
+    NotATest,
+    /// The child process failed, e.g. the closure panicked or there was an
+    /// error entering or exiting the Playspace in it.
+    #[error("child process failed ({status})\n--- stdout\n{stdout}\n--- stderr\n{stderr}")]
+    Failed {
+        /// The exit status of the child.
+        status: ExitStatus,
+        /// Everything written to stdout by the child.
+        stdout: String,
+        /// Everything written to stderr by the child.
+        stderr: String,
+    },
+    /// The child process succeeded without running the closure, e.g. because
+    /// the test harness could not find the test by name.
+    #[error("child process did not run the closure\n--- stdout\n{stdout}")]
+    NotRun {
+        /// Everything written to stdout by the child.
+        stdout: String,
+    },
+    /// A bubbled-up error from [`std::io`] functions.
+    #[error(transparent)]
+    StdIo(#[from] io::Error),
+}
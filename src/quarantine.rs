@@ -0,0 +1,65 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tempfile::TempDir;
+
+use crate::removal::Removal;
+
+/// Where Playspace directories are moved to, rather than being removed on
+/// exit
+#[derive(Debug, Clone)]
+pub(crate) struct Quarantine {
+    pub(crate) dir: PathBuf,
+}
+
+impl Quarantine {
+    /// Move `directory` into quarantine, then remove it in the background.
+    /// If it can't be moved, it is removed here instead.
+    pub(crate) fn dispose(&self, directory: TempDir, removal: Removal) -> Result<(), io::Error> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return removal.remove_dir_all(&directory.keep());
+        }
+
+        // Names may repeat between runs (e.g. with `sequential_names`), so
+        // make them unique in the quarantine
+        let mut name = directory.path().file_name().unwrap_or_default().to_owned();
+        name.push(format!(
+            ".{}.{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let path = directory.keep();
+        match std::fs::rename(&path, self.dir.join(name)) {
+            Ok(()) => {
+                sweep(&self.dir);
+                Ok(())
+            }
+            Err(_) => removal.remove_dir_all(&path),
+        }
+    }
+}
+
+/// Remove everything in the quarantine `dir` on a background thread
+///
+/// Errors are ignored: anything left behind is removed by the next sweep.
+pub(crate) fn sweep(dir: &Path) {
+    let dir = dir.to_owned();
+    let _spawned = std::thread::Builder::new()
+        .name("playspace-sweeper".to_owned())
+        .spawn(move || {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let _ignored = std::fs::remove_dir_all(entry.path());
+            }
+        });
+}
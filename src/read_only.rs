@@ -0,0 +1,118 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use static_assertions::assert_impl_all;
+
+#[cfg(feature = "async")]
+use crate::mutex::read_async;
+use crate::{
+    current,
+    mutex::{blocking_read, release_read, try_read, ReadLock},
+    SpaceError,
+};
+
+/// Shared access to the environment and working directory, for code which
+/// only reads them.
+///
+/// While any `ReadOnlySpace` exists, no [`Playspace`][crate::Playspace] can
+/// be entered, so nothing else in the process changes the environment or
+/// working directory through one. Unlike Playspaces, any number of
+/// `ReadOnlySpace`s can exist at once, so tests which only read can run
+/// concurrently with each other, while still never overlapping with tests
+/// which write. There's no temporary directory, and nothing is restored when
+/// it is dropped, so the holder must not change the environment or working
+/// directory itself.
+///
+/// Waiting Playspaces are not starved: once one is waiting, new
+/// `ReadOnlySpace`s wait behind it.
+///
+/// # Blocks
+///
+/// [`new`][ReadOnlySpace::new] blocks while a Playspace is entered. As for
+/// Playspaces, waiting from inside a [`scoped`][ReadOnlySpace::scoped]
+/// closure for a Playspace or another `ReadOnlySpace`, or for a
+/// `ReadOnlySpace` on a thread holding a Playspace, returns
+/// [`SpaceError::WouldDeadlock`] rather than blocking forever. Even another
+/// `ReadOnlySpace` would wait forever once a Playspace is waiting behind the
+/// first.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::ReadOnlySpace;
+/// let value = ReadOnlySpace::scoped(|| std::env::var("HOME").ok()).unwrap();
+/// ```
+#[must_use = "other Playspaces can be entered as soon as this is dropped"]
+pub struct ReadOnlySpace {
+    /// Only `None` once dropped
+    lock: Option<ReadLock>,
+}
+
+assert_impl_all!(ReadOnlySpace: Send);
+
+impl std::fmt::Debug for ReadOnlySpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadOnlySpace").finish_non_exhaustive()
+    }
+}
+
+impl ReadOnlySpace {
+    /// Take shared access, blocking until no Playspace is entered.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpaceError::WouldBlockRuntime`] if called from within a tokio
+    /// runtime with the `async` feature enabled, or
+    /// [`SpaceError::WouldDeadlock`] if this thread holds a Playspace, or is
+    /// inside a [`scoped`][ReadOnlySpace::scoped] closure.
+    pub fn new() -> Result<Self, SpaceError> {
+        Ok(Self {
+            lock: Some(blocking_read()?),
+        })
+    }
+
+    /// Take shared access, or fail immediately if a Playspace is entered.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpaceError::AlreadyInSpace`] if a Playspace is entered.
+    pub fn try_new() -> Result<Self, SpaceError> {
+        let lock = try_read().ok_or(SpaceError::AlreadyInSpace)?;
+        Ok(Self { lock: Some(lock) })
+    }
+
+    /// Take shared access, waiting asynchronously until no Playspace is
+    /// entered.
+    ///
+    /// # Errors
+    ///
+    /// Never fails, returns a `Result` for consistency with the other
+    /// constructors.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    #[allow(clippy::unnecessary_wraps)]
+    pub async fn new_async() -> Result<Self, SpaceError> {
+        Ok(Self {
+            lock: Some(read_async().await),
+        })
+    }
+
+    /// Run `f` with shared access, see [`new`][ReadOnlySpace::new].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`new`][ReadOnlySpace::new].
+    pub fn scoped<R>(f: impl FnOnce() -> R) -> Result<R, SpaceError> {
+        let _space = Self::new()?;
+        let _pin = current::pin();
+        Ok(f())
+    }
+}
+
+impl Drop for ReadOnlySpace {
+    fn drop(&mut self) {
+        if let Some(lock) = self.lock.take() {
+            release_read(lock);
+        }
+    }
+}
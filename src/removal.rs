@@ -0,0 +1,54 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{io, path::Path, time::Duration};
+
+/// How many times to try removing the Playspace directory on exit, see
+/// [`PlayspaceBuilder::removal_retries`][crate::PlayspaceBuilder::removal_retries].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Removal {
+    pub(crate) attempts: u32,
+    pub(crate) delay: Duration,
+}
+
+impl Default for Removal {
+    /// On Windows, antivirus scanners and indexers routinely hold files open
+    /// for a moment after they're written, so keep retrying for over half a
+    /// second. Elsewhere, a failure is almost never transient.
+    fn default() -> Self {
+        if cfg!(windows) {
+            Self {
+                attempts: 6,
+                delay: Duration::from_millis(20),
+            }
+        } else {
+            Self {
+                attempts: 1,
+                delay: Duration::ZERO,
+            }
+        }
+    }
+}
+
+impl Removal {
+    /// Remove `path` and everything in it, retrying with exponential backoff
+    ///
+    /// Returns the last error if every attempt fails.
+    pub(crate) fn remove_dir_all(self, path: &Path) -> Result<(), io::Error> {
+        let mut delay = self.delay;
+        let mut attempt = 1;
+        loop {
+            match std::fs::remove_dir_all(path) {
+                Ok(()) => return Ok(()),
+                // Nothing to wait for
+                Err(error) if error.kind() == io::ErrorKind::NotFound => return Err(error),
+                Err(error) if attempt >= self.attempts => return Err(error),
+                Err(_) => {
+                    std::thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    fmt::Write as _,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+
+/// File format for reports of retained Playspaces, see
+/// [`PlayspaceBuilder::retention_report`][crate::PlayspaceBuilder::retention_report].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RetentionFormat {
+    /// One JSON object per line (JSON Lines), with `test`, `path`, and
+    /// `reason` fields.
+    Json,
+    /// JUnit XML `<property>` elements, one per line, named
+    /// `playspace.retained.<test>` (with the path as the value) and
+    /// `playspace.reason.<test>`, ready to be spliced into a `<properties>`
+    /// element.
+    JunitProperties,
+}
+
+/// How a Playspace is retained on failure
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Retention {
+    pub(crate) report: Option<(PathBuf, RetentionFormat)>,
+}
+
+impl Retention {
+    /// Tell the user, and anything reading the report, that a Playspace was
+    /// retained
+    pub(crate) fn record(&self, retained: &Path, reason: &str) {
+        let test = std::thread::current()
+            .name()
+            .unwrap_or("<unnamed>")
+            .to_owned();
+        eprintln!(
+            "playspace: retained {} for {test} ({reason})",
+            retained.display()
+        );
+
+        if let Some((report, format)) = &self.report {
+            if let Err(error) = append(report, *format, &test, retained, reason) {
+                eprintln!(
+                    "playspace: could not write retention report {}: {error}",
+                    report.display()
+                );
+            }
+        }
+    }
+}
+
+fn append(
+    report: &Path,
+    format: RetentionFormat,
+    test: &str,
+    retained: &Path,
+    reason: &str,
+) -> Result<(), io::Error> {
+    let retained = retained.to_string_lossy();
+    let mut entry = String::new();
+    match format {
+        RetentionFormat::Json => {
+            let _infallible = writeln!(
+                entry,
+                r#"{{"test":"{}","path":"{}","reason":"{}"}}"#,
+                json_escape(test),
+                json_escape(&retained),
+                json_escape(reason)
+            );
+        }
+        RetentionFormat::JunitProperties => {
+            let test = xml_escape(test);
+            let _infallible = writeln!(
+                entry,
+                r#"<property name="playspace.retained.{test}" value="{}"/>"#,
+                xml_escape(&retained)
+            );
+            let _infallible = writeln!(
+                entry,
+                r#"<property name="playspace.reason.{test}" value="{}"/>"#,
+                xml_escape(reason)
+            );
+        }
+    }
+
+    if let Some(parent) = report.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    // A single write, so that concurrent test processes don't interleave
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(report)?
+        .write_all(entry.as_bytes())
+}
+
+fn json_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _infallible = write!(escaped, "\\u{:04x}", u32::from(c));
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
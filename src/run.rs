@@ -0,0 +1,270 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    borrow::Cow,
+    ffi::OsStr,
+    io,
+    process::{Command, ExitStatus, Output},
+};
+
+use crate::Playspace;
+
+/// The result of a command run with [`Playspace::run`].
+///
+/// The `assert_*` methods panic with the command's output, so failures
+/// explain themselves, and can be chained.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::Playspace;
+/// # #[cfg(unix)]
+/// Playspace::scoped(|space| {
+///     space.write_file("file.txt", "").unwrap();
+///     space
+///         .run(["ls"])
+///         .unwrap()
+///         .assert_success()
+///         .assert_stdout_contains("file.txt");
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunOutput {
+    /// How the command exited.
+    pub status: ExitStatus,
+    /// Everything the command wrote to standard output.
+    pub stdout: Vec<u8>,
+    /// Everything the command wrote to standard error.
+    pub stderr: Vec<u8>,
+}
+
+impl RunOutput {
+    /// Whether the command exited successfully.
+    #[must_use]
+    pub fn success(&self) -> bool {
+        self.status.success()
+    }
+
+    /// The exit code, if the command exited normally (rather than e.g. being
+    /// killed by a signal).
+    #[must_use]
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    /// Standard output as text, with any invalid UTF-8 replaced.
+    #[must_use]
+    pub fn stdout_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stdout)
+    }
+
+    /// Standard error as text, with any invalid UTF-8 replaced.
+    #[must_use]
+    pub fn stderr_str(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.stderr)
+    }
+
+    /// Assert that the command exited successfully.
+    ///
+    /// # Panics
+    ///
+    /// If it didn't.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate)]
+    pub fn assert_success(&self) -> &Self {
+        if !self.success() {
+            self.fail("expected the command to succeed");
+        }
+        self
+    }
+
+    /// Assert that the command did not exit successfully.
+    ///
+    /// # Panics
+    ///
+    /// If it did.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate)]
+    pub fn assert_failure(&self) -> &Self {
+        if self.success() {
+            self.fail("expected the command to fail");
+        }
+        self
+    }
+
+    /// Assert that the command exited with `code`.
+    ///
+    /// # Panics
+    ///
+    /// If it exited with any other code, or was killed.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate)]
+    pub fn assert_code(&self, code: i32) -> &Self {
+        if self.code() != Some(code) {
+            self.fail(&format!("expected the command to exit with code {code}"));
+        }
+        self
+    }
+
+    /// Assert that standard output contains `expected`.
+    ///
+    /// # Panics
+    ///
+    /// If it doesn't.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate)]
+    pub fn assert_stdout_contains(&self, expected: &str) -> &Self {
+        if !self.stdout_str().contains(expected) {
+            self.fail(&format!("expected standard output to contain {expected:?}"));
+        }
+        self
+    }
+
+    /// Assert that standard error contains `expected`.
+    ///
+    /// # Panics
+    ///
+    /// If it doesn't.
+    #[track_caller]
+    #[allow(clippy::must_use_candidate)]
+    pub fn assert_stderr_contains(&self, expected: &str) -> &Self {
+        if !self.stderr_str().contains(expected) {
+            self.fail(&format!("expected standard error to contain {expected:?}"));
+        }
+        self
+    }
+
+    #[track_caller]
+    fn fail(&self, message: &str) -> ! {
+        panic!(
+            "{message}, but it exited with {}\n--- stdout\n{}\n--- stderr\n{}",
+            self.status,
+            self.stdout_str(),
+            self.stderr_str()
+        )
+    }
+}
+
+impl From<Output> for RunOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            status: output.status,
+            stdout: output.stdout,
+            stderr: output.stderr,
+        }
+    }
+}
+
+impl Playspace {
+    /// Run a command in the Playspace root and wait for it to finish,
+    /// capturing its output.
+    ///
+    /// The first item is the program and the rest are its arguments. The
+    /// program is found on `PATH` as usual, so can be a
+    /// [shim][Playspace::shim]. The command inherits the current environment,
+    /// and standard input is closed.
+    ///
+    /// # Errors
+    ///
+    /// If `command` is empty, an error of kind
+    /// [`InvalidInput`][io::ErrorKind::InvalidInput] is returned. Any error
+    /// starting the command is bubbled-up. The command itself failing is not
+    /// an error, see [`RunOutput::assert_success`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # #[cfg(unix)]
+    /// Playspace::scoped(|space| {
+    ///     let output = space.run(["sh", "-c", "echo hello; exit 2"]).unwrap();
+    ///     assert_eq!(output.code(), Some(2));
+    ///     assert_eq!(output.stdout_str(), "hello\n");
+    /// }).unwrap();
+    /// ```
+    pub fn run<I, S>(&self, command: I) -> Result<RunOutput, io::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.command(command)?.output().map(RunOutput::from)
+    }
+
+    /// Async version of [`run`][Playspace::run], which waits for the command
+    /// without blocking.
+    ///
+    /// Like the rest of this crate, this doesn't depend on any particular
+    /// runtime: the command is run from a separate thread.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`run`][Playspace::run].
+    ///
+    /// # Panics
+    ///
+    /// If the thread running the command panics.
+    #[cfg(feature = "async")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+    pub async fn run_async<I, S>(&self, command: I) -> Result<RunOutput, io::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut command = self.command(command)?;
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ignored = sender.send(command.output().map(RunOutput::from));
+        });
+        receiver.await.expect("command thread panicked")
+    }
+
+    /// Run a shell snippet in the Playspace root, with `sh -c` (or `cmd /C`
+    /// on Windows), as for [`run`][Playspace::run].
+    ///
+    /// Handy for quick end-to-end tests which just need a pipeline or a
+    /// redirect, but the snippet is of course not portable between shells.
+    ///
+    /// # Errors
+    ///
+    /// Any error starting the shell is bubbled-up. The snippet itself failing
+    /// is not an error, see [`RunOutput::assert_success`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # #[cfg(unix)]
+    /// Playspace::scoped(|space| {
+    ///     space.sh("echo hi > out.txt").unwrap().assert_success();
+    ///     assert_eq!(std::fs::read_to_string("out.txt").unwrap(), "hi\n");
+    /// }).unwrap();
+    /// ```
+    #[cfg(feature = "shell")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "shell")))]
+    pub fn sh(&self, script: impl AsRef<OsStr>) -> Result<RunOutput, io::Error> {
+        let script = script.as_ref();
+        if cfg!(windows) {
+            self.run([OsStr::new("cmd"), OsStr::new("/C"), script])
+        } else {
+            self.run([OsStr::new("sh"), OsStr::new("-c"), script])
+        }
+    }
+
+    fn command<I, S>(&self, command: I) -> Result<Command, io::Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut parts = command.into_iter();
+        let Some(program) = parts.next() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no command to run",
+            ));
+        };
+        let mut command = Command::new(program);
+        command.args(parts).current_dir(self.directory());
+        Ok(command)
+    }
+}
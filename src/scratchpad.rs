@@ -0,0 +1,80 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{any::Any, collections::HashMap, sync::Arc};
+
+use crate::Playspace;
+
+pub(crate) type Scratchpad = HashMap<String, Arc<dyn Any + Send + Sync>>;
+
+impl Playspace {
+    /// Store a value in the Playspace, under the given key.
+    ///
+    /// This lets setup code, exit checks, and the test itself share state
+    /// (ports, process IDs, generated IDs, ...) through the Playspace, rather
+    /// than through statics that outlive it. Values can be of any type, and are
+    /// dropped when the Playspace exits, after any
+    /// [exit checks][Playspace::verify_on_exit] have run. Any value already
+    /// stored under the same key is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.store("port", 8080_u16);
+    ///     space.verify_on_exit(|space| {
+    ///         let port = space.get::<u16>("port").unwrap();
+    ///         // ... check nothing is still listening on the port ...
+    ///         Ok(())
+    ///     });
+    ///
+    ///     assert_eq!(*space.get::<u16>("port").unwrap(), 8080);
+    ///     assert!(space.get::<String>("port").is_none());
+    /// }).unwrap();
+    /// ```
+    pub fn store<T>(&self, key: impl Into<String>, value: T)
+    where
+        T: Any + Send + Sync,
+    {
+        self.scratchpad.lock().insert(key.into(), Arc::new(value));
+    }
+
+    /// Get a value stored with [`store`][Playspace::store].
+    ///
+    /// Returns `None` if there is no value under the key, or if the value is
+    /// not a `T`.
+    ///
+    /// # Example
+    ///
+    /// See [`store`][Playspace::store].
+    #[must_use]
+    pub fn get<T>(&self, key: &str) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        let value = Arc::clone(self.scratchpad.lock().get(key)?);
+        value.downcast().ok()
+    }
+
+    /// Remove a value stored with [`store`][Playspace::store], returning it
+    /// if it was a `T`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     space.store("pid", 1234_u32);
+    ///     assert_eq!(*space.remove::<u32>("pid").unwrap(), 1234);
+    ///     assert!(space.get::<u32>("pid").is_none());
+    /// }).unwrap();
+    /// ```
+    pub fn remove<T>(&self, key: &str) -> Option<Arc<T>>
+    where
+        T: Any + Send + Sync,
+    {
+        let value = self.scratchpad.lock().remove(key)?;
+        value.downcast().ok()
+    }
+}
@@ -0,0 +1,115 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use crate::{
+    current::{self, Pin},
+    mutex::{blocking_lock, release, Lock},
+    Playspace, SpaceError,
+};
+
+/// Runs several fresh Playspaces one after another, see
+/// [`Playspace::sequence`].
+pub struct SpaceSequence {
+    lock: Option<Lock>,
+    /// Held whenever `lock` is, since the lock isn't released until
+    /// [`Playspace::sequence`] returns
+    pin: Option<Pin>,
+}
+
+impl SpaceSequence {
+    /// Run a closure in a fresh Playspace, just like
+    /// [`scoped`][Playspace::scoped], but without releasing the Playspace lock
+    /// afterwards.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`scoped`][Playspace::scoped], for this phase only. Later
+    /// phases can still be run after an error.
+    pub fn space<R, F>(&mut self, f: F) -> Result<R, SpaceError>
+    where
+        F: FnOnce(&mut Playspace) -> R,
+    {
+        // Only missing if entering an earlier phase failed
+        let lock = if let Some(lock) = self.lock.take() {
+            lock
+        } else {
+            let lock = blocking_lock()?;
+            self.pin = Some(current::pin());
+            lock
+        };
+
+        let mut space = Playspace::from_lock(lock).inspect_err(|_error| self.pin = None)?;
+        // As in `Playspace::scoped`, exit before resuming any panic, keeping
+        // the lock for later phases
+        let out = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| f(&mut space))) {
+            Ok(out) => out,
+            Err(panic) => {
+                space.panicked = true;
+                let (_report, lock) = space.exit_retaining_lock();
+                self.lock = Some(lock);
+                std::panic::resume_unwind(panic)
+            }
+        };
+        let (report, lock) = space.exit_retaining_lock();
+        self.lock = Some(lock);
+        report.into_result()?;
+
+        Ok(out)
+    }
+}
+
+impl Playspace {
+    /// Run several Playspaces in sequence, while holding the Playspace lock
+    /// throughout.
+    ///
+    /// Each call to [`space`][SpaceSequence::space] enters a fresh Playspace,
+    /// runs its closure, and exits again, just like
+    /// [`scoped`][Playspace::scoped]. Unlike separate calls to `scoped`, no
+    /// other Playspace can be entered in between phases, and the lock is only
+    /// waited for once. Each phase reports its own result.
+    ///
+    /// N.B. any [barriers][Playspace::arrive_on_exit] are arrived at as each
+    /// phase exits, while the lock is still held.
+    ///
+    /// # Blocks
+    ///
+    /// The same as [`scoped`][Playspace::scoped].
+    ///
+    /// # Errors
+    ///
+    /// Only errors waiting for the Playspace lock, see
+    /// [`scoped`][Playspace::scoped]. Errors in each phase are returned by
+    /// the phase itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, SpaceError};
+    /// Playspace::sequence(|runner| -> Result<(), SpaceError> {
+    ///     runner.space(|space| {
+    ///         space.write_file("db.sqlite", "v1").unwrap();
+    ///         // ... migrate from a clean slate ...
+    ///     })?;
+    ///     runner.space(|space| {
+    ///         assert!(!space.directory().join("db.sqlite").exists());
+    ///         // ... migrate again from a clean slate ...
+    ///     })?;
+    ///     Ok(())
+    /// }).unwrap().unwrap();
+    /// ```
+    pub fn sequence<R, F>(f: F) -> Result<R, SpaceError>
+    where
+        F: FnOnce(&mut SpaceSequence) -> R,
+    {
+        let mut runner = SpaceSequence {
+            lock: Some(blocking_lock()?),
+            pin: Some(current::pin()),
+        };
+        let out = f(&mut runner);
+        if let Some(lock) = runner.lock.take() {
+            release(lock);
+        }
+        drop(runner.pin.take());
+        Ok(out)
+    }
+}
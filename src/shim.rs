@@ -0,0 +1,328 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    ffi::OsString,
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::Playspace;
+
+/// Name of the directory shims are installed in by [`Playspace::shim`], in
+/// the Playspace root.
+pub const BIN_DIR: &str = "bin";
+
+/// Name of the directory in the Playspace root where shims keep their output
+/// and record their calls
+const SHIM_DATA_DIR: &str = ".shims";
+
+/// A fake external command, created with [`Playspace::shim`].
+///
+/// By default the shim prints nothing and exits successfully. Nothing is
+/// written until [`install`][ShimBuilder::install] is called.
+#[must_use = "the shim is only created by calling `install`"]
+pub struct ShimBuilder<'a> {
+    space: &'a Playspace,
+    name: String,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    exit_code: i32,
+}
+
+impl ShimBuilder<'_> {
+    /// Print `stdout` to standard output when called.
+    pub fn with_stdout(mut self, stdout: impl AsRef<[u8]>) -> Self {
+        stdout.as_ref().clone_into(&mut self.stdout);
+        self
+    }
+
+    /// Print `stderr` to standard error when called.
+    pub fn with_stderr(mut self, stderr: impl AsRef<[u8]>) -> Self {
+        stderr.as_ref().clone_into(&mut self.stderr);
+        self
+    }
+
+    /// Exit with `code` when called.
+    pub fn with_exit_code(mut self, code: i32) -> Self {
+        self.exit_code = code;
+        self
+    }
+
+    /// Write the shim to [`BIN_DIR`] in the Playspace, and put that directory
+    /// first on `PATH` if it isn't already. Returns the path of the shim.
+    ///
+    /// Installing a shim with the same name again replaces it, but keeps its
+    /// recorded calls.
+    ///
+    /// # Errors
+    ///
+    /// If the name is not a plain file name, an error of kind
+    /// [`InvalidInput`][io::ErrorKind::InvalidInput] is returned. Any stardard
+    /// IO error is bubbled-up.
+    pub fn install(self) -> Result<PathBuf, io::Error> {
+        if self.name.is_empty()
+            || Path::new(&self.name).file_name() != Some(self.name.as_ref())
+            || self.name.contains(['/', '\\'])
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{:?} is not a valid shim name", self.name),
+            ));
+        }
+
+        let bin = self.space.directory().join(BIN_DIR);
+        let data = shim_data(self.space, &self.name);
+        std::fs::create_dir_all(&bin)?;
+        std::fs::create_dir_all(data.join("calls"))?;
+        std::fs::write(data.join("stdout"), &self.stdout)?;
+        std::fs::write(data.join("stderr"), &self.stderr)?;
+
+        let path = write_script(&bin, &self.name, &data, self.exit_code)?;
+
+        let path_var = std::env::var_os("PATH").unwrap_or_default();
+        if std::env::split_paths(&path_var).next().as_deref() != Some(&bin) {
+            let joined =
+                std::env::join_paths(std::iter::once(bin).chain(std::env::split_paths(&path_var)))
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+            self.space.set_envs([("PATH", Some(joined))]);
+        }
+        Ok(path)
+    }
+}
+
+/// A recorded call of a shim, see [`Playspace::shim_calls`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShimCall {
+    /// The arguments, not including the command itself.
+    pub args: Vec<OsString>,
+    /// The working directory it was called from.
+    pub cwd: PathBuf,
+    /// Everything read from standard input, unless it was a terminal. Always
+    /// empty on Windows.
+    pub stdin: Vec<u8>,
+}
+
+impl Playspace {
+    /// Start creating a fake external command named `name`, see
+    /// [`ShimBuilder`].
+    ///
+    /// Shims are small shell scripts (`.cmd` files on Windows) in
+    /// [`BIN_DIR`] in the Playspace, which is put first on `PATH`, so code
+    /// under test which runs `name` runs the shim instead. Each call is
+    /// recorded, and can be queried with
+    /// [`shim_calls`][Playspace::shim_calls]. On Unix, standard input is
+    /// always read to the end (unless it is a terminal), so must be closed by
+    /// the caller. It is not recorded on Windows, where arguments are also
+    /// only split on unquoted whitespace.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # #[cfg(unix)]
+    /// Playspace::scoped(|space| {
+    ///     space
+    ///         .shim("git")
+    ///         .with_stdout("fake output\n")
+    ///         .with_exit_code(0)
+    ///         .install()
+    ///         .unwrap();
+    ///
+    ///     let output = std::process::Command::new("git")
+    ///         .args(["status", "--short"])
+    ///         .output()
+    ///         .unwrap();
+    ///     assert_eq!(output.stdout, b"fake output\n");
+    ///
+    ///     let calls = space.shim_calls("git").unwrap();
+    ///     assert_eq!(calls.len(), 1);
+    ///     assert_eq!(calls[0].args, ["status", "--short"]);
+    /// }).unwrap();
+    /// ```
+    pub fn shim(&self, name: impl Into<String>) -> ShimBuilder<'_> {
+        ShimBuilder {
+            space: self,
+            name: name.into(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            exit_code: 0,
+        }
+    }
+
+    /// Every recorded call of the shim named `name`, in the order they were
+    /// made.
+    ///
+    /// Returns nothing if no shim with that name has been installed.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up.
+    pub fn shim_calls(&self, name: &str) -> Result<Vec<ShimCall>, io::Error> {
+        let calls = shim_data(self, name).join("calls");
+        let mut numbered = Vec::new();
+        let entries = match std::fs::read_dir(&calls) {
+            Ok(entries) => entries,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        for entry in entries {
+            let entry = entry?;
+            if let Some(number) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.parse::<u64>().ok())
+            {
+                numbered.push((number, entry.path()));
+            }
+        }
+        numbered.sort();
+
+        numbered
+            .into_iter()
+            .map(|(_number, call)| read_call(&call))
+            .collect()
+    }
+}
+
+fn shim_data(space: &Playspace, name: &str) -> PathBuf {
+    space.directory().join(SHIM_DATA_DIR).join(name)
+}
+
+#[cfg(unix)]
+fn write_script(bin: &Path, name: &str, data: &Path, exit_code: i32) -> Result<PathBuf, io::Error> {
+    use std::os::unix::{ffi::OsStrExt, fs::PermissionsExt};
+
+    let mut script = b"#!/bin/sh\ndata=".to_vec();
+    script.extend(quote(data.as_os_str().as_bytes()));
+    script.extend(
+        format!(
+            r#"
+n=0
+while ! mkdir "$data/calls/$n" 2>/dev/null; do
+    n=$((n + 1))
+done
+call="$data/calls/$n"
+pwd > "$call/cwd"
+for arg in "$@"; do
+    printf '%s\0' "$arg"
+done > "$call/args"
+if [ -t 0 ]; then
+    : > "$call/stdin"
+else
+    cat > "$call/stdin"
+fi
+cat "$data/stdout"
+cat "$data/stderr" >&2
+exit {exit_code}
+"#
+        )
+        .as_bytes(),
+    );
+
+    let path = bin.join(name);
+    std::fs::write(&path, script)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    Ok(path)
+}
+
+/// Quote `value` for a POSIX shell
+#[cfg(unix)]
+fn quote(value: &[u8]) -> Vec<u8> {
+    let mut out = vec![b'\''];
+    for &byte in value {
+        if byte == b'\'' {
+            out.extend(b"'\\''");
+        } else {
+            out.push(byte);
+        }
+    }
+    out.push(b'\'');
+    out
+}
+
+#[cfg(unix)]
+fn read_call(call: &Path) -> Result<ShimCall, io::Error> {
+    use std::os::unix::ffi::OsStringExt;
+
+    let args = std::fs::read(call.join("args"))?;
+    let mut cwd = std::fs::read(call.join("cwd"))?;
+    if cwd.last() == Some(&b'\n') {
+        cwd.pop();
+    }
+    Ok(ShimCall {
+        // Every argument is terminated by a NUL
+        args: match args.strip_suffix(b"\0") {
+            Some(args) => args
+                .split(|&byte| byte == 0)
+                .map(|arg| OsString::from_vec(arg.to_vec()))
+                .collect(),
+            None => Vec::new(),
+        },
+        cwd: OsString::from_vec(cwd).into(),
+        stdin: std::fs::read(call.join("stdin"))?,
+    })
+}
+
+#[cfg(windows)]
+fn write_script(bin: &Path, name: &str, data: &Path, exit_code: i32) -> Result<PathBuf, io::Error> {
+    let data = data.display();
+    let script = format!(
+        "@echo off\r\n\
+         setlocal\r\n\
+         set \"data={data}\"\r\n\
+         set n=0\r\n\
+         :next\r\n\
+         mkdir \"%data%\\calls\\%n%\" 2>nul || (set /a n+=1 & goto next)\r\n\
+         set \"call=%data%\\calls\\%n%\"\r\n\
+         cd > \"%call%\\cwd\"\r\n\
+         echo(%*> \"%call%\\cmdline\"\r\n\
+         type nul > \"%call%\\stdin\"\r\n\
+         type \"%data%\\stdout\"\r\n\
+         type \"%data%\\stderr\" 1>&2\r\n\
+         exit /b {exit_code}\r\n"
+    );
+
+    let path = bin.join(format!("{name}.cmd"));
+    std::fs::write(&path, script)?;
+    Ok(path)
+}
+
+#[cfg(windows)]
+fn read_call(call: &Path) -> Result<ShimCall, io::Error> {
+    let cmdline = std::fs::read_to_string(call.join("cmdline"))?;
+    let cwd = std::fs::read_to_string(call.join("cwd"))?;
+
+    // `cmd` only passes on the raw command line, so split it the simple way
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut in_arg = false;
+    for character in cmdline.trim_end_matches(['\r', '\n']).chars() {
+        match character {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            ' ' | '\t' if !quoted => {
+                if in_arg {
+                    args.push(OsString::from(std::mem::take(&mut current)));
+                    in_arg = false;
+                }
+            }
+            character => {
+                current.push(character);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(OsString::from(current));
+    }
+
+    Ok(ShimCall {
+        args,
+        cwd: cwd.trim_end_matches(['\r', '\n']).into(),
+        stdin: std::fs::read(call.join("stdin"))?,
+    })
+}
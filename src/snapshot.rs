@@ -0,0 +1,198 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    collections::BTreeMap,
+    hash::{Hash, Hasher},
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{walk::walk, Playspace};
+
+/// The state of a directory tree at a point in time, see
+/// [`Playspace::snapshot`].
+///
+/// Records every file, directory, and symlink (which is not followed) by its
+/// path relative to the snapshotted directory, along with enough information
+/// about file contents to detect changes. Compare two snapshots with
+/// [`diff`][DirSnapshot::diff].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirSnapshot {
+    entries: BTreeMap<PathBuf, EntryState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EntryState {
+    Dir,
+    File { len: u64, hash: u64 },
+    Symlink(PathBuf),
+}
+
+impl DirSnapshot {
+    /// Take a snapshot of any directory.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error walking the directory or reading files is
+    /// bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::DirSnapshot;
+    /// let snapshot = DirSnapshot::capture(env!("CARGO_MANIFEST_DIR")).unwrap();
+    /// assert!(snapshot.contains("Cargo.toml"));
+    /// ```
+    pub fn capture(root: impl AsRef<Path>) -> Result<Self, io::Error> {
+        let root = root.as_ref();
+        let entries = walk(root)?
+            .into_iter()
+            .map(|path| {
+                let full = root.join(&path);
+                let file_type = std::fs::symlink_metadata(&full)?.file_type();
+                let state = if file_type.is_symlink() {
+                    EntryState::Symlink(std::fs::read_link(&full)?)
+                } else if file_type.is_dir() {
+                    EntryState::Dir
+                } else {
+                    let contents = std::fs::read(&full)?;
+                    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                    contents.hash(&mut hasher);
+                    EntryState::File {
+                        len: contents.len() as u64,
+                        hash: hasher.finish(),
+                    }
+                };
+                Ok((path, state))
+            })
+            .collect::<Result<_, io::Error>>()?;
+
+        Ok(Self { entries })
+    }
+
+    /// All paths in the snapshot, relative to the snapshotted directory, in
+    /// sorted order.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(PathBuf::as_path)
+    }
+
+    /// Whether the snapshot contains the given relative path.
+    #[must_use]
+    pub fn contains(&self, path: impl AsRef<Path>) -> bool {
+        self.entries.contains_key(path.as_ref())
+    }
+
+    /// Number of entries (files, directories, and symlinks) in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the snapshotted directory was empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Changes from this snapshot to a `later` one.
+    ///
+    /// A file is modified if its contents changed, and a symlink if its target
+    /// changed. Directories are never reported as modified, but any entry that
+    /// changed kind (e.g. from a file to a directory) is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// # use std::path::PathBuf;
+    /// Playspace::scoped(|space| {
+    ///     space.write_file("kept.txt", "before").unwrap();
+    ///     space.write_file("removed.txt", "").unwrap();
+    ///     let before = space.snapshot().unwrap();
+    ///
+    ///     // ... run the code under test ...
+    ///     # std::fs::write("kept.txt", "after").unwrap();
+    ///     # std::fs::write("added.txt", "").unwrap();
+    ///     # std::fs::remove_file("removed.txt").unwrap();
+    ///
+    ///     let diff = before.diff(&space.snapshot().unwrap());
+    ///     assert_eq!(diff.created, [PathBuf::from("added.txt")]);
+    ///     assert_eq!(diff.modified, [PathBuf::from("kept.txt")]);
+    ///     assert_eq!(diff.deleted, [PathBuf::from("removed.txt")]);
+    /// }).unwrap();
+    /// ```
+    #[must_use]
+    pub fn diff(&self, later: &DirSnapshot) -> DirDiff {
+        let mut diff = DirDiff::default();
+        for (path, state) in &self.entries {
+            match later.entries.get(path) {
+                None => diff.deleted.push(path.clone()),
+                Some(later_state) if later_state != state => {
+                    if state != &EntryState::Dir || later_state != &EntryState::Dir {
+                        diff.modified.push(path.clone());
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+        diff.created = later
+            .entries
+            .keys()
+            .filter(|path| !self.entries.contains_key(*path))
+            .cloned()
+            .collect();
+        diff
+    }
+}
+
+/// Differences between two [`DirSnapshot`]s.
+///
+/// All paths are relative to the snapshotted directory, and each list is
+/// sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirDiff {
+    /// Paths that exist only in the later snapshot.
+    pub created: Vec<PathBuf>,
+    /// Paths that exist in both snapshots, but changed.
+    pub modified: Vec<PathBuf>,
+    /// Paths that exist only in the earlier snapshot.
+    pub deleted: Vec<PathBuf>,
+}
+
+impl DirDiff {
+    /// Whether nothing changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+}
+
+impl Playspace {
+    /// Take a snapshot of the Playspace's file tree.
+    ///
+    /// Take one before and one after running the code under test, then
+    /// [`diff`][DirSnapshot::diff] them to see exactly which files it touched.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error walking the tree or reading files is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let before = space.snapshot().unwrap();
+    ///     assert!(before.is_empty());
+    ///
+    ///     space.write_file("output.txt", "results").unwrap();
+    ///     let after = space.snapshot().unwrap();
+    ///     assert!(after.contains("output.txt"));
+    ///     assert!(!before.diff(&after).is_empty());
+    /// }).unwrap();
+    /// ```
+    pub fn snapshot(&self) -> Result<DirSnapshot, io::Error> {
+        DirSnapshot::capture(self.directory())
+    }
+}
@@ -0,0 +1,103 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::{Path, PathBuf};
+
+use crate::{Playspace, WriteError};
+
+impl Playspace {
+    /// Path for a SQLite database file in the Playspace.
+    ///
+    /// The path is checked to be inside the Playspace in the same way as
+    /// [`write_file`][Playspace::write_file], and any missing parent
+    /// directories are created so that the database can be opened straight
+    /// away. The database file itself is not created.
+    ///
+    /// # Errors
+    ///
+    /// If the provided path is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let db = space.sqlite_path("data/test.db").unwrap();
+    ///     assert!(db.parent().unwrap().is_dir());
+    ///     assert!(!db.exists());
+    /// }).unwrap();
+    /// ```
+    pub fn sqlite_path(&self, path: impl AsRef<Path>) -> Result<PathBuf, WriteError> {
+        let path = self.playspace_path(path)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| WriteError::io(parent, source))?;
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
+impl Playspace {
+    /// Create a SQLite database in the Playspace and seed it from SQL
+    /// fixtures.
+    ///
+    /// Each fixture is executed as a batch, in order, so it may contain
+    /// several statements. The database file is created if it doesn't already
+    /// exist. Returns the open connection.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SqliteError::Write`] if the path is not in the Playspace or
+    /// its parent directories could not be created, or
+    /// [`SqliteError::Sqlite`] if opening the database or running a fixture
+    /// failed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::Playspace;
+    /// Playspace::scoped(|space| {
+    ///     let connection = space.create_sqlite("test.db", [
+    ///         "CREATE TABLE users (name TEXT NOT NULL);",
+    ///         "INSERT INTO users VALUES ('alice'), ('bob');",
+    ///     ]).unwrap();
+    ///
+    ///     let count: i64 = connection
+    ///         .query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))
+    ///         .unwrap();
+    ///     assert_eq!(count, 2);
+    /// }).unwrap();
+    /// ```
+    pub fn create_sqlite<P, I, S>(
+        &self,
+        path: P,
+        fixtures: I,
+    ) -> Result<rusqlite::Connection, SqliteError>
+    where
+        P: AsRef<Path>,
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let path = self.sqlite_path(path)?;
+        let connection = rusqlite::Connection::open(path)?;
+        for fixture in fixtures {
+            connection.execute_batch(fixture.as_ref())?;
+        }
+        Ok(connection)
+    }
+}
+
+/// Error creating a SQLite database in a Playspace
+#[cfg(feature = "sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteError {
+    /// Error resolving the database path in the Playspace.
+    #[error(transparent)]
+    Write(#[from] WriteError),
+    /// A bubbled-up error from [`rusqlite`].
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+}
@@ -0,0 +1,214 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Synchronisation utilities for choreographing tests around Playspaces.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Weak},
+    time::{Duration, Instant},
+};
+
+use parking_lot::{Condvar, Mutex};
+
+static REGISTRY: Mutex<Option<HashMap<String, Weak<State>>>> = parking_lot::const_mutex(None);
+
+/// A named, process-wide barrier.
+///
+/// Every `Barrier` created with the same name refers to the same underlying
+/// barrier, so tests on different threads can coordinate without having to
+/// share a handle. The barrier is released once `parties` arrivals have been
+/// counted, either from threads blocking in [`wait`][Barrier::wait] or from
+/// non-blocking [`arrive`][Barrier::arrive] calls. It then resets, ready to be
+/// used again.
+///
+/// A Playspace can [arrive at a barrier when it exits][crate::Playspace::arrive_on_exit],
+/// which makes it easy to wait for a sandboxed test to have finished, and
+/// restored the environment, before continuing.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::{Playspace, sync::Barrier};
+/// let migrated = Barrier::new("db-migrations", 2);
+///
+/// let handle = std::thread::spawn(|| {
+///     let space = Playspace::new().unwrap();
+///     space.arrive_on_exit(&Barrier::new("db-migrations", 2));
+///     // ... run migrations ...
+///     space.exit().unwrap();
+/// });
+///
+/// // Blocks until the other thread has exited its Playspace
+/// migrated.wait();
+/// # handle.join().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct Barrier {
+    name: Arc<str>,
+    state: Arc<State>,
+}
+
+struct State {
+    parties: usize,
+    progress: Mutex<Progress>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct Progress {
+    arrived: usize,
+    generation: u64,
+}
+
+impl Barrier {
+    /// Get the barrier with the given name, creating it if it doesn't exist.
+    ///
+    /// The barrier exists for as long as any handle to it does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parties` is zero, or if a barrier with the same name
+    /// already exists with a different number of parties.
+    #[must_use]
+    pub fn new(name: impl Into<String>, parties: usize) -> Self {
+        assert!(parties > 0, "a barrier needs at least one party");
+        let name = name.into();
+
+        let mut registry = REGISTRY.lock();
+        let registry = registry.get_or_insert_with(HashMap::new);
+        registry.retain(|_name, state| state.strong_count() > 0);
+
+        let state = if let Some(state) = registry.get(&name).and_then(Weak::upgrade) {
+            assert_eq!(
+                state.parties, parties,
+                "barrier {name:?} already exists with {} parties",
+                state.parties
+            );
+            state
+        } else {
+            let state = Arc::new(State {
+                parties,
+                progress: Mutex::new(Progress::default()),
+                condvar: Condvar::new(),
+            });
+            registry.insert(name.clone(), Arc::downgrade(&state));
+            state
+        };
+
+        Self {
+            name: name.into(),
+            state,
+        }
+    }
+
+    /// The name of the barrier.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of arrivals needed to release the barrier.
+    #[must_use]
+    pub fn parties(&self) -> usize {
+        self.state.parties
+    }
+
+    /// Arrive at the barrier and block until it is released.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::sync::Barrier;
+    /// let barrier = Barrier::new("wait-example", 2);
+    /// let other = barrier.clone();
+    /// let handle = std::thread::spawn(move || other.wait());
+    /// barrier.wait();
+    /// # handle.join().unwrap();
+    /// ```
+    pub fn wait(&self) {
+        let mut progress = self.state.progress.lock();
+        if let Some(generation) = self.state.arrive(&mut progress) {
+            while progress.generation == generation {
+                self.state.condvar.wait(&mut progress);
+            }
+        }
+    }
+
+    /// Arrive at the barrier and block until it is released, or until the
+    /// timeout expires.
+    ///
+    /// Returns whether the barrier was released. If the timeout expires, the
+    /// arrival is withdrawn.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::sync::Barrier;
+    /// # use std::time::Duration;
+    /// let barrier = Barrier::new("timeout-example", 2);
+    /// assert!(!barrier.wait_timeout(Duration::from_millis(10)));
+    /// ```
+    #[must_use]
+    pub fn wait_timeout(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut progress = self.state.progress.lock();
+        if let Some(generation) = self.state.arrive(&mut progress) {
+            while progress.generation == generation {
+                if self
+                    .state
+                    .condvar
+                    .wait_until(&mut progress, deadline)
+                    .timed_out()
+                {
+                    if progress.generation == generation {
+                        progress.arrived -= 1;
+                        return false;
+                    }
+                    break;
+                }
+            }
+        }
+        true
+    }
+
+    /// Arrive at the barrier without waiting for it to be released.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::sync::Barrier;
+    /// let barrier = Barrier::new("arrive-example", 2);
+    /// barrier.arrive();
+    /// barrier.wait(); // Doesn't block, this is the second arrival
+    /// ```
+    pub fn arrive(&self) {
+        let mut progress = self.state.progress.lock();
+        self.state.arrive(&mut progress);
+    }
+}
+
+impl std::fmt::Debug for Barrier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Barrier")
+            .field("name", &self.name)
+            .field("parties", &self.state.parties)
+            .finish_non_exhaustive()
+    }
+}
+
+impl State {
+    /// Count an arrival. Returns the generation to wait on if the barrier was
+    /// not released by this arrival.
+    fn arrive(&self, progress: &mut Progress) -> Option<u64> {
+        progress.arrived += 1;
+        if progress.arrived >= self.parties {
+            progress.arrived = 0;
+            progress.generation = progress.generation.wrapping_add(1);
+            self.condvar.notify_all();
+            None
+        } else {
+            Some(progress.generation)
+        }
+    }
+}
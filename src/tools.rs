@@ -0,0 +1,119 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{ffi::OsString, io, path::Path};
+
+use crate::Playspace;
+
+/// Name of the directory created by [`Playspace::isolate_tool_configs`], in the
+/// Playspace root.
+pub const TOOL_CONFIG_DIR: &str = "tool-configs";
+
+/// A tool whose user configuration can be isolated with
+/// [`Playspace::isolate_tool_configs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Tool {
+    /// Sets `GIT_CONFIG_GLOBAL` to an empty config file, and
+    /// `GIT_CONFIG_NOSYSTEM` to ignore the system config.
+    Git,
+    /// Sets `NPM_CONFIG_USERCONFIG` to an empty `npmrc`, and
+    /// `NPM_CONFIG_CACHE` to an empty directory.
+    Npm,
+    /// Sets `PIP_CONFIG_FILE` to an empty config file, and `PIP_CACHE_DIR` to
+    /// an empty directory.
+    Pip,
+    /// Sets `CARGO_HOME` to an empty directory. N.B. this means that any
+    /// dependencies need to be downloaded again.
+    Cargo,
+    /// Sets `GRADLE_USER_HOME` to an empty directory.
+    Gradle,
+    /// Sets `MAVEN_OPTS` to use an empty local repository.
+    Maven,
+}
+
+impl Tool {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Git => "git",
+            Self::Npm => "npm",
+            Self::Pip => "pip",
+            Self::Cargo => "cargo",
+            Self::Gradle => "gradle",
+            Self::Maven => "maven",
+        }
+    }
+
+    /// Create the tool's files in `directory`, and return the variables
+    /// pointing at them
+    fn isolate(self, directory: &Path) -> Result<Vec<(&'static str, OsString)>, io::Error> {
+        std::fs::create_dir_all(directory)?;
+        let empty_file = |name: &str| -> Result<OsString, io::Error> {
+            let path = directory.join(name);
+            std::fs::write(&path, "")?;
+            Ok(path.into_os_string())
+        };
+        let empty_dir = |name: &str| -> Result<OsString, io::Error> {
+            let path = directory.join(name);
+            std::fs::create_dir_all(&path)?;
+            Ok(path.into_os_string())
+        };
+
+        Ok(match self {
+            Self::Git => vec![
+                ("GIT_CONFIG_GLOBAL", empty_file("config")?),
+                ("GIT_CONFIG_NOSYSTEM", "1".into()),
+            ],
+            Self::Npm => vec![
+                ("NPM_CONFIG_USERCONFIG", empty_file("npmrc")?),
+                ("NPM_CONFIG_CACHE", empty_dir("cache")?),
+            ],
+            Self::Pip => vec![
+                ("PIP_CONFIG_FILE", empty_file("pip.conf")?),
+                ("PIP_CACHE_DIR", empty_dir("cache")?),
+            ],
+            Self::Cargo => vec![("CARGO_HOME", empty_dir("home")?)],
+            Self::Gradle => vec![("GRADLE_USER_HOME", empty_dir("home")?)],
+            Self::Maven => {
+                let mut options = OsString::from("-Dmaven.repo.local=");
+                options.push(empty_dir("repository")?);
+                vec![("MAVEN_OPTS", options)]
+            }
+        })
+    }
+}
+
+impl Playspace {
+    /// Point the user configuration of common tools into the Playspace.
+    ///
+    /// Each tool gets empty configuration files and directories in its own
+    /// directory under [`TOOL_CONFIG_DIR`], and the environment variables the
+    /// tool uses to find them are set (see [`Tool`] for exactly which). Tests
+    /// which run these tools are then unaffected by the user's own
+    /// configuration, and can't change it.
+    ///
+    /// # Errors
+    ///
+    /// Any stardard IO error is bubbled-up, in which case the environment
+    /// variables for any tools already isolated remain set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{Playspace, Tool};
+    /// Playspace::scoped(|space| {
+    ///     space.isolate_tool_configs(&[Tool::Git, Tool::Npm]).unwrap();
+    ///
+    ///     let git_config = std::env::var_os("GIT_CONFIG_GLOBAL").unwrap();
+    ///     assert!(space.contains(git_config));
+    /// }).unwrap();
+    /// ```
+    pub fn isolate_tool_configs(&self, tools: &[Tool]) -> Result<(), io::Error> {
+        let root = self.directory().join(TOOL_CONFIG_DIR);
+        for tool in tools {
+            let variables = tool.isolate(&root.join(tool.name()))?;
+            self.set_envs(variables.into_iter().map(|(key, value)| (key, Some(value))));
+        }
+        Ok(())
+    }
+}
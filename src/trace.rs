@@ -0,0 +1,73 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+//! Optional [`tracing`] instrumentation, compiled away without the `tracing`
+//! feature.
+
+/// Emit a `tracing` event with the `playspace` target, if the `tracing`
+/// feature is enabled and the level is [configured][crate::config::Config::max_trace_level].
+/// Takes the same arguments as [`tracing::event!`], after the target.
+macro_rules! event {
+    ($level:expr, $($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        if crate::config::traces($level) {
+            tracing::event!(target: "playspace", $level, $($arg)*);
+        }
+    };
+}
+pub(crate) use event;
+
+/// Take the Playspace lock with `lock`, tracing who holds it while waiting
+/// and how long the wait was
+#[cfg(feature = "tracing")]
+pub(crate) fn lock<L>(lock: impl FnOnce() -> L) -> L {
+    let _span = tracing::debug_span!(target: "playspace", "lock_wait").entered();
+    let start = waiting();
+    let out = lock();
+    acquired(start);
+    out
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn lock<L>(lock: impl FnOnce() -> L) -> L {
+    lock()
+}
+
+/// Async version of [`lock`]
+#[cfg(all(feature = "async", feature = "tracing"))]
+pub(crate) async fn lock_async<F: std::future::Future>(lock: F) -> F::Output {
+    use tracing::Instrument;
+
+    let span = tracing::debug_span!(target: "playspace", "lock_wait");
+    let start = span.in_scope(waiting);
+    let out = lock.instrument(span.clone()).await;
+    span.in_scope(|| acquired(start));
+    out
+}
+
+#[cfg(all(feature = "async", not(feature = "tracing")))]
+#[inline]
+pub(crate) async fn lock_async<F: std::future::Future>(lock: F) -> F::Output {
+    lock.await
+}
+
+#[cfg(feature = "tracing")]
+fn waiting() -> std::time::Instant {
+    if let crate::SpaceStatus::Held(holder) = crate::Playspace::status() {
+        event!(
+            tracing::Level::INFO,
+            root = %holder.root().display(),
+            thread = ?holder.thread(),
+            thread_name = holder.thread_name(),
+            held_for = ?holder.entered().elapsed(),
+            "waiting for Playspace lock",
+        );
+    }
+    std::time::Instant::now()
+}
+
+#[cfg(feature = "tracing")]
+fn acquired(start: std::time::Instant) {
+    event!(tracing::Level::DEBUG, waited = ?start.elapsed(), "acquired Playspace lock");
+}
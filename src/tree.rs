@@ -0,0 +1,178 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::path::{Path, PathBuf};
+
+use crate::{Playspace, WriteError};
+
+/// Declare a directory layout, see [`Tree`].
+///
+/// Entries are `name => contents` for files, or `name => { ... }` for
+/// directories, which may be nested and may be empty. Names may contain
+/// several path components. Contents can be anything that is `AsRef<[u8]>`.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::{tree, Playspace};
+/// let layout = tree! {
+///     "Cargo.toml" => "[package]\nname = \"example\"",
+///     "src" => {
+///         "main.rs" => "fn main() {}",
+///         "bin" => {},
+///     },
+///     "tests/data/input.bin" => [0u8, 1, 2],
+/// };
+///
+/// Playspace::scoped(|space| {
+///     space.write_tree(&layout).unwrap();
+///     assert!(space.directory().join("src/bin").is_dir());
+/// }).unwrap();
+/// ```
+#[macro_export]
+macro_rules! tree {
+    (@entries $tree:ident;) => {};
+    (@entries $tree:ident; $name:expr => { $($inner:tt)* } $(, $($rest:tt)*)?) => {
+        $tree = $tree.dir($name, $crate::tree! { $($inner)* });
+        $crate::tree!(@entries $tree; $($($rest)*)?);
+    };
+    (@entries $tree:ident; $name:expr => $contents:expr $(, $($rest:tt)*)?) => {
+        $tree = $tree.file($name, $contents);
+        $crate::tree!(@entries $tree; $($($rest)*)?);
+    };
+    ($($entries:tt)*) => {{
+        #[allow(unused_mut)]
+        let mut tree = $crate::Tree::new();
+        $crate::tree!(@entries tree; $($entries)*);
+        tree
+    }};
+}
+
+/// A declarative directory layout of files and (possibly empty) directories.
+///
+/// Usually built with the [`tree!`] macro, but can also be built directly.
+/// Write it to a Playspace with [`write_tree`][Playspace::write_tree], or
+/// pass it to [`with_files`][Playspace::with_files] and friends to apply it
+/// on entry. As an iterator, a `Tree` yields only its files (with their full
+/// relative paths), so empty directories are only created by `write_tree`.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::{Playspace, Tree};
+/// let layout = Tree::new()
+///     .file("Cargo.toml", "[package]")
+///     .dir("src", Tree::new().file("lib.rs", ""));
+///
+/// Playspace::scoped_with_files(layout, |space| {
+///     assert!(space.directory().join("src/lib.rs").exists());
+/// }).unwrap();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tree {
+    entries: Vec<(PathBuf, Entry)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entry {
+    File(Vec<u8>),
+    Dir(Tree),
+}
+
+impl Tree {
+    /// An empty layout.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file to the layout.
+    #[must_use]
+    pub fn file(mut self, path: impl Into<PathBuf>, contents: impl AsRef<[u8]>) -> Self {
+        self.entries
+            .push((path.into(), Entry::File(contents.as_ref().to_owned())));
+        self
+    }
+
+    /// Add a directory, and its contents, to the layout.
+    #[must_use]
+    pub fn dir(mut self, path: impl Into<PathBuf>, contents: Tree) -> Self {
+        self.entries.push((path.into(), Entry::Dir(contents)));
+        self
+    }
+
+    fn flatten(self, prefix: &Path, out: &mut Vec<(PathBuf, Option<Vec<u8>>)>) {
+        for (path, entry) in self.entries {
+            let path = prefix.join(path);
+            match entry {
+                Entry::File(contents) => out.push((path, Some(contents))),
+                Entry::Dir(tree) => {
+                    out.push((path.clone(), None));
+                    tree.flatten(&path, out);
+                }
+            }
+        }
+    }
+}
+
+impl IntoIterator for Tree {
+    type Item = (PathBuf, Vec<u8>);
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut entries = Vec::new();
+        self.flatten(Path::new(""), &mut entries);
+        entries
+            .into_iter()
+            .filter_map(|(path, contents)| Some((path, contents?)))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl Playspace {
+    /// Write a [`Tree`] layout to the Playspace.
+    ///
+    /// All directories in the layout are created, even if they are empty, as
+    /// are any missing parents. Every path is handled exactly as by
+    /// [`write_file`][Playspace::write_file].
+    ///
+    /// # Errors
+    ///
+    /// If any of the paths is not in the Playspace, an error will be returned.
+    /// Any stardard IO error is bubbled-up. Entries are written in order, and
+    /// writing stops at the first error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use playspace::{tree, Playspace};
+    /// Playspace::scoped(|space| {
+    ///     space.write_tree(&tree! {
+    ///         "logs" => {},
+    ///         "config" => { "app.toml" => "verbose = true" },
+    ///     }).unwrap();
+    /// }).unwrap();
+    /// ```
+    pub fn write_tree(&self, tree: &Tree) -> Result<(), WriteError> {
+        let mut entries = Vec::new();
+        tree.clone().flatten(Path::new(""), &mut entries);
+
+        for (path, contents) in entries {
+            let path = self.playspace_path(path)?;
+            match contents {
+                Some(contents) => {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .map_err(|source| WriteError::io(parent, source))?;
+                    }
+                    std::fs::write(&path, contents)
+                        .map_err(|source| WriteError::io(&path, source))?;
+                }
+                None => std::fs::create_dir_all(&path)
+                    .map_err(|source| WriteError::io(&path, source))?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Recursively list everything under `root`, as paths relative to `root`.
+///
+/// Symlinks are listed but not followed. Parents are always listed before
+/// their children, and the output is sorted.
+pub(crate) fn walk(root: &Path) -> Result<Vec<PathBuf>, io::Error> {
+    let mut out = Vec::new();
+    walk_into(root, Path::new(""), &mut out)?;
+    out.sort();
+    Ok(out)
+}
+
+fn walk_into(root: &Path, relative: &Path, out: &mut Vec<PathBuf>) -> Result<(), io::Error> {
+    for entry in std::fs::read_dir(root.join(relative))? {
+        let entry = entry?;
+        let path = relative.join(entry.file_name());
+        let is_dir = entry.file_type()?.is_dir();
+        out.push(path.clone());
+        if is_dir {
+            walk_into(root, &path, out)?;
+        }
+    }
+    Ok(())
+}
+
+/// Absolute paths to everything left in `root` that has no children left
+/// (i.e. files, symlinks, and empty directories)
+///
+/// Used to find what stopped `root` being removed. Errors are ignored, since
+/// this is only used to explain another error.
+pub(crate) fn blocking(root: &Path) -> Vec<PathBuf> {
+    let Ok(paths) = walk(root) else {
+        return Vec::new();
+    };
+    // Parents are listed immediately before their first child
+    let mut paths = paths.into_iter().peekable();
+    let mut out = Vec::new();
+    while let Some(path) = paths.next() {
+        if !paths.peek().is_some_and(|next| next.starts_with(&path)) {
+            out.push(root.join(path));
+        }
+    }
+    out
+}
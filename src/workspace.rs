@@ -0,0 +1,91 @@
+//  SPDX-License-Identifier: MIT OR Apache-2.0
+//  Licensed under either MIT Apache 2.0 licenses (attached), at your option.
+
+use std::{ops::Deref, path::PathBuf};
+
+use static_assertions::assert_impl_all;
+
+use crate::{default_directory, ExitError, Playspace, SpaceError};
+
+/// Just a temporary directory, with all the path-guarded helpers of a
+/// [`Playspace`].
+///
+/// A `Workspace` takes no lock, never changes the working directory, and
+/// never touches the environment, so any number can be used at once, from
+/// any number of threads, without waiting. It is the same as a Playspace
+/// built with [`chdir(false)`][crate::PlayspaceBuilder::chdir], for the many
+/// tests which only need a scratch directory.
+///
+/// The Playspace helpers are available through [`Deref`], and resolve
+/// relative paths against the workspace root. Those which change the
+/// environment or working directory (e.g. [`set_envs`][Playspace::set_envs])
+/// should not be used, since nothing is restored. The directory is removed
+/// when the `Workspace` is dropped, or [`exit`][Workspace::exit]ed to handle
+/// any errors.
+///
+/// # Example
+///
+/// ```rust
+/// # use playspace::Workspace;
+/// let workspace = Workspace::new().unwrap();
+/// workspace.write_files([("config/app.toml", "key = 1")]).unwrap();
+/// assert!(workspace.write_file("../escape.txt", "").is_err());
+///
+/// let config = workspace.directory().join("config/app.toml");
+/// assert_eq!(std::fs::read_to_string(config).unwrap(), "key = 1");
+/// workspace.exit().unwrap();
+/// ```
+pub struct Workspace {
+    space: Playspace,
+}
+
+assert_impl_all!(Workspace: Send);
+
+impl Workspace {
+    /// Create a new, empty workspace directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpaceError::StdIo`] if the directory could not be created.
+    pub fn new() -> Result<Self, SpaceError> {
+        Ok(Self {
+            space: Playspace::without_chdir(default_directory)?,
+        })
+    }
+
+    /// Remove the workspace directory, reporting any errors doing so.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`Playspace::exit`], though only removing the directory
+    /// (and running any [exit checks][Playspace::verify_on_exit]) can fail.
+    pub fn exit(self) -> Result<(), ExitError> {
+        self.space.exit()
+    }
+
+    /// Keep the workspace directory, returning the path to it, as for
+    /// [`Playspace::into_path`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`exit`][Workspace::exit].
+    pub fn into_path(self) -> Result<PathBuf, ExitError> {
+        self.space.into_path()
+    }
+}
+
+impl std::fmt::Debug for Workspace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Workspace")
+            .field("directory", &self.directory())
+            .finish()
+    }
+}
+
+impl Deref for Workspace {
+    type Target = Playspace;
+
+    fn deref(&self) -> &Playspace {
+        &self.space
+    }
+}
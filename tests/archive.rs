@@ -0,0 +1,159 @@
+#![cfg(feature = "archive")]
+
+use std::{fs::File, io::Read, path::Path};
+
+use playspace::{ArchiveError, Playspace};
+use serial_test::serial;
+
+fn write_contents(space: &Playspace) {
+    space
+        .write_files([
+            ("out/report.xml", "<testsuites/>"),
+            ("out/nested/data.txt", "data"),
+            ("top.txt", "top"),
+        ])
+        .unwrap();
+    space.create_dir_all("empty").unwrap();
+}
+
+#[test]
+#[serial]
+fn export_tar_gz() {
+    let artifacts = tempfile::tempdir().unwrap();
+    let archive = artifacts.path().join("space.tar.gz");
+    Playspace::scoped(|space| {
+        write_contents(space);
+        space.export_tar_gz(&archive).unwrap();
+    })
+    .unwrap();
+
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(File::open(&archive).unwrap()));
+    let mut names = Vec::new();
+    for entry in tar.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let name = entry.path().unwrap().to_string_lossy().into_owned();
+        if name == "out/report.xml" {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            assert_eq!(contents, "<testsuites/>");
+        }
+        names.push(name);
+    }
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "empty",
+            "out",
+            "out/nested",
+            "out/nested/data.txt",
+            "out/report.xml",
+            "top.txt"
+        ]
+    );
+}
+
+#[test]
+#[serial]
+fn export_zip() {
+    let artifacts = tempfile::tempdir().unwrap();
+    let archive = artifacts.path().join("space.zip");
+    Playspace::scoped(|space| {
+        write_contents(space);
+        space.export_zip(&archive).unwrap();
+    })
+    .unwrap();
+
+    let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+    let mut names: Vec<_> = zip.file_names().map(ToOwned::to_owned).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "empty/",
+            "out/",
+            "out/nested/",
+            "out/nested/data.txt",
+            "out/report.xml",
+            "top.txt"
+        ]
+    );
+    let mut contents = String::new();
+    zip.by_name("out/nested/data.txt")
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "data");
+}
+
+#[test]
+#[serial]
+fn export_inside_space() {
+    Playspace::scoped(|space| {
+        space.write_file("file.txt", "").unwrap();
+        space.export_zip("space.zip").unwrap();
+        let zip = zip::ZipArchive::new(File::open("space.zip").unwrap()).unwrap();
+        assert_eq!(zip.file_names().collect::<Vec<_>>(), ["file.txt"]);
+    })
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn round_trip() {
+    let artifacts = tempfile::tempdir().unwrap();
+    let tar_gz = artifacts.path().join("space.tar.gz");
+    let zip = artifacts.path().join("space.zip");
+    Playspace::scoped(|space| {
+        write_contents(space);
+        space.export_tar_gz(&tar_gz).unwrap();
+        space.export_zip(&zip).unwrap();
+    })
+    .unwrap();
+
+    for archive in [&tar_gz, &zip] {
+        let space = Playspace::from_archive(archive).expect("Failed to create space");
+        assert_eq!(
+            std::fs::read_to_string("out/report.xml").unwrap(),
+            "<testsuites/>"
+        );
+        assert_eq!(
+            std::fs::read_to_string("out/nested/data.txt").unwrap(),
+            "data"
+        );
+        assert!(Path::new("empty").is_dir());
+
+        space.extract(archive, "again").unwrap();
+        assert_eq!(std::fs::read_to_string("again/top.txt").unwrap(), "top");
+        space.exit().unwrap();
+    }
+}
+
+#[test]
+#[serial]
+fn extract_plain_tar() {
+    let artifacts = tempfile::tempdir().unwrap();
+    let archive = artifacts.path().join("plain.tar");
+    let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(5);
+    header.set_mode(0o644);
+    builder
+        .append_data(&mut header, "dir/file.txt", "hello".as_bytes())
+        .unwrap();
+    builder.finish().unwrap();
+    drop(builder);
+
+    Playspace::scoped(|space| {
+        space.extract(&archive, ".").unwrap();
+        assert_eq!(std::fs::read_to_string("dir/file.txt").unwrap(), "hello");
+
+        #[allow(clippy::match_wild_err_arm)]
+        match space.extract(&archive, "../outside") {
+            Err(ArchiveError::Write(_)) => (),
+            Err(_) => panic!("Wrong error"),
+            Ok(()) => panic!("Should not have worked"),
+        }
+    })
+    .unwrap();
+}
@@ -4,12 +4,11 @@ use std::{
     path::PathBuf,
     sync::{
         atomic::{AtomicU32, Ordering},
-        Arc,
+        Arc, LazyLock,
     },
 };
 
 use futures::FutureExt;
-use lazy_static::lazy_static;
 use parking_lot::Mutex;
 
 use playspace::Playspace;
@@ -18,9 +17,8 @@ const ABSENT: &str = "SOME_ABSENT_ENVVAR";
 const PRESENT: &str = "SOME_PRESENT_ENVVAR";
 const TRANSIENT: &str = "SOME_TRANSIENT_ENVVAR";
 
-lazy_static! {
-    static ref SERIAL: async_std::sync::Mutex<()> = async_std::sync::Mutex::new(());
-}
+static SERIAL: LazyLock<async_std::sync::Mutex<()>> =
+    LazyLock::new(|| async_std::sync::Mutex::new(()));
 
 fn set_vars_before() {
     std::env::remove_var(ABSENT);
@@ -69,14 +67,17 @@ async fn files_and_envs() {
                 (PRESENT, Some("present_value_during")),
             ]);
 
-            let mut path_during = path_during.lock();
-            space
-                .write_file(&*path_during, "some file contents")
-                .unwrap();
-            *path_during = space.directory().join("some_file.txt");
+            let path_inside = {
+                let mut path_during = path_during.lock();
+                space
+                    .write_file(&*path_during, "some file contents")
+                    .unwrap();
+                *path_during = space.directory().join("some_file.txt");
+                path_during.clone()
+            };
 
             assert_eq!(
-                async_std::fs::read_to_string(&*path_during).await.unwrap(),
+                async_std::fs::read_to_string(&path_inside).await.unwrap(),
                 "some file contents"
             );
 
@@ -145,3 +146,17 @@ async fn wait_when_spaced() {
 
     assert_eq!(counter1.load(Ordering::Acquire), 4);
 }
+
+#[async_std::test]
+async fn exit_async() {
+    let _serial = SERIAL.lock().await;
+
+    let space = Playspace::new_async()
+        .await
+        .expect("Failed to create playspace");
+    let directory = space.directory().to_owned();
+    // Outside of a tokio runtime
+    space.exit_async().await.expect("Failed to exit");
+    assert!(!directory.exists());
+    assert!(!Playspace::is_active());
+}
@@ -0,0 +1,35 @@
+#![cfg(feature = "cap-std")]
+
+use serial_test::serial;
+
+use playspace::Playspace;
+
+#[test]
+#[serial]
+fn dir() {
+    Playspace::scoped(|space| {
+        let dir = space.dir().unwrap();
+        dir.create_dir_all("nested/deeper").unwrap();
+        dir.write("nested/deeper/file.txt", "contents").unwrap();
+        space.assert_file_contents("nested/deeper/file.txt", "contents");
+
+        // Relative to the root, not the working directory
+        std::env::set_current_dir("nested").unwrap();
+        assert_eq!(
+            dir.read_to_string("nested/deeper/file.txt").unwrap(),
+            "contents"
+        );
+
+        // Can't escape
+        let outside = space.directory().parent().unwrap().join("outside.txt");
+        assert!(dir.write(&outside, "outside").is_err());
+        assert!(dir.write("../outside.txt", "outside").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("..", space.directory().join("up")).unwrap();
+            assert!(dir.write("up/outside.txt", "outside").is_err());
+        }
+        assert!(!outside.exists());
+    })
+    .unwrap();
+}
@@ -5,7 +5,7 @@ use std::sync::{
 
 use serial_test::serial;
 
-use playspace::{Playspace, SpaceError};
+use playspace::{Playspace, ReadOnlySpace, SpaceError};
 
 #[test]
 #[serial]
@@ -87,3 +87,140 @@ fn fail_when_spaced_thread() {
 
     assert!(Playspace::try_new().is_ok());
 }
+
+#[test]
+#[serial]
+fn read_only_spaces() {
+    // Many at once, from many threads
+    let barrier = std::sync::Barrier::new(3);
+    std::thread::scope(|scope| {
+        for _ in 0..3 {
+            scope.spawn(|| {
+                ReadOnlySpace::scoped(|| {
+                    // Every thread holds one here at the same time
+                    barrier.wait();
+                    assert!(matches!(
+                        Playspace::try_new(),
+                        Err(SpaceError::AlreadyInSpace)
+                    ));
+                    barrier.wait();
+                })
+                .expect("Failed to share");
+            });
+        }
+    });
+
+    // But never alongside a Playspace
+    let space = Playspace::new().expect("Failed to create space");
+    assert!(matches!(
+        ReadOnlySpace::try_new(),
+        Err(SpaceError::AlreadyInSpace)
+    ));
+    let entered = Arc::new(AtomicU32::new(0));
+    let handle = std::thread::spawn({
+        let entered = entered.clone();
+        move || {
+            let _shared = ReadOnlySpace::new().expect("Failed to share");
+            entered.store(1, Ordering::Release);
+        }
+    });
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    assert_eq!(entered.load(Ordering::Acquire), 0);
+    space.exit().expect("Failed to exit space");
+    handle.join().unwrap();
+    assert_eq!(entered.load(Ordering::Acquire), 1);
+}
+
+#[test]
+#[serial]
+fn read_inside_read() {
+    ReadOnlySpace::scoped(|| {
+        // A waiting Playspace makes any new reader wait behind it
+        let handle = std::thread::spawn(|| {
+            Playspace::new()
+                .expect("Failed to wait for space")
+                .exit()
+                .expect("Failed to exit space");
+        });
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(matches!(
+            ReadOnlySpace::new(),
+            Err(SpaceError::WouldDeadlock)
+        ));
+        assert!(matches!(
+            ReadOnlySpace::scoped(|| ()),
+            Err(SpaceError::WouldDeadlock)
+        ));
+        handle
+    })
+    .expect("Failed to share")
+    .join()
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn would_deadlock() {
+    let space = Playspace::new().expect("Failed to create space");
+    assert!(matches!(Playspace::new(), Err(SpaceError::WouldDeadlock)));
+    assert!(matches!(
+        Playspace::scoped(|_space| ()),
+        Err(SpaceError::WouldDeadlock)
+    ));
+    assert!(matches!(
+        Playspace::builder().build(),
+        Err(SpaceError::WouldDeadlock)
+    ));
+    assert!(matches!(
+        Playspace::scoped_in_thread(|_space| ()),
+        Err(SpaceError::WouldDeadlock)
+    ));
+    assert!(matches!(
+        Playspace::try_new(),
+        Err(SpaceError::AlreadyInSpace)
+    ));
+    assert!(matches!(
+        ReadOnlySpace::new(),
+        Err(SpaceError::WouldDeadlock)
+    ));
+
+    // Once handed to another thread, waiting is fine again
+    let detached = space.detach();
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        detached.attach().exit().expect("Failed to exit space");
+    });
+    Playspace::new()
+        .expect("Failed to wait for space")
+        .exit()
+        .expect("Failed to exit space");
+    handle.join().unwrap();
+
+    ReadOnlySpace::scoped(|| {
+        assert!(matches!(Playspace::new(), Err(SpaceError::WouldDeadlock)));
+        assert!(matches!(
+            Playspace::scoped(|_space| ()),
+            Err(SpaceError::WouldDeadlock)
+        ));
+    })
+    .expect("Failed to share");
+}
+
+#[test]
+#[serial]
+fn moved_without_detach() {
+    // `Playspace` is `Send`, but one moved to another thread without being
+    // detached is still held by this one, so waiting is reported here even
+    // though the other thread would have exited it
+    let space = Playspace::new().expect("Failed to create space");
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        space.exit().expect("Failed to exit space");
+    });
+    assert!(matches!(Playspace::new(), Err(SpaceError::WouldDeadlock)));
+    handle.join().unwrap();
+    Playspace::new()
+        .expect("Failed to create space")
+        .exit()
+        .expect("Failed to exit space");
+}
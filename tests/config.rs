@@ -0,0 +1,55 @@
+//! Configuration is process-wide, so is tested alone in this binary
+
+use playspace::{
+    config::{self, Config, ConfigureError},
+    DropErrorPolicy, Playspace,
+};
+
+#[test]
+fn configure() {
+    let root = tempfile::tempdir().expect("Failed to create root");
+    let temp_root = root.path().join("spaces");
+    config::configure(
+        Config::new()
+            .temp_root(&temp_root)
+            .keep_on_failure()
+            .on_drop_error(DropErrorPolicy::Log),
+    )
+    .expect("Failed to configure");
+
+    #[allow(clippy::match_wild_err_arm)]
+    match config::configure(Config::new()) {
+        Err(ConfigureError::AlreadyConfigured) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+
+    // Both constructors and builders use the defaults
+    let space = Playspace::new().expect("Failed to create space");
+    assert!(space.directory().starts_with(&temp_root));
+    space.exit().expect("Failed to exit");
+    let space = Playspace::builder()
+        .build()
+        .expect("Failed to create space");
+    assert!(space.directory().starts_with(&temp_root));
+
+    // Kept on failure
+    space.verify_on_exit(|_space| Err("failed".to_owned()));
+    let directory = space.directory().to_owned();
+    assert!(space.exit().is_err());
+    assert!(directory.exists());
+
+    // Logged rather than panicking when dropped
+    let space = Playspace::new().expect("Failed to create space");
+    std::fs::remove_dir_all(space.directory()).unwrap();
+    drop(space);
+
+    // Builder options take precedence
+    let space = Playspace::builder()
+        .on_drop_error(DropErrorPolicy::Panic)
+        .build()
+        .expect("Failed to create space");
+    std::fs::remove_dir_all(space.directory()).unwrap();
+    let dropped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(space)));
+    assert!(dropped.is_err());
+}
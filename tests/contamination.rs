@@ -0,0 +1,47 @@
+use std::ffi::OsStr;
+
+use playspace::{OnContamination, Playspace, SpaceError};
+
+// The baseline is global to the process, so everything is in a single test
+#[test]
+fn contamination() {
+    Playspace::detect_contamination(OnContamination::Fail);
+
+    // Changes inside a Playspace are undone, so don't count
+    Playspace::scoped(|space| {
+        space.set_envs([("SET_INSIDE_A_SPACE", Some("value"))]);
+    })
+    .expect("Failed to use playspace");
+    Playspace::new()
+        .expect("Should not be contaminated")
+        .exit()
+        .unwrap();
+
+    std::env::set_var("LEAKED_ENVVAR", "leaked");
+    #[allow(clippy::match_wild_err_arm)]
+    match Playspace::new() {
+        Err(SpaceError::Contaminated(contamination)) => {
+            assert!(contamination
+                .environment
+                .added
+                .contains_key(OsStr::new("LEAKED_ENVVAR")));
+            assert!(contamination.working_dir.is_none());
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+
+    // Only reported once
+    Playspace::new()
+        .expect("Should have a new baseline")
+        .exit()
+        .unwrap();
+
+    let original = std::env::current_dir().unwrap();
+    std::env::set_current_dir(std::env::temp_dir()).unwrap();
+    Playspace::detect_contamination(OnContamination::Warn);
+    Playspace::new().expect("Should only warn").exit().unwrap();
+
+    std::env::set_current_dir(original).unwrap();
+    std::env::remove_var("LEAKED_ENVVAR");
+}
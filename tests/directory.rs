@@ -1,6 +1,8 @@
 use serial_test::serial;
 
-use playspace::Playspace;
+use playspace::{
+    DropErrorPolicy, EnvPreset, ExitError, ExitStep, Playspace, SpaceError, SpaceStatus,
+};
 
 #[test]
 #[serial]
@@ -10,6 +12,7 @@ fn new_temporary() {
 
     {
         let space = Playspace::new().expect("Failed to create space");
+        assert_eq!(space.original_directory(), Some(original.as_path()));
 
         let spaced = std::env::current_dir().expect("Invalid spaced dir");
         assert_ne!(original, spaced);
@@ -54,6 +57,7 @@ fn starting_invalid() {
     assert!(std::env::current_dir().is_err());
 
     let space = Playspace::new().expect("Failed to create space");
+    assert!(space.original_directory().is_none());
 
     let spaced_cwd = std::env::current_dir().expect("Failed to get spaced CWD");
     assert!(spaced_cwd.exists());
@@ -61,7 +65,10 @@ fn starting_invalid() {
 
     drop(space);
 
-    assert!(std::env::current_dir().is_err());
+    // Moved out of the Playspace, rather than left in its removed directory
+    let ending = std::env::current_dir().expect("Left in removed directory");
+    assert!(!spaced_cwd.exists());
+    assert_ne!(ending, spaced_cwd);
 
     let space2 = Playspace::new().expect("Failed to create second space");
     let spaced_cwd = std::env::current_dir().expect("Failed to get spaced CWD");
@@ -69,8 +76,742 @@ fn starting_invalid() {
     assert_ne!(temp_path, spaced_cwd);
 
     drop(space2);
-    assert!(std::env::current_dir().is_err());
+    assert!(std::env::current_dir().is_ok());
 
     // Tidy up to be nice to other tests
     std::env::set_current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap()).unwrap();
 }
+
+// Disabled on Windows for the same reason as `starting_invalid`
+#[cfg(not(target_os = "windows"))]
+#[test]
+#[serial]
+fn fallback_directory() {
+    let fallback = tempfile::tempdir().expect("Failed to create fallback dir");
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    std::env::set_current_dir(temp_dir.path()).expect("Failed to move to temp dir");
+
+    let space = Playspace::builder()
+        .fallback_directory(fallback.path())
+        .build()
+        .expect("Failed to create space");
+    // The original directory disappears while in the Playspace
+    temp_dir.close().unwrap();
+    space.exit().expect("Failed to exit to fallback");
+
+    assert_eq!(
+        std::env::current_dir().unwrap().canonicalize().unwrap(),
+        fallback.path().canonicalize().unwrap()
+    );
+
+    std::env::set_current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap()).unwrap();
+}
+
+#[test]
+#[serial]
+fn exit_report() {
+    let space = Playspace::new().expect("Failed to create space");
+    let report = space.exit_with_report().expect("Failed to exit");
+    assert!(report.is_clean());
+
+    let space = Playspace::new().expect("Failed to create space");
+    space
+        .write_files([("left/behind.txt", ""), ("other.txt", "")])
+        .unwrap();
+    let directory = space.directory().to_owned();
+    let report = space.exit_with_report().expect("Failed to exit");
+    assert!(!report.is_clean());
+    assert_eq!(
+        report.leftover(),
+        ["left", "left/behind.txt", "other.txt"].map(std::path::PathBuf::from)
+    );
+    assert!(!directory.exists());
+}
+
+#[test]
+#[serial]
+fn cwd_left_inside() {
+    let original = std::env::current_dir().expect("Invalid starting dir");
+
+    let space = Playspace::new().expect("Failed to create space");
+    std::fs::create_dir("a_subdir").expect("Failed to create subdirectory");
+    std::env::set_current_dir("a_subdir").expect("Failed to move to subdirectory");
+    let report = space.exit_report();
+    assert!(report.cwd_left_inside().is_none());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+
+    // Nothing restores the working directory of a space which didn't change it
+    let space = Playspace::builder()
+        .chdir(false)
+        .build()
+        .expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    space.create_dir_all("a_subdir").unwrap();
+    std::env::set_current_dir(directory.join("a_subdir")).unwrap();
+    let report = space.exit_report();
+    assert!(report.is_ok());
+    assert!(report.cwd_left_inside().unwrap().ends_with("a_subdir"));
+    assert!(!directory.exists());
+    let cwd = std::env::current_dir().expect("Left in removed directory");
+    assert!(!cwd.starts_with(&directory));
+
+    std::env::set_current_dir(original).unwrap();
+}
+
+#[test]
+#[serial]
+fn log_lines() {
+    let space = Playspace::new().expect("Failed to create space");
+    space.log("first").expect("Failed to log");
+    let value = 2;
+    playspace::space_log!(space, "second {value}").expect("Failed to log");
+
+    let log = std::fs::read_to_string(space.directory().join(playspace::LOG_FILE)).unwrap();
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with('[') && lines[0].ends_with("] first"));
+    assert!(lines[1].ends_with("] second 2"));
+
+    let report = space.exit_with_report().expect("Failed to exit");
+    assert!(report.is_clean());
+}
+
+#[test]
+#[serial]
+fn exit_checks() {
+    let space = Playspace::new().expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    space.verify_on_exit(|space| {
+        assert!(space.directory().exists());
+        Err("first".to_owned())
+    });
+    space.verify_on_exit(|_space| Ok(()));
+    space.verify_on_exit(|_space| Err("second".to_owned()));
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.exit() {
+        Err(ExitError::VerificationFailed { failures }) => {
+            assert_eq!(failures, ["first", "second"]);
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    // Cleanup still happened
+    assert!(!directory.exists());
+
+    let space = Playspace::new().expect("Failed to create space");
+    space.verify_on_exit(|_space| Ok(()));
+    space.exit().expect("Checks should have passed");
+}
+
+#[test]
+#[serial]
+fn exit_steps() {
+    let space = Playspace::new().expect("Failed to create space");
+    let report = space.exit_report();
+    assert!(report.is_ok());
+    assert!(report
+        .steps()
+        .iter()
+        .any(|(step, _)| *step == ExitStep::TempDir));
+
+    let space = Playspace::new().expect("Failed to create space");
+    space.verify_on_exit(|_space| Err("failed".to_owned()));
+    std::fs::remove_dir_all(space.directory()).unwrap();
+
+    let report = space.exit_report();
+    let failed: Vec<_> = report.failures().map(|(step, _)| step).collect();
+    assert_eq!(
+        failed,
+        [ExitStep::Checks, ExitStep::TempDir, ExitStep::Leftover]
+    );
+
+    // Every failure is reported, but checks take precedence as an error
+    #[allow(clippy::match_wild_err_arm)]
+    match report.into_result() {
+        Err(ExitError::VerificationFailed { failures }) => assert_eq!(failures, ["failed"]),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+#[serial]
+fn link_origin() {
+    let original = std::env::current_dir().expect("Invalid starting dir");
+
+    let space = Playspace::builder()
+        .link_origin("links/__origin__")
+        .build()
+        .expect("Failed to create space");
+
+    let link = space.directory().join("links/__origin__");
+    assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+    assert_eq!(
+        link.canonicalize().unwrap(),
+        original.canonicalize().unwrap()
+    );
+    assert!(std::path::Path::new("links/__origin__/Cargo.toml").is_file());
+
+    // The helpers refuse to write through the link, or out of the space by any
+    // other relative path
+    #[allow(clippy::match_wild_err_arm)]
+    match space.write_file("links/__origin__/should-not-exist.txt", "") {
+        Err(playspace::WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    assert!(!space.contains("../should-not-exist.txt"));
+    assert!(space.contains("links/not-origin.txt"));
+
+    space.exit().expect("Failed to exit");
+    assert!(!original.join("should-not-exist.txt").exists());
+}
+
+#[test]
+#[serial]
+fn reset() {
+    let original = std::env::current_dir().expect("Invalid starting dir");
+    std::env::remove_var("__PLAYSPACE_RESET");
+    std::env::set_var("CI", "true");
+
+    let mut space = Playspace::builder()
+        .link_origin("__origin__")
+        .preset(EnvPreset::clean_ci())
+        .build()
+        .expect("Failed to create space");
+    let directory = space.directory().to_owned();
+
+    for _ in 0..2 {
+        space
+            .write_files([("nested/file.txt", ""), ("file.txt", "")])
+            .unwrap();
+        space.set_envs([("__PLAYSPACE_RESET", Some("value"))]);
+        std::env::set_current_dir("nested").unwrap();
+
+        space.reset().expect("Failed to reset");
+
+        assert!(std::env::var("__PLAYSPACE_RESET").is_err());
+        // Presets are applied again
+        assert!(std::env::var("CI").is_err());
+        assert_eq!(
+            std::env::current_dir().unwrap().canonicalize().unwrap(),
+            directory.canonicalize().unwrap()
+        );
+        let entries: Vec<_> = std::fs::read_dir(&directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, ["__origin__"]);
+        // The original directory is untouched
+        assert!(original.join("Cargo.toml").is_file());
+    }
+
+    space.exit().expect("Failed to exit");
+    assert_eq!(std::env::var("CI").unwrap(), "true");
+    std::env::remove_var("CI");
+}
+
+#[test]
+#[serial]
+fn sequential_names() {
+    let root = std::env::temp_dir().join("playspace-sequential-test");
+    let stale = root.join("playspace-0002");
+    std::fs::create_dir_all(&stale).unwrap();
+    std::fs::write(stale.join("stale.txt"), "").unwrap();
+
+    let mut names = Vec::new();
+    for _ in 0..2 {
+        let space = Playspace::builder()
+            .sequential_names(&root)
+            .build()
+            .expect("Failed to create space");
+        assert!(space.directory().starts_with(&root));
+        assert_eq!(space.snapshot().unwrap().len(), 0);
+        names.push(space.directory().file_name().unwrap().to_owned());
+    }
+    // Only this test creates sequential spaces in this process
+    assert_eq!(names, ["playspace-0001", "playspace-0002"]);
+    assert!(!stale.exists());
+    std::fs::remove_dir(root).unwrap();
+}
+
+#[test]
+#[serial]
+fn retained_on_panic() {
+    let report_dir = tempfile::tempdir().unwrap();
+    let report = report_dir.path().join("reports/retained.jsonl");
+    let report_clone = report.clone();
+
+    let retained = std::thread::Builder::new()
+        .name("retained_on_panic".to_owned())
+        .spawn(move || {
+            let space = Playspace::builder()
+                .retention_report(report_clone, playspace::RetentionFormat::Json)
+                .build()
+                .expect("Failed to create space");
+            space.write_file("evidence.txt", "").unwrap();
+            let directory = space.directory().to_owned();
+            std::panic::panic_any(directory);
+        })
+        .unwrap()
+        .join()
+        .expect_err("Should have panicked");
+    let retained = retained.downcast::<std::path::PathBuf>().unwrap();
+
+    assert!(retained.join("evidence.txt").exists());
+    let contents = std::fs::read_to_string(&report).unwrap();
+    assert_eq!(
+        contents,
+        format!(
+            "{{\"test\":\"retained_on_panic\",\"path\":\"{}\",\"reason\":\"panicked\"}}\n",
+            retained.display()
+        )
+    );
+    std::fs::remove_dir_all(*retained).unwrap();
+}
+
+#[test]
+#[serial]
+fn retained_on_failed_check() {
+    let report = tempfile::NamedTempFile::new().unwrap();
+
+    let space = Playspace::builder()
+        .retention_report(report.path(), playspace::RetentionFormat::JunitProperties)
+        .build()
+        .expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    space.verify_on_exit(|_space| Err("failed".to_owned()));
+    assert!(space.exit().is_err());
+
+    assert!(directory.exists());
+    let contents = std::fs::read_to_string(report.path()).unwrap();
+    assert!(contents.contains("<property name=\"playspace.retained.retained_on_failed_check\""));
+    assert!(contents.contains("value=\"exit checks failed\"/>"));
+    std::fs::remove_dir_all(directory).unwrap();
+
+    // Nothing is retained without a failure
+    let space = Playspace::builder()
+        .keep_on_failure()
+        .build()
+        .expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    drop(space);
+    assert!(!directory.exists());
+}
+
+#[test]
+#[serial]
+fn clock_and_ids() {
+    use playspace::clock::FixedClock;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let time = UNIX_EPOCH + Duration::from_millis(1_500);
+    let space = Playspace::builder()
+        .clock(FixedClock(time))
+        .id_source(|| "clock-and-ids-test".to_owned())
+        .build()
+        .expect("Failed to create space");
+
+    assert_eq!(
+        space.directory(),
+        std::env::temp_dir().join("playspace-clock-and-ids-test")
+    );
+    assert_eq!(space.now(), time);
+
+    space.log("message").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(playspace::LOG_FILE).unwrap(),
+        "[1.500] message\n"
+    );
+    space.touch("touched").unwrap();
+    assert_eq!(
+        std::fs::metadata("touched").unwrap().modified().unwrap(),
+        time
+    );
+
+    let directory = space.directory().to_owned();
+    space.exit().expect("Failed to exit");
+    assert!(!directory.exists());
+}
+
+#[test]
+#[serial]
+fn detach_attach() {
+    let space = Playspace::new().expect("Failed to create space");
+    space.write_file("setup.txt", "").unwrap();
+    let directory = space.directory().to_owned();
+    let detached = space.detach();
+
+    // Still held while detached
+    assert!(Playspace::try_new().is_err());
+
+    std::thread::spawn(move || {
+        let space = detached.attach();
+        assert!(space.exists("setup.txt"));
+        space.exit().expect("Failed to exit");
+    })
+    .join()
+    .expect("Worker panicked");
+
+    assert!(!directory.exists());
+    Playspace::try_new()
+        .expect("Should have been released")
+        .exit()
+        .unwrap();
+}
+
+#[test]
+#[serial]
+fn quarantine() {
+    let quarantine = tempfile::tempdir().expect("Failed to create quarantine");
+    std::fs::create_dir(quarantine.path().join("left-by-previous-run")).unwrap();
+
+    let space = Playspace::builder()
+        .quarantine(quarantine.path())
+        .build()
+        .expect("Failed to create space");
+    space.write_files([("some/file.txt", "")]).unwrap();
+    let directory = space.directory().to_owned();
+    space.exit().expect("Failed to exit");
+    assert!(!directory.exists());
+
+    // Eventually swept, along with anything from previous runs
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while std::fs::read_dir(quarantine.path())
+        .unwrap()
+        .next()
+        .is_some()
+    {
+        assert!(std::time::Instant::now() < deadline, "Quarantine not swept");
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+}
+
+#[test]
+#[serial]
+fn canonical_root() {
+    let space = Playspace::new().expect("Failed to create space");
+    assert_eq!(
+        space.directory_canonical(),
+        space.directory().canonicalize().unwrap()
+    );
+    space.create_dir_all("sub").unwrap();
+    std::env::set_current_dir("sub").unwrap();
+    assert_eq!(
+        std::env::current_dir().unwrap(),
+        space.directory_canonical().join("sub")
+    );
+}
+
+#[test]
+#[serial]
+fn current_root() {
+    assert!(Playspace::current_root().is_none());
+
+    let space = Playspace::new().expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    assert_eq!(Playspace::current_root(), Some(directory.clone()));
+
+    // Visible from other threads too
+    let from_thread = std::thread::spawn(Playspace::current_root).join().unwrap();
+    assert_eq!(from_thread, Some(directory));
+
+    space.verify_on_exit(|space| {
+        assert_eq!(
+            Playspace::current_root().as_deref(),
+            Some(space.directory())
+        );
+        Ok(())
+    });
+    space.exit().expect("Failed to exit");
+    assert!(Playspace::current_root().is_none());
+}
+
+#[test]
+#[serial]
+fn status() {
+    assert!(!Playspace::is_active());
+    assert_eq!(Playspace::status(), SpaceStatus::Free);
+
+    let space = Playspace::new().expect("Failed to create space");
+    assert!(Playspace::is_active());
+    let SpaceStatus::Held(holder) = Playspace::status() else {
+        panic!("Should be held");
+    };
+    assert_eq!(holder.root(), space.directory());
+    assert_eq!(holder.thread(), std::thread::current().id());
+    assert_eq!(holder.thread_name(), Some("status"));
+
+    // Attaching on another thread makes it the holder
+    let detached = space.detach();
+    let worker = std::thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(move || {
+            let space = detached.attach();
+            let SpaceStatus::Held(holder) = Playspace::status() else {
+                panic!("Should be held");
+            };
+            assert_eq!(holder.thread_name(), Some("worker"));
+            space.exit().expect("Failed to exit");
+        })
+        .unwrap();
+    worker.join().expect("Worker panicked");
+
+    assert!(!Playspace::is_active());
+}
+
+#[test]
+#[serial]
+fn drop_error_policy() {
+    let space = Playspace::builder()
+        .on_drop_error(DropErrorPolicy::Panic)
+        .build()
+        .expect("Failed to create space");
+    // Exiting fails if the directory has already gone
+    std::fs::remove_dir_all(space.directory()).unwrap();
+    let dropped = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| drop(space)));
+    assert!(dropped.is_err());
+    assert!(!Playspace::is_active());
+
+    for policy in [DropErrorPolicy::Log, DropErrorPolicy::Ignore] {
+        let space = Playspace::builder()
+            .on_drop_error(policy)
+            .build()
+            .expect("Failed to create space");
+        std::fs::remove_dir_all(space.directory()).unwrap();
+        drop(space);
+    }
+
+    // Clean exits never panic
+    drop(
+        Playspace::builder()
+            .on_drop_error(DropErrorPolicy::Panic)
+            .build()
+            .expect("Failed to create space"),
+    );
+}
+
+#[test]
+#[serial]
+fn removal_retries() {
+    let space = Playspace::builder()
+        .removal_retries(3, std::time::Duration::from_millis(10))
+        .build()
+        .expect("Failed to create space");
+    let directory = space.directory().to_owned();
+    space.write_file("file.txt", "contents").unwrap();
+    space.exit().expect("Failed to exit");
+    assert!(!directory.exists());
+
+    // Nothing to wait for if the directory has already gone
+    let space = Playspace::builder()
+        .removal_retries(5, std::time::Duration::from_secs(10))
+        .build()
+        .expect("Failed to create space");
+    std::fs::remove_dir_all(space.directory()).unwrap();
+    let start = std::time::Instant::now();
+    #[allow(clippy::match_wild_err_arm)]
+    match space.exit() {
+        Err(ExitError::TempDirRemoveFailed { .. }) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[cfg(unix)]
+#[test]
+#[serial]
+fn blocking_files() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let space = Playspace::new().expect("Failed to create space");
+    space.create_dir_all("stuck/empty").unwrap();
+    space.write_file("stuck/held.txt", "contents").unwrap();
+    space.write_file("stuck/also.txt", "contents").unwrap();
+    space.write_file("free.txt", "contents").unwrap();
+    let stuck = space.directory().join("stuck");
+    std::fs::set_permissions(&stuck, std::fs::Permissions::from_mode(0o500)).unwrap();
+    if std::fs::write(stuck.join("probe"), "").is_ok() {
+        // Privileged users can remove anything anyway
+        std::fs::set_permissions(&stuck, std::fs::Permissions::from_mode(0o700)).unwrap();
+        return;
+    }
+
+    let result = space.exit();
+    std::fs::set_permissions(&stuck, std::fs::Permissions::from_mode(0o700)).unwrap();
+    #[allow(clippy::match_wild_err_arm)]
+    match result {
+        Err(ExitError::TempDirRemoveFailed { blocking, .. }) => {
+            assert_eq!(
+                blocking,
+                ["also.txt", "empty", "held.txt"].map(|name| stuck.join(name))
+            );
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    std::fs::remove_dir_all(stuck.parent().unwrap()).unwrap();
+}
+
+#[test]
+#[serial]
+fn into_path() {
+    let original = std::env::current_dir().unwrap();
+    let space = Playspace::new().expect("Failed to create space");
+    space
+        .write_files([("output/result.txt", "results")])
+        .unwrap();
+
+    let path = space.into_path().expect("Failed to exit");
+    assert!(!Playspace::is_active());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert_eq!(
+        std::fs::read_to_string(path.join("output/result.txt")).unwrap(),
+        "results"
+    );
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+#[serial]
+fn persist_to() {
+    let artifacts = tempfile::tempdir().expect("Failed to create artifacts");
+    let space = Playspace::new().expect("Failed to create space");
+    space.persist_to(artifacts.path(), ["out/*.xml", "logs/**", "missing"]);
+    space
+        .write_files([
+            ("out/report.xml", "<testsuites/>"),
+            ("out/other.txt", ""),
+            ("logs/a/b.log", "b"),
+            ("logs/c.log", "c"),
+            ("root.xml", ""),
+        ])
+        .unwrap();
+    space.exit().expect("Failed to exit");
+
+    let mut copied: Vec<_> = walk(artifacts.path());
+    copied.sort();
+    assert_eq!(
+        copied,
+        [
+            "logs",
+            "logs/a",
+            "logs/a/b.log",
+            "logs/c.log",
+            "out",
+            "out/report.xml"
+        ]
+        .map(std::path::PathBuf::from)
+    );
+
+    // Also when panicking
+    let artifacts = tempfile::tempdir().expect("Failed to create artifacts");
+    let dest = artifacts.path().to_owned();
+    let panicked = std::panic::catch_unwind(|| {
+        Playspace::scoped(|space| {
+            space.persist_to(&dest, ["**/*.log"]);
+            space.write_files([("deep/down/fail.log", "")]).unwrap();
+            panic!("test failed");
+        })
+    });
+    assert!(panicked.is_err());
+    assert!(artifacts.path().join("deep/down/fail.log").exists());
+}
+
+fn walk(root: &std::path::Path) -> Vec<std::path::PathBuf> {
+    let mut out = Vec::new();
+    let mut pending = vec![root.to_owned()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                pending.push(path.clone());
+            }
+            out.push(path.strip_prefix(root).unwrap().to_owned());
+        }
+    }
+    out
+}
+
+#[test]
+#[serial]
+fn no_chdir() {
+    let original = std::env::current_dir().unwrap();
+    let first = Playspace::builder().chdir(false).build().unwrap();
+    let second = Playspace::builder().chdir(false).try_build().unwrap();
+    assert_ne!(first.directory(), second.directory());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert!(!Playspace::is_active());
+
+    // Helpers are relative to each root
+    first.write_files([("nested/file.txt", "first")]).unwrap();
+    second.write_files([("nested/file.txt", "second")]).unwrap();
+    first.assert_file_contents("nested/file.txt", "first");
+    second.assert_file_contents("nested/file.txt", "second");
+    assert!(!original.join("nested/file.txt").exists());
+
+    // A normal Playspace can be entered alongside them
+    Playspace::try_scoped(|space| {
+        assert_ne!(space.directory(), first.directory());
+        assert!(!std::path::Path::new("nested").exists());
+    })
+    .expect("Failed to use playspace");
+
+    // The environment is left alone on exit
+    std::env::set_var("__PLAYSPACE_NO_CHDIR", "set");
+    let first_directory = first.directory().to_owned();
+    first.exit().expect("Failed to exit playspace");
+    second.exit().expect("Failed to exit playspace");
+    assert!(!first_directory.exists());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert_eq!(std::env::var("__PLAYSPACE_NO_CHDIR").unwrap(), "set");
+    std::env::remove_var("__PLAYSPACE_NO_CHDIR");
+
+    match Playspace::builder()
+        .chdir(false)
+        .preset(EnvPreset::new().set("KEY", "value"))
+        .build()
+    {
+        Err(SpaceError::StdIo(error)) => assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput),
+        Err(other) => panic!("Wrong error: {other}"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+#[serial]
+fn child_spaces() {
+    Playspace::scoped(|space| {
+        space.write_file("shared.txt", "shared").unwrap();
+        for case in ["case-1", "case-2"] {
+            let child = space.child(case).unwrap().chdir().unwrap();
+            assert_eq!(std::env::current_dir().unwrap(), child.directory());
+            assert_eq!(std::fs::read_dir(".").unwrap().count(), 0);
+            child
+                .write_files([("nested/input.txt", case)])
+                .expect("Failed to write files");
+            child.write_file("output.txt", case).unwrap();
+            assert!(child.write_file("../escape.txt", "").is_err());
+            assert!(space.exists(format!("{case}/nested/input.txt")));
+        }
+        // Only the child subtrees are cleaned
+        assert_eq!(std::env::current_dir().unwrap(), space.directory());
+        assert!(!space.exists("case-1"));
+        assert!(!space.exists("case-2"));
+        assert!(!space.exists("escape.txt"));
+        space.assert_file_contents("shared.txt", "shared");
+
+        // Existing directories start empty, and exit reports errors
+        space.write_files([("stale/old.txt", "")]).unwrap();
+        let child = space.child("stale").unwrap();
+        assert!(!child.directory().join("old.txt").exists());
+        child.exit().expect("Failed to exit child");
+        assert!(!space.exists("stale"));
+
+        assert!(space.child("").is_err());
+        assert!(space.child("../outside").is_err());
+    })
+    .expect("Failed to use playspace");
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "dotenv")]
+
+use playspace::{DotenvError, Playspace};
+
+#[test]
+fn set_envs_from_file() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space
+        .write_file(
+            ".env",
+            "# comment\n\
+             __PLAYSPACE_DOTENV_PLAIN=plain\n\
+             __PLAYSPACE_DOTENV_QUOTED=\"two words\"\n\
+             __PLAYSPACE_DOTENV_SUBST=${__PLAYSPACE_DOTENV_PLAIN}-suffix\n",
+        )
+        .unwrap();
+    space
+        .set_envs_from_file(".env")
+        .expect("Failed to set envs from file");
+    assert_eq!(std::env::var("__PLAYSPACE_DOTENV_PLAIN").unwrap(), "plain");
+    assert_eq!(
+        std::env::var("__PLAYSPACE_DOTENV_QUOTED").unwrap(),
+        "two words"
+    );
+    assert_eq!(
+        std::env::var("__PLAYSPACE_DOTENV_SUBST").unwrap(),
+        "plain-suffix"
+    );
+
+    // Nothing is set from a file that doesn't parse
+    space
+        .write_file(".env.bad", "__PLAYSPACE_DOTENV_BAD=ok\nnot valid\n")
+        .unwrap();
+    assert!(matches!(
+        space.set_envs_from_file(".env.bad"),
+        Err(DotenvError::Parse { .. })
+    ));
+    assert!(std::env::var("__PLAYSPACE_DOTENV_BAD").is_err());
+
+    assert!(matches!(
+        space.set_envs_from_file("missing.env"),
+        Err(DotenvError::File(_))
+    ));
+
+    space.exit().expect("Failed to exit playspace");
+    assert!(std::env::var("__PLAYSPACE_DOTENV_PLAIN").is_err());
+}
+
+#[test]
+fn export_env_round_trip() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    let awkward = "quote\" backslash\\ dollar$ newline\nend";
+    space.set_envs([("__PLAYSPACE_DOTENV_AWKWARD", Some(awkward))]);
+    space.export_env("env.dump").expect("Failed to export env");
+    space.set_envs([("__PLAYSPACE_DOTENV_AWKWARD", None::<&str>)]);
+
+    space
+        .set_envs_from_file("env.dump")
+        .expect("Failed to read back exported env");
+    assert_eq!(
+        std::env::var("__PLAYSPACE_DOTENV_AWKWARD").unwrap(),
+        awkward
+    );
+
+    space.exit().expect("Failed to exit playspace");
+}
@@ -1,6 +1,6 @@
 use serial_test::serial;
 
-use playspace::Playspace;
+use playspace::{EnvPreset, EnvSpec, Playspace};
 
 const ABSENT: &str = "SOME_ABSENT_ENVVAR";
 const PRESENT: &str = "SOME_PRESENT_ENVVAR";
@@ -94,3 +94,512 @@ fn with_envs() {
 
     assert_envs_outside();
 }
+
+#[test]
+#[serial]
+fn snapshot_round_trip() {
+    set_vars_before();
+    let snapshot = tempfile::NamedTempFile::new().expect("Failed to create snapshot file");
+
+    Playspace::scoped(|space| {
+        space.set_envs([
+            (ABSENT, Some("absent_value")),
+            (PRESENT, Some("multi\nline \"quoted\" $value\\")),
+            (TRANSIENT, None),
+        ]);
+        space
+            .export_env_snapshot(snapshot.path())
+            .expect("Failed to export snapshot");
+    })
+    .unwrap();
+
+    assert_envs_outside();
+
+    Playspace::scoped(|space| {
+        space.set_envs([(TRANSIENT, Some("something else"))]);
+        space
+            .apply_env_snapshot(snapshot.path())
+            .expect("Failed to apply snapshot");
+
+        assert_eq!(std::env::var(ABSENT), Ok("absent_value".to_owned()));
+        assert_eq!(
+            std::env::var(PRESENT),
+            Ok("multi\nline \"quoted\" $value\\".to_owned())
+        );
+        assert_eq!(
+            std::env::var(TRANSIENT),
+            Err(std::env::VarError::NotPresent)
+        );
+    })
+    .unwrap();
+
+    assert_envs_outside();
+}
+
+#[test]
+#[serial]
+fn snapshot_invalid() {
+    set_vars_before();
+
+    Playspace::scoped(|space| {
+        space.write_file("bad.env", "NO_EQUALS_SIGN\n").unwrap();
+        let error = space
+            .apply_env_snapshot("bad.env")
+            .expect_err("Should not have parsed");
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+        // Left unchanged
+        assert_eq!(
+            std::env::var(TRANSIENT),
+            Ok("transient_value_before".to_owned())
+        );
+    })
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn env_diff() {
+    set_vars_before();
+
+    let space = Playspace::with_envs([
+        (ABSENT, Some("absent_value")),
+        (PRESENT, Some("present_value_during")),
+        (TRANSIENT, None),
+    ])
+    .expect("Failed to create space");
+
+    let diff = space.env_diff();
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff.added.into_iter().collect::<Vec<_>>(),
+        [(ABSENT.into(), "absent_value".into())]
+    );
+    assert_eq!(
+        diff.changed.into_iter().collect::<Vec<_>>(),
+        [(
+            PRESENT.into(),
+            ("present_value_before".into(), "present_value_during".into())
+        )]
+    );
+    assert_eq!(
+        diff.removed.into_iter().collect::<Vec<_>>(),
+        [(TRANSIENT.into(), "transient_value_before".into())]
+    );
+
+    space.set_envs([
+        (ABSENT, None),
+        (PRESENT, Some("present_value_before")),
+        (TRANSIENT, Some("transient_value_before")),
+    ]);
+    assert!(space.env_diff().is_empty());
+}
+
+#[test]
+#[serial]
+fn standalone_snapshot() {
+    set_vars_before();
+
+    let snapshot = playspace::EnvSnapshot::capture();
+    assert_eq!(
+        snapshot.get(PRESENT),
+        Some(std::ffi::OsStr::new("present_value_before"))
+    );
+    assert_eq!(snapshot.get(ABSENT), None);
+
+    std::env::set_var(ABSENT, "absent_value");
+    std::env::set_var(PRESENT, "present_value_during");
+    std::env::remove_var(TRANSIENT);
+    let diff = snapshot.diff();
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.removed.len(), 1);
+
+    assert!(snapshot.restore().is_empty());
+    assert_envs_outside();
+
+    // Can be restored more than once
+    std::env::set_var(ABSENT, "absent_value");
+    snapshot.restore();
+    assert_envs_outside();
+}
+
+#[test]
+#[serial]
+fn nested_env_scopes() {
+    set_vars_before();
+    let space = Playspace::new().expect("Failed to create space");
+
+    {
+        let _outer = space.env_scope([
+            (ABSENT, Some("absent_value")),
+            (PRESENT, Some("present_value_during")),
+            (TRANSIENT, None),
+        ]);
+        assert_envs_inside();
+
+        {
+            let _inner = space.env_scope([(PRESENT, Some("inner")), (PRESENT, Some("twice"))]);
+            assert_eq!(std::env::var(PRESENT), Ok("twice".to_owned()));
+        }
+        assert_envs_inside();
+    }
+    assert_envs_outside();
+}
+
+#[test]
+#[serial]
+fn isolated_runtime_dir() {
+    let original_tmp = std::env::temp_dir();
+    {
+        let space = Playspace::new().expect("Failed to create space");
+        let runtime = space
+            .isolate_runtime_dir()
+            .expect("Failed to isolate runtime dir");
+        assert_eq!(runtime, space.directory().join(playspace::RUNTIME_DIR));
+        assert!(runtime.is_dir());
+        assert_eq!(
+            std::env::var_os("XDG_RUNTIME_DIR"),
+            Some(runtime.clone().into())
+        );
+        assert_eq!(std::env::temp_dir(), runtime);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&runtime).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+    assert_eq!(std::env::temp_dir(), original_tmp);
+}
+
+#[test]
+#[serial]
+fn isolated_tool_configs() {
+    use playspace::Tool;
+
+    let space = Playspace::new().expect("Failed to create space");
+    space
+        .isolate_tool_configs(&[Tool::Git, Tool::Npm, Tool::Pip, Tool::Cargo, Tool::Maven])
+        .expect("Failed to isolate tool configs");
+
+    for file in [
+        "GIT_CONFIG_GLOBAL",
+        "NPM_CONFIG_USERCONFIG",
+        "PIP_CONFIG_FILE",
+    ] {
+        let path = std::env::var_os(file).unwrap();
+        assert!(space.contains(&path));
+        assert_eq!(std::fs::read(path).unwrap(), b"");
+    }
+    for dir in ["NPM_CONFIG_CACHE", "PIP_CACHE_DIR", "CARGO_HOME"] {
+        let path = std::env::var_os(dir).unwrap();
+        assert!(space.contains(&path));
+        assert!(std::path::Path::new(&path).is_dir());
+    }
+    assert_eq!(std::env::var("GIT_CONFIG_NOSYSTEM").unwrap(), "1");
+    assert!(std::env::var("MAVEN_OPTS")
+        .unwrap()
+        .starts_with("-Dmaven.repo.local="));
+}
+
+#[test]
+#[serial]
+fn env_spec() {
+    let space = Playspace::with_envs([
+        ("SPEC_MODE", Some("production")),
+        ("SPEC_URL", Some("https://api.example.com/v1")),
+        ("SPEC_DEBUG", None),
+        ("SPEC_TOKEN", Some("secret")),
+    ])
+    .expect("Failed to create playspace");
+
+    let spec = EnvSpec::new()
+        .require("SPEC_MODE", "production")
+        .require_matching("SPEC_URL", "https://*.example.com/*")
+        .require_set("SPEC_TOKEN")
+        .forbid("SPEC_DEBUG");
+    assert_eq!(spec.check(), Ok(()));
+    space.assert_env_matches(&spec);
+
+    space.set_envs([("SPEC_MODE", Some("debug")), ("SPEC_DEBUG", Some("1"))]);
+    let mismatches = spec
+        .clone()
+        .require_matching("SPEC_TOKEN", "sec*x")
+        .require_set("SPEC_MISSING")
+        .check()
+        .unwrap_err();
+    assert_eq!(
+        mismatches.lines().collect::<Vec<_>>(),
+        [
+            r#"SPEC_MODE should be "production", but is "debug""#,
+            r#"SPEC_DEBUG should not be set, but is "1""#,
+            r#"SPEC_TOKEN should match "sec*x", but is "secret""#,
+            "SPEC_MISSING should be set, but is not",
+        ]
+    );
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        space.assert_env_matches(&spec);
+    }))
+    .is_err());
+}
+
+#[test]
+#[serial]
+fn env_presets() {
+    std::env::set_var("GITHUB_ACTIONS", "true");
+    std::env::set_var("https_proxy", "http://proxy.invalid");
+    std::env::set_var("LANGUAGE", "fr");
+
+    Playspace::scoped(|space| {
+        space.apply(&EnvPreset::clean_ci().and(EnvPreset::no_proxy()));
+        assert!(std::env::var_os("GITHUB_ACTIONS").is_none());
+        assert!(std::env::var_os("https_proxy").is_none());
+        assert_eq!(std::env::var("LANGUAGE").unwrap(), "fr");
+    })
+    .unwrap();
+
+    let space = Playspace::builder()
+        .preset(EnvPreset::posix_locale())
+        .preset(
+            EnvPreset::new()
+                .set("LANG", "C.UTF-8")
+                .unset("GITHUB_ACTIONS"),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(std::env::var("LC_ALL").unwrap(), "C");
+    assert_eq!(std::env::var("LANG").unwrap(), "C.UTF-8");
+    assert!(std::env::var_os("LANGUAGE").is_none());
+    assert!(std::env::var_os("GITHUB_ACTIONS").is_none());
+    space.exit().unwrap();
+
+    assert_eq!(std::env::var("GITHUB_ACTIONS").unwrap(), "true");
+    std::env::remove_var("GITHUB_ACTIONS");
+    std::env::remove_var("https_proxy");
+    std::env::remove_var("LANGUAGE");
+}
+
+#[test]
+#[serial]
+fn isolated_home() {
+    let original_home = std::env::var_os("HOME");
+    {
+        let space = Playspace::new().expect("Failed to create space");
+        let home = space.isolate_home().expect("Failed to isolate home");
+        assert_eq!(home, space.directory().join(playspace::HOME_DIR));
+        assert_eq!(std::env::var_os("HOME"), Some(home.clone().into()));
+        for (variable, relative) in [
+            ("XDG_CONFIG_HOME", ".config"),
+            ("XDG_CACHE_HOME", ".cache"),
+            ("XDG_DATA_HOME", ".local/share"),
+            ("XDG_STATE_HOME", ".local/state"),
+        ] {
+            let path = home.join(relative);
+            assert!(path.is_dir());
+            assert_eq!(std::env::var_os(variable), Some(path.into()));
+        }
+        #[cfg(windows)]
+        assert_eq!(std::env::var_os("USERPROFILE"), Some(home.into()));
+    }
+    assert_eq!(std::env::var_os("HOME"), original_home);
+}
+
+#[test]
+#[serial]
+fn isolated_tempdir() {
+    let original_tmp = std::env::temp_dir();
+    let leaked;
+    {
+        let space = Playspace::new().expect("Failed to create space");
+        let tmp = space.isolate_tempdir().expect("Failed to isolate tempdir");
+        assert_eq!(tmp, space.directory().join(playspace::TEMP_DIR));
+        for variable in ["TMPDIR", "TEMP", "TMP"] {
+            assert_eq!(std::env::var_os(variable), Some(tmp.clone().into()));
+        }
+        assert_eq!(std::env::temp_dir(), tmp);
+
+        leaked = tempfile::NamedTempFile::new()
+            .unwrap()
+            .into_temp_path()
+            .keep()
+            .unwrap();
+        assert!(leaked.starts_with(&tmp));
+    }
+    assert_eq!(std::env::temp_dir(), original_tmp);
+    assert!(!leaked.exists());
+}
+
+#[test]
+#[serial]
+fn isolated_cargo() {
+    let original_home = std::env::var_os("CARGO_HOME");
+    {
+        let space = Playspace::new().expect("Failed to create space");
+        let cargo = space.isolate_cargo().expect("Failed to isolate cargo");
+        for (variable, path) in [
+            ("CARGO_HOME", cargo.home()),
+            ("CARGO_TARGET_DIR", cargo.target()),
+            ("RUSTUP_HOME", cargo.rustup()),
+        ] {
+            assert!(path.is_dir());
+            assert!(path.starts_with(space.directory()));
+            assert_eq!(std::env::var_os(variable), Some(path.into()));
+        }
+
+        assert!(!cargo.home().join("registry").exists());
+        cargo.seed_registry().unwrap();
+        assert!(cargo.home().join("registry/cache").is_dir());
+        assert!(cargo.home().join("git/checkouts").is_dir());
+    }
+    assert_eq!(std::env::var_os("CARGO_HOME"), original_home);
+}
+
+#[test]
+#[serial]
+fn env_lock() {
+    let space = Playspace::new().expect("Failed to create space");
+
+    let guard = playspace::env_lock();
+    // Re-entrant, so the crate's own functions still work while it's held
+    space.set_envs([(PRESENT, Some("present_value_during"))]);
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        let _env = playspace::env_lock();
+        sender.send(std::env::var(PRESENT).unwrap()).unwrap();
+    });
+    assert!(receiver
+        .recv_timeout(std::time::Duration::from_millis(50))
+        .is_err());
+
+    std::env::set_var(PRESENT, "present_value_locked");
+    drop(guard);
+    assert_eq!(receiver.recv().unwrap(), "present_value_locked");
+    handle.join().unwrap();
+
+    space.exit().expect("Failed to exit");
+}
+
+#[test]
+#[serial]
+fn selective_snapshot() {
+    set_vars_before();
+
+    let space = Playspace::builder()
+        .snapshot_only(["SOME_"])
+        .protect([TRANSIENT])
+        .build()
+        .expect("Failed to create space");
+    space.set_envs([
+        (ABSENT, Some("absent_value")),
+        (TRANSIENT, Some("transient_value_during")),
+        ("__PLAYSPACE_UNTRACKED", Some("untracked_value")),
+    ]);
+    let diff = space.env_diff();
+    assert_eq!(diff.added.len(), 1);
+    assert!(diff.added.contains_key(std::ffi::OsStr::new(ABSENT)));
+    assert!(diff.changed.is_empty());
+    space.exit().expect("Failed to exit");
+
+    assert!(std::env::var_os(ABSENT).is_none());
+    assert_eq!(
+        std::env::var(TRANSIENT),
+        Ok("transient_value_during".to_owned())
+    );
+    assert_eq!(
+        std::env::var("__PLAYSPACE_UNTRACKED"),
+        Ok("untracked_value".to_owned())
+    );
+    std::env::remove_var("__PLAYSPACE_UNTRACKED");
+}
+
+#[test]
+#[serial]
+fn clear_env() {
+    set_vars_before();
+    let path = std::env::var_os("PATH");
+
+    Playspace::scoped(|space| {
+        space.clear_env();
+        assert_eq!(std::env::vars_os().count(), 0);
+    })
+    .expect("Failed to use space");
+    assert_envs_outside();
+
+    Playspace::scoped(|space| {
+        space.clear_env_except(["PATH", PRESENT]);
+        assert_eq!(std::env::var_os("PATH"), path);
+        assert_eq!(
+            std::env::var(PRESENT),
+            Ok("present_value_before".to_owned())
+        );
+        assert!(std::env::var_os(TRANSIENT).is_none());
+    })
+    .expect("Failed to use space");
+    assert_envs_outside();
+    assert_eq!(std::env::var_os("PATH"), path);
+}
+
+#[test]
+#[serial]
+fn saved_vars() {
+    set_vars_before();
+
+    let space = Playspace::builder()
+        .protect([TRANSIENT])
+        .build()
+        .expect("Failed to create space");
+    space.set_envs([
+        (ABSENT, Some("absent_value")),
+        (PRESENT, Some("present_value_during")),
+        (TRANSIENT, Some("transient_value_during")),
+    ]);
+    assert_eq!(space.var(PRESENT).unwrap(), "present_value_during");
+    assert_eq!(space.saved_var(PRESENT).unwrap(), "present_value_before");
+    assert!(space.saved_var(ABSENT).is_none());
+    // Not saved at all
+    assert!(space.saved_var(TRANSIENT).is_none());
+
+    space.restore_var(PRESENT);
+    space.restore_var(ABSENT);
+    space.restore_var(TRANSIENT);
+    assert_eq!(space.var(PRESENT).unwrap(), "present_value_before");
+    assert!(space.var(ABSENT).is_none());
+    assert_eq!(space.var(TRANSIENT).unwrap(), "transient_value_during");
+    space.exit().expect("Failed to exit");
+
+    std::env::set_var(TRANSIENT, "transient_value_before");
+    assert_envs_outside();
+}
+
+#[cfg(windows)]
+#[test]
+#[serial]
+fn case_insensitive_restore() {
+    set_vars_before();
+    let lower = PRESENT.to_ascii_lowercase();
+
+    {
+        let space = Playspace::new().expect("Failed to create space");
+        // The same variable, under a different case
+        space.set_envs([
+            (PRESENT, None),
+            (lower.as_str(), Some("present_value_during")),
+        ]);
+        assert_eq!(
+            std::env::var(PRESENT),
+            Ok("present_value_during".to_owned())
+        );
+        assert!(space.env_diff().added.is_empty());
+        assert_eq!(space.env_diff().changed.len(), 1);
+    }
+
+    assert_envs_outside();
+    let names: Vec<_> = std::env::vars_os()
+        .map(|(key, _value)| key)
+        .filter(|key| key.eq_ignore_ascii_case(PRESENT))
+        .collect();
+    assert_eq!(names, [PRESENT]);
+}
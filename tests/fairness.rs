@@ -0,0 +1,37 @@
+//! Fair queuing is configured process-wide, so is tested alone in this binary
+
+use std::{sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use playspace::{
+    config::{self, Config},
+    Playspace,
+};
+
+#[test]
+fn fair_queuing() {
+    config::configure(Config::new().fair_queuing()).expect("Failed to configure");
+
+    for _ in 0..3 {
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let space = Playspace::new().expect("Failed to create space");
+
+        let waiters: Vec<_> = (0..8)
+            .map(|i| {
+                let order = order.clone();
+                let waiter = std::thread::spawn(move || {
+                    Playspace::scoped(|_space| order.lock().push(i)).expect("Failed to use space");
+                });
+                // Long enough to have started waiting before the next
+                std::thread::sleep(Duration::from_millis(20));
+                waiter
+            })
+            .collect();
+
+        space.exit().expect("Failed to exit space");
+        for waiter in waiters {
+            waiter.join().unwrap();
+        }
+        assert_eq!(*order.lock(), (0..8).collect::<Vec<_>>());
+    }
+}
@@ -1,6 +1,6 @@
 use std::{io::Write, path::Path};
 
-use playspace::{Playspace, WriteError};
+use playspace::{fixtures, Playspace, WriteError};
 
 #[test]
 fn write_files() {
@@ -85,15 +85,78 @@ fn good_absolute_dir() {
     space
         .write_file(path.join("a_file.txt"), "some file contents")
         .unwrap();
-    std::env::set_current_dir("some/new").unwrap();
-    let file_contents = std::fs::read_to_string("dirs/a_file.txt").unwrap();
-    assert_eq!(file_contents, "some file contents");
+    {
+        let _subdir = space.dir_scope("some/new").unwrap();
+        let file_contents = std::fs::read_to_string("dirs/a_file.txt").unwrap();
+        assert_eq!(file_contents, "some file contents");
+    }
+    assert_eq!(
+        std::env::current_dir().unwrap().canonicalize().unwrap(),
+        space.directory().canonicalize().unwrap()
+    );
 
     drop(space);
 
     assert!(!path.exists());
 }
 
+#[test]
+fn traversal() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    let outside = space.directory().parent().unwrap().join("outside.txt");
+    assert!(!outside.exists());
+
+    for path in [
+        "../outside.txt",
+        "new/../../outside.txt",
+        "./a/./../../outside.txt",
+    ] {
+        #[allow(clippy::match_wild_err_arm)]
+        match space.write_files([(path, "")]) {
+            Err(WriteError::OutsidePlayspace(_)) => (),
+            Err(_) => panic!("Wrong error"),
+            Ok(()) => panic!("Should not have worked"),
+        }
+    }
+    assert!(!outside.exists());
+    assert!(!Path::new("new").exists());
+
+    // Staying inside is fine
+    space.write_files([("new/../inside.txt", "")]).unwrap();
+    assert!(Path::new("inside.txt").exists());
+    assert!(space.contains("a/b/../../c"));
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_escapes() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    let outside = tempfile::tempdir().unwrap();
+
+    // Through an existing directory
+    std::os::unix::fs::symlink(outside.path(), "to_outside").unwrap();
+    // Through a broken link, which would create its target
+    std::os::unix::fs::symlink(outside.path().join("created.txt"), "dangling").unwrap();
+    // Through a chain of links
+    std::os::unix::fs::symlink("dangling", "chained").unwrap();
+
+    for path in ["to_outside/file.txt", "dangling", "chained"] {
+        #[allow(clippy::match_wild_err_arm)]
+        match space.write_file(path, "") {
+            Err(WriteError::OutsidePlayspace(_)) => (),
+            Err(_) => panic!("Wrong error"),
+            Ok(()) => panic!("Should not have worked"),
+        }
+    }
+    assert!(!outside.path().join("file.txt").exists());
+    assert!(!outside.path().join("created.txt").exists());
+
+    // Broken links within the Playspace are fine
+    std::os::unix::fs::symlink("target.txt", "inside").unwrap();
+    space.write_file("inside", "contents").unwrap();
+    assert_eq!(std::fs::read_to_string("target.txt").unwrap(), "contents");
+}
+
 #[test]
 fn bad_absolute_dir() {
     let space = Playspace::new().expect("Failed to create playspace");
@@ -112,6 +175,555 @@ fn bad_absolute_dir() {
     match space.create_dir_all(path) {
         Err(WriteError::OutsidePlayspace(_)) => (),
         Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn contains_paths() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    assert!(space.contains("relative/file.txt"));
+    assert!(space.contains(space.directory().join("absolute.txt")));
+    assert!(!space.contains(std::env::temp_dir()));
+
+    let normalized = space
+        .normalize("relative/file.txt")
+        .expect("Failed to normalize");
+    assert_eq!(normalized, space.directory().join("relative/file.txt"));
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.normalize(std::env::temp_dir().join("elsewhere.txt")) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn with_files() {
+    let space = Playspace::with_files([
+        ("top.txt", "top contents"),
+        ("some/nested/file.txt", "nested contents"),
+    ])
+    .expect("Failed to create playspace");
+
+    assert_eq!(std::fs::read_to_string("top.txt").unwrap(), "top contents");
+    assert_eq!(
+        std::fs::read_to_string("some/nested/file.txt").unwrap(),
+        "nested contents"
+    );
+
+    let nested = space.directory().join("some/nested/file.txt");
+    drop(space);
+    assert!(!nested.exists());
+}
+
+#[test]
+fn scoped_with_files() {
+    let output = Playspace::scoped_with_files([("a/b.txt", b"bytes".as_slice())], |_space| {
+        std::fs::read("a/b.txt").unwrap()
+    })
+    .expect("Failed to use playspace");
+    assert_eq!(output, b"bytes");
+
+    #[allow(clippy::match_wild_err_arm)]
+    match Playspace::with_files([(std::env::temp_dir().join("outside.txt"), "")]) {
+        Err(playspace::SpaceError::WriteError(WriteError::OutsidePlayspace(_))) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[cfg(all(windows, feature = "windows-acl"))]
+#[test]
+fn deny_write() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.write_file("locked.txt", "before").unwrap();
+    space.create_dir_all("locked_dir").unwrap();
+
+    space
+        .deny_write("locked.txt")
+        .expect("Failed to deny write");
+    space
+        .deny_write("locked_dir")
+        .expect("Failed to deny write");
+    assert!(std::fs::write("locked.txt", "after").is_err());
+    assert!(std::fs::write("locked_dir/new.txt", "").is_err());
+
+    space
+        .allow_write("locked.txt")
+        .expect("Failed to allow write");
+    std::fs::write("locked.txt", "after").expect("Should be writable again");
+
+    // The remaining deny entry must not stop the directory being removed
+    space.exit().expect("Failed to exit");
+}
+
+#[test]
+fn dir_scope() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    let root = std::env::current_dir().unwrap();
+
+    {
+        let _outer = space.dir_scope("outer").expect("Failed to enter directory");
+        assert_eq!(std::env::current_dir().unwrap(), root.join("outer"));
+        {
+            let _inner = space
+                .dir_scope("outer/inner")
+                .expect("Failed to enter directory");
+            assert_eq!(std::env::current_dir().unwrap(), root.join("outer/inner"));
+        }
+        assert_eq!(std::env::current_dir().unwrap(), root.join("outer"));
+    }
+    assert_eq!(std::env::current_dir().unwrap(), root);
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.dir_scope(std::env::temp_dir()) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    };
+}
+
+#[test]
+fn append_file() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space.append_file("log.txt", "first\n").unwrap();
+    space.append_file("log.txt", b"second\n").unwrap();
+    assert_eq!(
+        std::fs::read_to_string("log.txt").unwrap(),
+        "first\nsecond\n"
+    );
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.append_file(std::env::temp_dir().join("outside.txt"), "") {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn touch() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space.touch("empty.txt").unwrap();
+    assert_eq!(std::fs::read("empty.txt").unwrap(), b"");
+    assert!(space.touch("missing/parent.txt").is_err());
+    space.touch_all("missing/parent.txt").unwrap();
+    assert!(Path::new("missing/parent.txt").is_file());
+
+    space.write_file("old.txt", "contents").unwrap();
+    let old = std::time::SystemTime::now() - std::time::Duration::from_secs(100);
+    std::fs::File::options()
+        .write(true)
+        .open("old.txt")
+        .unwrap()
+        .set_modified(old)
+        .unwrap();
+
+    space.touch("old.txt").unwrap();
+    let modified = std::fs::metadata("old.txt").unwrap().modified().unwrap();
+    assert!(modified > old);
+    assert_eq!(std::fs::read_to_string("old.txt").unwrap(), "contents");
+}
+
+#[test]
+fn remove() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("file.txt", ""), ("dir/nested/file.txt", "")])
+        .unwrap();
+
+    space.remove_file("file.txt").unwrap();
+    assert!(!Path::new("file.txt").exists());
+    assert!(space.remove_file("file.txt").is_err());
+
+    space.remove_dir_all("dir").unwrap();
+    assert!(!Path::new("dir").exists());
+
+    let outside = tempfile::NamedTempFile::new().unwrap();
+    #[allow(clippy::match_wild_err_arm)]
+    match space.remove_file(outside.path()) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    #[allow(clippy::match_wild_err_arm)]
+    match space.remove_dir_all(outside.path().parent().unwrap()) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    assert!(outside.path().exists());
+}
+
+#[test]
+fn rename_and_copy() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("a.txt", "contents"), ("sub/.keep", "")])
+        .unwrap();
+
+    {
+        // Relative paths are always with respect to the root
+        let _subdir = space.dir_scope("sub").unwrap();
+        assert_eq!(space.copy("a.txt", "b.txt").unwrap(), 8);
+        space.rename("b.txt", "sub/c.txt").unwrap();
+    }
+    assert_eq!(std::fs::read_to_string("a.txt").unwrap(), "contents");
+    assert!(!Path::new("b.txt").exists());
+    assert_eq!(std::fs::read_to_string("sub/c.txt").unwrap(), "contents");
+
+    let outside = std::env::temp_dir().join("outside.txt");
+    #[allow(clippy::match_wild_err_arm)]
+    match space.rename("a.txt", &outside) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    #[allow(clippy::match_wild_err_arm)]
+    match space.copy(&outside, "a.txt") {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+    assert!(Path::new("a.txt").exists());
+}
+
+#[test]
+fn list() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([
+            ("b.txt", ""),
+            ("a/nested.txt", ""),
+            ("a/deeper/file.txt", ""),
+        ])
+        .unwrap();
+
+    assert_eq!(
+        space.list("").unwrap(),
+        [Path::new("a"), Path::new("b.txt")]
+    );
+    assert_eq!(
+        space.list(space.directory().join("a")).unwrap(),
+        [Path::new("a/deeper"), Path::new("a/nested.txt")]
+    );
+    assert!(space.list("missing").is_err());
+    assert!(space.list(std::env::temp_dir()).is_err());
+}
+
+#[test]
+fn link_or_copy_in() {
+    let outside = tempfile::tempdir().unwrap();
+    let fixture = outside.path().join("fixture.bin");
+    std::fs::write(&fixture, "fixture contents").unwrap();
+
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.link_or_copy_in(&fixture, "fixture.bin").unwrap();
+    assert_eq!(
+        std::fs::read_to_string("fixture.bin").unwrap(),
+        "fixture contents"
+    );
+
+    assert!(space.link_or_copy_in(&fixture, "fixture.bin").is_err());
+    #[allow(clippy::match_wild_err_arm)]
+    match space.link_or_copy_in(&fixture, outside.path().join("other.bin")) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+
+    // Removing the space leaves the original intact
+    space.exit().unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&fixture).unwrap(),
+        "fixture contents"
+    );
+}
+
+#[test]
+fn exists_and_metadata() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("sub/file.txt", "contents"), ("other.txt", "")])
+        .unwrap();
+
+    let _subdir = space.dir_scope("sub").unwrap();
+    assert!(space.exists("sub/file.txt"));
+    assert!(space.exists("sub"));
+    assert!(!space.exists("file.txt"));
+    assert!(!space.exists(std::env::temp_dir()));
+
+    assert_eq!(space.metadata("sub/file.txt").unwrap().len(), 8);
+    assert!(space.metadata("sub").unwrap().is_dir());
+    assert!(space.metadata("missing.txt").is_err());
+    #[allow(clippy::match_wild_err_arm)]
+    match space.metadata(std::env::temp_dir()) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn open() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.write_file("file.txt", "first\n").unwrap();
+
+    let _subdir = space.dir_scope("sub").unwrap();
+    space
+        .open("file.txt")
+        .append(true)
+        .open()
+        .unwrap()
+        .write_all(b"second\n")
+        .unwrap();
+    assert!(space
+        .open("file.txt")
+        .create_new(true)
+        .write(true)
+        .open()
+        .is_err());
+    assert!(space.open("missing.txt").read(true).open().is_err());
+    space
+        .open("new.txt")
+        .create(true)
+        .write(true)
+        .open()
+        .unwrap();
+    assert!(space.exists("new.txt"));
+    assert_eq!(
+        std::fs::read_to_string(space.directory().join("file.txt")).unwrap(),
+        "first\nsecond\n"
+    );
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space
+        .open(std::env::temp_dir().join("outside.txt"))
+        .create(true)
+        .write(true)
+        .open()
+    {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn symlink() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("data/v1/input.txt", "first"), ("sub/.keep", "")])
+        .unwrap();
+
+    let _subdir = space.dir_scope("sub").unwrap();
+    space.symlink("v1", "data/current").unwrap();
+    space
+        .symlink("../data/v1/input.txt", "sub/input.txt")
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(space.directory().join("data/current/input.txt")).unwrap(),
+        "first"
+    );
+    assert_eq!(std::fs::read_to_string("input.txt").unwrap(), "first");
+    #[cfg(unix)]
+    assert_eq!(
+        std::fs::read_link("input.txt").unwrap(),
+        Path::new("../data/v1/input.txt")
+    );
+
+    for target in [Path::new("../../.."), &std::env::temp_dir()] {
+        #[allow(clippy::match_wild_err_arm)]
+        match space.symlink(target, "escape") {
+            Err(WriteError::OutsidePlayspace(_)) => (),
+            Err(_) => panic!("Wrong error"),
+            Ok(()) => panic!("Should not have worked"),
+        }
+    }
+}
+
+#[test]
+fn hard_link() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("a.txt", "contents"), ("sub/.keep", "")])
+        .unwrap();
+
+    let _subdir = space.dir_scope("sub").unwrap();
+    space.hard_link("a.txt", "sub/b.txt").unwrap();
+    std::fs::write("b.txt", "changed").unwrap();
+    assert_eq!(
+        std::fs::read_to_string(space.directory().join("a.txt")).unwrap(),
+        "changed"
+    );
+    assert!(space.hard_link("a.txt", "sub/b.txt").is_err());
+
+    let outside = std::env::temp_dir().join("outside.txt");
+    #[allow(clippy::match_wild_err_arm)]
+    match space.hard_link("a.txt", &outside) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn permissions() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.write_files([("dir/file.txt", "")]).unwrap();
+
+    space.set_readonly("dir/file.txt", true).unwrap();
+    assert!(std::fs::metadata("dir/file.txt")
+        .unwrap()
+        .permissions()
+        .readonly());
+    space.set_readonly("dir/file.txt", false).unwrap();
+    assert!(!std::fs::metadata("dir/file.txt")
+        .unwrap()
+        .permissions()
+        .readonly());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        space.set_mode("dir", 0o500).unwrap();
+        assert_eq!(
+            std::fs::metadata("dir").unwrap().permissions().mode() & 0o777,
+            0o500
+        );
+        space.set_mode("dir", 0o755).unwrap();
+    }
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.set_readonly(std::env::temp_dir(), true) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    space.exit().expect("Failed to exit");
+}
+
+#[test]
+fn file_times() {
+    use std::time::{Duration, SystemTime};
+
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("old.txt", ""), ("dir/new.txt", "")])
+        .unwrap();
+
+    let accessed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    let modified = accessed + Duration::from_secs(100);
+    space.set_file_times("old.txt", accessed, modified).unwrap();
+    space.set_file_times("dir", accessed, modified).unwrap();
+
+    for path in ["old.txt", "dir"] {
+        let metadata = std::fs::metadata(path).unwrap();
+        assert_eq!(metadata.accessed().unwrap(), accessed);
+        assert_eq!(metadata.modified().unwrap(), modified);
+    }
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.set_file_times(std::env::temp_dir(), accessed, modified) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn write_error_kinds() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.write_file("exists.txt", "").unwrap();
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.write_file("missing/file.txt", "") {
+        Err(WriteError::ParentMissing { path, .. }) => {
+            assert_eq!(path, space.directory().join("missing/file.txt"));
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    #[allow(clippy::match_wild_err_arm)]
+    match space.open("exists.txt").write(true).create_new(true).open() {
+        Err(WriteError::AlreadyExists { path, .. }) => {
+            assert_eq!(path, space.directory().join("exists.txt"));
+        }
+        Err(_) => panic!("Wrong error"),
         Ok(_) => panic!("Should not have worked"),
     }
+    let fixture = fixtures::get("write_error_kinds", |_root| Ok(())).unwrap();
+    #[allow(clippy::match_wild_err_arm)]
+    match space.link_fixture(&fixture, "exists.txt") {
+        Err(WriteError::AlreadyExists { path, .. }) => {
+            assert_eq!(path, space.directory().join("exists.txt"));
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    // Missing files, rather than parents, are still IO errors, with the path
+    #[allow(clippy::match_wild_err_arm)]
+    match space.remove_file("missing.txt") {
+        Err(WriteError::Io { path, source }) => {
+            assert_eq!(path, space.directory().join("missing.txt"));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn assertions() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_files([("dir/file.txt", "one\ntwo\n"), ("binary", "\u{0}\u{1}")])
+        .unwrap();
+
+    space.assert_exists("dir");
+    space.assert_exists("dir/file.txt");
+    space.assert_not_exists("dir/missing.txt");
+    space.assert_file_contents("dir/file.txt", "one\ntwo\n");
+    space.assert_file_contents("binary", [0, 1]);
+    #[cfg(feature = "regex")]
+    space.assert_file_matches("dir/file.txt", r"^one\n\w+\n$");
+}
+
+#[test]
+#[should_panic(expected = "  one\n- two\n+ three\n  four\n+ five\n")]
+fn assert_file_contents_diff() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space
+        .write_file("file.txt", "one\nthree\nfour\nfive")
+        .unwrap();
+    space.assert_file_contents("file.txt", "one\ntwo\nfour\n");
+}
+
+#[test]
+#[should_panic(expected = "expected missing.txt to exist in the Playspace")]
+fn assert_exists_missing() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.assert_exists("missing.txt");
+}
+
+#[test]
+#[should_panic(expected = "could not check ../outside.txt")]
+fn assert_not_exists_outside() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.assert_not_exists("../outside.txt");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+#[should_panic(expected = "do not match")]
+fn assert_file_matches_mismatch() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    space.write_file("app.log", "failed").unwrap();
+    space.assert_file_matches("app.log", "^started");
 }
@@ -0,0 +1,93 @@
+use std::{
+    io,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use playspace::{fixtures, Playspace};
+
+#[test]
+fn built_once_and_shared() {
+    static BUILDS: AtomicUsize = AtomicUsize::new(0);
+    let build = |root: &Path| {
+        BUILDS.fetch_add(1, Ordering::SeqCst);
+        std::fs::create_dir(root.join("nested"))?;
+        std::fs::write(root.join("nested/data.txt"), "expensive")
+    };
+
+    for _ in 0..2 {
+        let space = Playspace::new().expect("Failed to create playspace");
+        let fixture = fixtures::get("shared", build).expect("Failed to build fixture");
+        assert_eq!(fixture.name(), "shared");
+
+        space
+            .link_fixture(&fixture, "linked/fixture")
+            .expect("Failed to link fixture");
+        assert_eq!(
+            std::fs::read_to_string("linked/fixture/nested/data.txt").unwrap(),
+            "expensive"
+        );
+        assert!(std::fs::metadata("linked/fixture/nested/data.txt")
+            .unwrap()
+            .permissions()
+            .readonly());
+        assert!(std::fs::metadata(fixture.path())
+            .unwrap()
+            .permissions()
+            .readonly());
+    }
+    assert_eq!(BUILDS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn failed_build_is_retried() {
+    let result = fixtures::get("retried", |_root| Err(io::Error::other("failed")));
+    assert!(result.is_err());
+
+    let fixture = fixtures::get("retried", |root| std::fs::write(root.join("ok"), ""))
+        .expect("Failed to build fixture");
+    assert!(fixture.path().join("ok").is_file());
+}
+
+#[test]
+fn link_outside() {
+    let space = Playspace::new().expect("Failed to create playspace");
+    let fixture = fixtures::get("outside", |_root| Ok(())).expect("Failed to build fixture");
+    assert!(space
+        .link_fixture(&fixture, std::env::temp_dir().join("outside"))
+        .is_err());
+}
+
+static SHARED_BUILDS: AtomicUsize = AtomicUsize::new(0);
+static SHARED: fixtures::SharedFixture = fixtures::SharedFixture::new("shared-static", |root| {
+    SHARED_BUILDS.fetch_add(1, Ordering::SeqCst);
+    std::fs::write(root.join("data.txt"), "shared")
+});
+
+#[test]
+fn shared_fixture() {
+    // Read concurrently, from outside of any Playspace, while one is held
+    let space = Playspace::new().expect("Failed to create playspace");
+    let paths: Vec<_> = std::thread::scope(|scope| {
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                scope.spawn(|| {
+                    let fixture = SHARED.get().expect("Failed to build fixture");
+                    assert_eq!(
+                        std::fs::read_to_string(fixture.path().join("data.txt")).unwrap(),
+                        "shared"
+                    );
+                    fixture.path().to_owned()
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    assert!(paths.windows(2).all(|pair| pair[0] == pair[1]));
+    assert_eq!(SHARED_BUILDS.load(Ordering::SeqCst), 1);
+    assert_eq!(SHARED.get().unwrap().name(), "shared-static");
+    space.exit().expect("Failed to exit playspace");
+}
@@ -0,0 +1,115 @@
+#![cfg(any(feature = "serde-json", feature = "toml", feature = "yaml"))]
+
+use std::collections::BTreeMap;
+
+use playspace::Playspace;
+
+fn config() -> BTreeMap<&'static str, BTreeMap<&'static str, i32>> {
+    BTreeMap::from([("table", BTreeMap::from([("option1", 1), ("option2", 2)]))])
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn write_json() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space
+        .write_json("config.json", &config())
+        .expect("Failed to write json");
+    let contents = std::fs::read_to_string("config.json").unwrap();
+    assert!(contents.contains(r#""option1": 1"#));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn write_toml() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space
+        .write_toml("config.toml", &config())
+        .expect("Failed to write toml");
+    let contents = std::fs::read_to_string("config.toml").unwrap();
+    assert!(contents.contains("[table]"));
+    assert!(contents.contains("option2 = 2"));
+
+    // TOML documents must be tables
+    assert!(matches!(
+        space.write_toml("bad.toml", &5),
+        Err(playspace::FormatError::TomlSerialize(_))
+    ));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn write_yaml() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space
+        .write_yaml("config.yaml", &config())
+        .expect("Failed to write yaml");
+    let contents = std::fs::read_to_string("config.yaml").unwrap();
+    assert!(contents.contains("table:"));
+    assert!(contents.contains("option1: 1"));
+
+    assert!(matches!(
+        space.write_yaml(std::env::temp_dir().join("config.yaml"), &config()),
+        Err(playspace::FormatError::File(
+            playspace::WriteError::OutsidePlayspace(_)
+        ))
+    ));
+}
+
+#[cfg(feature = "serde-json")]
+#[test]
+fn read_json() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space.write_json("config.json", &config()).unwrap();
+    let read: BTreeMap<String, BTreeMap<String, i32>> =
+        space.read_json("config.json").expect("Failed to read json");
+    assert_eq!(read["table"]["option2"], 2);
+
+    space.write_file("broken.json", "{").unwrap();
+    assert!(matches!(
+        space.read_json::<BTreeMap<String, i32>>("broken.json"),
+        Err(playspace::FormatError::Json(_))
+    ));
+    assert!(matches!(
+        space.read_json::<BTreeMap<String, i32>>("missing.json"),
+        Err(playspace::FormatError::File(_))
+    ));
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn read_toml() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space.write_toml("config.toml", &config()).unwrap();
+    let read: BTreeMap<String, BTreeMap<String, i32>> =
+        space.read_toml("config.toml").expect("Failed to read toml");
+    assert_eq!(read["table"]["option1"], 1);
+
+    space.write_file("broken.toml", "[table").unwrap();
+    assert!(matches!(
+        space.read_toml::<BTreeMap<String, i32>>("broken.toml"),
+        Err(playspace::FormatError::TomlDeserialize(_))
+    ));
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn read_yaml() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    space.write_yaml("config.yaml", &config()).unwrap();
+    let read: BTreeMap<String, BTreeMap<String, i32>> =
+        space.read_yaml("config.yaml").expect("Failed to read yaml");
+    assert_eq!(
+        read,
+        BTreeMap::from([(
+            "table".to_owned(),
+            BTreeMap::from([("option1".to_owned(), 1), ("option2".to_owned(), 2)])
+        )])
+    );
+}
@@ -0,0 +1,36 @@
+#![cfg(feature = "glob")]
+
+use std::path::Path;
+
+use playspace::{GlobError, Playspace};
+
+#[test]
+fn glob_matches() {
+    let space = Playspace::with_files([
+        ("out/a.toml", ""),
+        ("out/b.toml", ""),
+        ("out/nested/c.toml", ""),
+        ("out/d.json", ""),
+        ("other/e.toml", ""),
+    ])
+    .expect("Failed to create playspace");
+
+    assert_eq!(
+        space.glob("out/*.toml").unwrap(),
+        [Path::new("out/a.toml"), Path::new("out/b.toml")]
+    );
+    assert_eq!(space.glob("out/**/*.toml").unwrap().len(), 3);
+    assert_eq!(space.glob("**/*.toml").unwrap().len(), 4);
+    assert_eq!(
+        space.glob("*").unwrap(),
+        [Path::new("other"), Path::new("out")]
+    );
+    assert!(space.glob("*.rs").unwrap().is_empty());
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.glob("out/***") {
+        Err(GlobError::Pattern(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
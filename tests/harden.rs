@@ -0,0 +1,49 @@
+#![cfg(all(target_os = "linux", feature = "harden-linux"))]
+
+use std::io::ErrorKind;
+
+use serial_test::serial;
+
+use playspace::{Playspace, SpaceError};
+
+#[test]
+#[serial]
+fn restrict_filesystem() {
+    let outside = tempfile::tempdir().unwrap();
+    let outside_file = outside.path().join("outside.txt");
+    let directory = std::thread::spawn(move || {
+        let space = match Playspace::builder().restrict_filesystem(true).build() {
+            Err(SpaceError::StdIo(error)) if error.kind() == ErrorKind::Unsupported => {
+                eprintln!("Skipping, Landlock is not supported");
+                return None;
+            }
+            other => other.expect("Failed to create playspace"),
+        };
+        space.write_files([("inside/file.txt", "inside")]).unwrap();
+        std::fs::remove_file("inside/file.txt").unwrap();
+        assert_eq!(
+            std::fs::write(&outside_file, "outside").unwrap_err().kind(),
+            ErrorKind::PermissionDenied
+        );
+        // Still readable
+        assert!(std::fs::read_dir(std::env::temp_dir()).is_ok());
+        // Still usable for child processes
+        std::process::Command::new("true")
+            .stdout(std::process::Stdio::null())
+            .status()
+            .unwrap();
+
+        let directory = space.directory().to_owned();
+        space.exit().expect("Failed to exit playspace");
+        Some(directory)
+    })
+    .join()
+    .unwrap();
+
+    assert!(!outside.path().join("outside.txt").exists());
+    if let Some(directory) = directory {
+        assert!(!directory.exists());
+    }
+    // Other threads are unaffected
+    std::fs::write(outside.path().join("after.txt"), "after").unwrap();
+}
@@ -0,0 +1,37 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use playspace::{metrics, Playspace};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn lock_wait() {
+    let waits = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&waits);
+    metrics::on_lock_wait(move |waited| recorded.lock().unwrap().push(waited));
+
+    let space = Playspace::new().expect("Failed to create space");
+    let waiter = std::thread::spawn(|| {
+        let space = Playspace::new().expect("Failed to create space");
+        space.lock_wait()
+    });
+    std::thread::sleep(Duration::from_millis(100));
+    drop(space);
+    let waited = waiter.join().unwrap();
+    assert!(waited >= Duration::from_millis(100));
+
+    // Not waited for at all
+    let space = Playspace::try_new().expect("Failed to create space");
+    assert_eq!(space.lock_wait(), Duration::ZERO);
+    drop(space);
+
+    metrics::clear_lock_wait_hook();
+    drop(Playspace::new().expect("Failed to create space"));
+
+    let waits = waits.lock().unwrap();
+    assert_eq!(waits.len(), 2);
+    assert_eq!(waits[1], waited);
+}
@@ -0,0 +1,82 @@
+//! Nesting is configured process-wide, so is tested alone in this binary
+
+use playspace::{
+    config::{self, Config},
+    ExitError, ExitStep, Playspace, SpaceError, StepFailure,
+};
+
+const VAR: &str = "__PLAYSPACE_NESTING_VAR";
+
+/// A helper which uses a Playspace of its own
+fn helper() -> std::path::PathBuf {
+    Playspace::scoped(|space| {
+        space.set_envs([(VAR, Some("nested"))]);
+        space.write_file("helper.txt", "").unwrap();
+        assert_eq!(std::env::current_dir().unwrap(), space.directory());
+        assert_eq!(
+            Playspace::current_root().as_deref(),
+            Some(space.directory())
+        );
+        space.directory().to_owned()
+    })
+    .expect("Failed to nest playspace")
+}
+
+#[test]
+fn nesting() {
+    config::configure(Config::new().allow_nesting()).expect("Failed to configure");
+    let original = std::env::current_dir().unwrap();
+    std::env::remove_var(VAR);
+
+    Playspace::scoped(|space| {
+        space.set_envs([(VAR, Some("outer"))]);
+
+        let nested = helper();
+        assert_ne!(nested, space.directory());
+        assert!(!nested.exists());
+        // The outer Playspace is back as it was
+        assert_eq!(std::env::current_dir().unwrap(), space.directory());
+        assert_eq!(std::env::var(VAR).unwrap(), "outer");
+        assert!(!space.exists("helper.txt"));
+        assert_eq!(
+            Playspace::current_root().as_deref(),
+            Some(space.directory())
+        );
+
+        // Any depth, and without blocking
+        let inner = Playspace::try_scoped(|_inner| helper()).expect("Failed to nest");
+        assert!(!inner.exists());
+        assert_eq!(std::env::current_dir().unwrap(), space.directory());
+
+        // Other threads still can't enter
+        let other = std::thread::spawn(|| Playspace::try_new().map(drop))
+            .join()
+            .unwrap();
+        assert!(matches!(other, Err(SpaceError::AlreadyInSpace)));
+    })
+    .expect("Failed to use playspace");
+
+    assert!(!Playspace::is_active());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert!(std::env::var_os(VAR).is_none());
+
+    // A nested Playspace outliving its parent leaves the next one alone
+    let outer = Playspace::new().expect("Failed to create playspace");
+    let nested = Playspace::new().expect("Failed to nest playspace");
+    outer.exit().expect("Failed to exit outer playspace");
+    let next = Playspace::try_new().expect("Nested playspace held the lock");
+    next.set_envs([(VAR, Some("next"))]);
+    let result = nested.exit();
+    assert!(matches!(
+        result,
+        Err(ExitError::StepFailed {
+            step: ExitStep::Environment,
+            source: StepFailure::OutOfOrder,
+        })
+    ));
+    assert_eq!(std::env::var(VAR).unwrap(), "next");
+    assert_eq!(std::env::current_dir().unwrap(), next.directory());
+    assert_eq!(Playspace::current_root().as_deref(), Some(next.directory()));
+    next.exit().expect("Failed to exit playspace");
+    assert!(std::env::var_os(VAR).is_none());
+}
@@ -0,0 +1,43 @@
+use playspace::{Playspace, ProcessError};
+
+const VAR: &str = "__PLAYSPACE_PROCESS_TEST_VAR";
+
+#[test]
+fn scoped_process() {
+    let original = std::env::current_dir().unwrap();
+    Playspace::scoped_process(|space| {
+        std::env::set_var(VAR, "child");
+        std::fs::create_dir("nested").unwrap();
+        std::env::set_current_dir("nested").unwrap();
+        assert_eq!(
+            std::env::current_dir().unwrap(),
+            space.directory().join("nested")
+        );
+    })
+    .expect("Child process failed");
+
+    // Nothing happened in this process, even briefly
+    assert!(std::env::var_os(VAR).is_none());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert!(!Playspace::is_active());
+}
+
+#[test]
+fn scoped_process_panic() {
+    match Playspace::scoped_process(|_space| panic!("panic in child")) {
+        Err(ProcessError::Failed { status, stderr, .. }) => {
+            assert!(!status.success());
+            assert!(stderr.contains("panic in child"));
+        }
+        Err(other) => panic!("Wrong error: {other}"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
+
+#[test]
+fn scoped_process_not_a_test() {
+    let result = std::thread::spawn(|| Playspace::scoped_process(|_space| ()))
+        .join()
+        .unwrap();
+    assert!(matches!(result, Err(ProcessError::NotATest)));
+}
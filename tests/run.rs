@@ -0,0 +1,95 @@
+#![cfg(unix)]
+
+use playspace::Playspace;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn run() {
+    Playspace::scoped(|space| {
+        space.create_dir_all("sub").unwrap();
+        std::env::set_current_dir("sub").unwrap();
+        std::env::set_var("PLAYSPACE_RUN_VAR", "from the environment");
+
+        // Runs in the root, whatever the current directory
+        let output = space
+            .run([
+                "sh",
+                "-c",
+                "pwd; echo \"$PLAYSPACE_RUN_VAR\"; echo oops >&2; exit 4",
+            ])
+            .unwrap();
+        output
+            .assert_failure()
+            .assert_code(4)
+            .assert_stdout_contains("from the environment")
+            .assert_stderr_contains("oops");
+        let pwd = output.stdout_str().lines().next().unwrap().to_owned();
+        assert_eq!(
+            std::path::Path::new(&pwd).canonicalize().unwrap(),
+            space.directory_canonical()
+        );
+
+        space.run(["true"]).unwrap().assert_success().assert_code(0);
+
+        // Standard input is closed
+        space.run(["cat"]).unwrap().assert_success();
+
+        assert_eq!(
+            space.run(Vec::<&str>::new()).unwrap_err().kind(),
+            std::io::ErrorKind::InvalidInput
+        );
+        assert!(space.run(["playspace-no-such-command"]).is_err());
+    })
+    .unwrap();
+}
+
+#[test]
+#[serial]
+fn run_shim() {
+    Playspace::scoped(|space| {
+        space
+            .shim("fake-tool")
+            .with_stdout("faked")
+            .install()
+            .unwrap();
+        space
+            .run(["fake-tool", "--flag"])
+            .unwrap()
+            .assert_success()
+            .assert_stdout_contains("faked");
+        assert_eq!(space.shim_calls("fake-tool").unwrap()[0].args, ["--flag"]);
+    })
+    .unwrap();
+}
+
+#[test]
+#[serial]
+#[should_panic(
+    expected = "expected the command to succeed, but it exited with exit status: 1\n--- stdout\nsome output\n"
+)]
+fn assert_success_failed() {
+    Playspace::scoped(|space| {
+        space
+            .run(["sh", "-c", "echo some output; exit 1"])
+            .unwrap()
+            .assert_success();
+    })
+    .unwrap();
+}
+
+#[cfg(feature = "shell")]
+#[test]
+#[serial]
+fn sh() {
+    Playspace::scoped(|space| {
+        space
+            .sh("echo hi | tr a-z A-Z > out.txt && cat out.txt")
+            .unwrap()
+            .assert_success()
+            .assert_stdout_contains("HI");
+        space.assert_file_contents("out.txt", "HI\n");
+        space.sh("exit 7").unwrap().assert_code(7);
+    })
+    .unwrap();
+}
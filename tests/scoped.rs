@@ -2,7 +2,7 @@ use std::{cell::RefCell, path::PathBuf, rc::Rc};
 
 use serial_test::serial;
 
-use playspace::Playspace;
+use playspace::{Playspace, ScopedError, SpaceError};
 
 const ABSENT: &str = "SOME_ABSENT_ENVVAR";
 const PRESENT: &str = "SOME_PRESENT_ENVVAR";
@@ -87,3 +87,185 @@ fn files_and_envs() {
 
     assert_envs_outside();
 }
+
+#[test]
+fn scratchpad() {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    struct Flag(Arc<AtomicBool>);
+    impl Drop for Flag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    let seen_on_exit = Arc::new(AtomicBool::new(false));
+
+    let space = Playspace::new().expect("Failed to create space");
+    space.store("flag", Flag(Arc::clone(&dropped)));
+    space.store("id", String::from("first"));
+    space.store("id", String::from("second"));
+    assert_eq!(space.get::<String>("id").unwrap().as_str(), "second");
+    assert!(space.get::<u32>("id").is_none());
+    assert!(space.get::<String>("missing").is_none());
+
+    let seen = Arc::clone(&seen_on_exit);
+    space.verify_on_exit(move |space| {
+        seen.store(space.get::<Flag>("flag").is_some(), Ordering::SeqCst);
+        Ok(())
+    });
+
+    assert_eq!(space.remove::<String>("id").unwrap().as_str(), "second");
+    assert!(space.get::<String>("id").is_none());
+
+    space.exit().expect("Failed to exit");
+    assert!(seen_on_exit.load(Ordering::SeqCst));
+    assert!(dropped.load(Ordering::SeqCst));
+}
+
+#[test]
+#[serial]
+fn sequence() {
+    let original = std::env::current_dir().unwrap();
+
+    let directories = Playspace::sequence(|runner| {
+        let first = runner
+            .space(|space| {
+                space.write_file("file.txt", "").unwrap();
+                // Nothing else can get in between phases
+                assert!(Playspace::try_new().is_err());
+                space.directory().to_owned()
+            })
+            .expect("Failed first phase");
+        assert!(Playspace::try_new().is_err());
+        // Waiting would never finish, since the lock is only released after this
+        assert!(matches!(Playspace::new(), Err(SpaceError::WouldDeadlock)));
+        assert_eq!(std::env::current_dir().unwrap(), original);
+
+        let second = runner
+            .space(|space| {
+                assert!(!space.directory().join("file.txt").exists());
+                space.directory().to_owned()
+            })
+            .expect("Failed second phase");
+        [first, second]
+    })
+    .expect("Failed to run sequence");
+
+    assert_ne!(directories[0], directories[1]);
+    assert!(directories.iter().all(|directory| !directory.exists()));
+    Playspace::try_new()
+        .expect("Should be free again")
+        .exit()
+        .unwrap();
+}
+
+#[test]
+#[serial]
+fn scoped_fallible() {
+    let out = Playspace::scoped_fallible(|space| {
+        space.write_file("file.txt", "contents")?;
+        Ok::<_, playspace::WriteError>(std::fs::read_to_string("file.txt")?)
+    })
+    .expect("Failed to use playspace");
+    assert_eq!(out, "contents");
+
+    let directory = Rc::new(RefCell::new(PathBuf::new()));
+    let directory_inside = directory.clone();
+    #[allow(clippy::match_wild_err_arm)]
+    match Playspace::scoped_fallible(move |space| {
+        *directory_inside.borrow_mut() = space.directory().to_owned();
+        space.write_file("missing/file.txt", "")
+    }) {
+        Err(ScopedError::Closure(playspace::WriteError::ParentMissing { .. })) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+    // Still exited
+    assert!(!directory.borrow().exists());
+    assert!(!Playspace::is_active());
+}
+
+#[test]
+#[serial]
+fn panic_exits_first() {
+    let original = std::env::current_dir().unwrap();
+    std::env::remove_var(ABSENT);
+
+    let directory = Rc::new(RefCell::new(PathBuf::new()));
+    let directory_inside = directory.clone();
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        Playspace::scoped(|space| {
+            *directory_inside.borrow_mut() = space.directory().to_owned();
+            space.set_envs([(ABSENT, Some("absent_value"))]);
+            panic!("test panic");
+        })
+    }));
+    assert!(result.is_err());
+
+    // Already restored when the panic is caught
+    assert!(!Playspace::is_active());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+    assert!(std::env::var_os(ABSENT).is_none());
+    assert!(!directory.borrow().exists());
+
+    // Later phases of a sequence still work after a caught panic
+    Playspace::sequence(|sequence| {
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            sequence.space(|_space| panic!("test panic"))
+        }));
+        assert!(panicked.is_err());
+        assert!(!Playspace::is_active());
+        // The lock is still held by the sequence
+        assert!(Playspace::try_new().is_err());
+        sequence
+            .space(|_space| ())
+            .expect("Failed to use playspace");
+    })
+    .expect("Failed to use sequence");
+}
+
+#[test]
+#[serial]
+fn scoped_in_thread() {
+    thread_local! {
+        static MARKER: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+    }
+    MARKER.with(|marker| marker.set(true));
+
+    let original = std::env::current_dir().unwrap();
+    let caller = std::thread::current().id();
+    let (directory, thread) = Playspace::scoped_in_thread(|space| {
+        assert!(!MARKER.with(std::cell::Cell::get));
+        assert_eq!(std::env::current_dir().unwrap(), space.directory());
+        (space.directory().to_owned(), std::thread::current().id())
+    })
+    .expect("Failed to use playspace");
+    assert_ne!(thread, caller);
+    assert!(!directory.exists());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+
+    // Deep enough to overflow the default stack
+    let out =
+        Playspace::scoped_in_thread_with_stack_size(256 * 1024 * 1024, |_space| depth(1_000_000))
+            .expect("Failed to use playspace");
+    assert_eq!(out, 1_000_000);
+
+    let result =
+        std::panic::catch_unwind(|| Playspace::scoped_in_thread(|_space| panic!("test panic")));
+    assert!(result.is_err());
+    assert!(!Playspace::is_active());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+}
+
+fn depth(n: u64) -> u64 {
+    if n == 0 {
+        0
+    } else {
+        1 + std::hint::black_box(depth(n - 1))
+    }
+}
@@ -0,0 +1,84 @@
+#![cfg(unix)]
+
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use playspace::{Playspace, BIN_DIR};
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn shim_calls() {
+    Playspace::scoped(|space| {
+        let path = space
+            .shim("fake-tool")
+            .with_stdout("out\n")
+            .with_stderr("err\n")
+            .with_exit_code(3)
+            .install()
+            .unwrap();
+        assert_eq!(path, space.directory().join(BIN_DIR).join("fake-tool"));
+        assert!(space.shim_calls("fake-tool").unwrap().is_empty());
+
+        let output = Command::new("fake-tool")
+            .args(["plain", "with space", "new\nline", "quote'd", ""])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+
+        space.create_dir_all("sub").unwrap();
+        let mut child = Command::new("fake-tool")
+            .current_dir("sub")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(b"input").unwrap();
+        child.wait().unwrap();
+
+        let calls = space.shim_calls("fake-tool").unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[0].args,
+            ["plain", "with space", "new\nline", "quote'd", ""]
+        );
+        assert!(calls[0].stdin.is_empty());
+        assert!(calls[1].args.is_empty());
+        assert_eq!(calls[1].stdin, b"input");
+        assert_eq!(
+            calls[1].cwd.canonicalize().unwrap(),
+            space.directory_canonical().join("sub")
+        );
+
+        // Replaced, keeping the calls
+        space.shim("fake-tool").install().unwrap();
+        let output = Command::new("fake-tool").output().unwrap();
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty());
+        assert_eq!(space.shim_calls("fake-tool").unwrap().len(), 3);
+
+        // Only added to PATH once
+        space.shim("other-tool").install().unwrap();
+        let path = std::env::var_os("PATH").unwrap();
+        let bin = space.directory().join(BIN_DIR);
+        assert_eq!(
+            std::env::split_paths(&path)
+                .filter(|entry| *entry == bin)
+                .count(),
+            1
+        );
+
+        assert!(space.shim_calls("never-installed").unwrap().is_empty());
+        for name in ["", "..", "a/b"] {
+            assert_eq!(
+                space.shim(name).install().unwrap_err().kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+    })
+    .unwrap();
+}
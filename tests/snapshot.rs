@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use playspace::Playspace;
+
+#[test]
+fn snapshot_diff() {
+    let space = Playspace::with_files([
+        ("unchanged.txt", "same"),
+        ("changed.txt", "before"),
+        ("removed/file.txt", "gone"),
+        ("kind.txt", "file"),
+    ])
+    .expect("Failed to create playspace");
+
+    let before = space.snapshot().expect("Failed to snapshot");
+    assert_eq!(before.len(), 5);
+    assert!(before.contains("removed/file.txt"));
+
+    std::fs::write("changed.txt", "after").unwrap();
+    std::fs::remove_dir_all("removed").unwrap();
+    std::fs::remove_file("kind.txt").unwrap();
+    std::fs::create_dir("kind.txt").unwrap();
+    std::fs::create_dir_all("new/dir").unwrap();
+    std::fs::write("new/dir/file.txt", "").unwrap();
+
+    let after = space.snapshot().expect("Failed to snapshot");
+    let diff = before.diff(&after);
+
+    assert_eq!(
+        diff.created,
+        [
+            PathBuf::from("new"),
+            PathBuf::from("new/dir"),
+            PathBuf::from("new/dir/file.txt"),
+        ]
+    );
+    assert_eq!(
+        diff.modified,
+        [PathBuf::from("changed.txt"), PathBuf::from("kind.txt")]
+    );
+    assert_eq!(
+        diff.deleted,
+        [PathBuf::from("removed"), PathBuf::from("removed/file.txt")]
+    );
+
+    assert!(after.diff(&after).is_empty());
+}
+
+#[test]
+fn clone_contents() {
+    let space = Playspace::with_files([("a.txt", "a"), ("nested/b.txt", "b")])
+        .expect("Failed to create playspace");
+
+    space
+        .clone_contents_to("copy")
+        .expect("Failed to clone inside the playspace");
+    let original = playspace::DirSnapshot::capture(space.directory()).unwrap();
+    let copy = playspace::DirSnapshot::capture("copy").unwrap();
+    assert!(!copy.contains("copy"));
+    assert!(copy.contains("nested/b.txt"));
+    assert_eq!(copy.len(), 3);
+    assert_eq!(original.len(), 3 + 1 + 3);
+
+    // Changing one copy doesn't affect the other
+    std::fs::write("copy/a.txt", "changed").unwrap();
+    assert_eq!(std::fs::read_to_string("a.txt").unwrap(), "a");
+
+    let outside = tempfile::tempdir().unwrap();
+    space
+        .clone_contents_to(outside.path())
+        .expect("Failed to clone outside the playspace");
+    let outside_snapshot = playspace::DirSnapshot::capture(outside.path()).unwrap();
+    assert_eq!(outside_snapshot.len(), original.len());
+}
+
+#[test]
+#[serial_test::serial]
+fn checkpoint_rollback() {
+    let space = Playspace::with_files([("kept.txt", "before"), ("removed/file.txt", "")])
+        .expect("Failed to create playspace");
+    space.set_envs([("__PLAYSPACE_CHECKPOINT", Some("before"))]);
+    let checkpoint = space.checkpoint().expect("Failed to checkpoint");
+    let before = space.snapshot().unwrap();
+    assert_eq!(
+        checkpoint.environment().get("__PLAYSPACE_CHECKPOINT"),
+        Some(std::ffi::OsStr::new("before"))
+    );
+
+    for _ in 0..2 {
+        space
+            .write_files([("kept.txt", "after"), ("created.txt", "")])
+            .unwrap();
+        std::fs::remove_dir_all("removed").unwrap();
+        space.set_envs([("__PLAYSPACE_CHECKPOINT", Some("after"))]);
+        let _nested = space.dir_scope("created-dir").unwrap();
+
+        space.rollback(&checkpoint).expect("Failed to roll back");
+        assert!(before.diff(&space.snapshot().unwrap()).is_empty());
+        assert_eq!(
+            std::fs::read_to_string(space.directory().join("kept.txt")).unwrap(),
+            "before"
+        );
+        assert_eq!(std::env::var("__PLAYSPACE_CHECKPOINT").unwrap(), "before");
+    }
+}
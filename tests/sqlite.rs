@@ -0,0 +1,55 @@
+use playspace::{Playspace, WriteError};
+
+#[test]
+fn sqlite_path_creates_parents() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    let path = space
+        .sqlite_path("some/nested/test.db")
+        .expect("Failed to get database path");
+    assert!(path.starts_with(space.directory()));
+    assert!(path.parent().unwrap().is_dir());
+    assert!(!path.exists());
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.sqlite_path(std::env::temp_dir().join("test.db")) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn create_and_seed() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    let connection = space
+        .create_sqlite(
+            "test.db",
+            [
+                "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT NOT NULL);",
+                "INSERT INTO items (name) VALUES ('one'); INSERT INTO items (name) VALUES ('two');",
+            ],
+        )
+        .expect("Failed to create database");
+
+    let names: Vec<String> = connection
+        .prepare("SELECT name FROM items ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(names, ["one", "two"]);
+    drop(connection);
+
+    assert!(space.directory().join("test.db").exists());
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.create_sqlite("broken.db", ["NOT VALID SQL"]) {
+        Err(playspace::SqliteError::Sqlite(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+}
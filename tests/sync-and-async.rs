@@ -1,16 +1,14 @@
-#![cfg(all(feature = "async"))]
+#![cfg(feature = "async")]
 
 use std::sync::{
     atomic::{AtomicU32, Ordering},
-    Arc,
+    Arc, LazyLock,
 };
 
-use lazy_static::lazy_static;
 use playspace::Playspace;
 
-lazy_static! {
-    static ref SERIAL: async_std::sync::Mutex<()> = async_std::sync::Mutex::new(());
-}
+static SERIAL: LazyLock<async_std::sync::Mutex<()>> =
+    LazyLock::new(|| async_std::sync::Mutex::new(()));
 
 #[async_std::test]
 async fn async_blocks_sync() {
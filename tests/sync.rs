@@ -0,0 +1,64 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use playspace::{sync::Barrier, Playspace};
+
+#[test]
+fn named_barriers_are_shared() {
+    let barrier = Barrier::new("shared", 3);
+    assert_eq!(barrier.name(), "shared");
+    assert_eq!(barrier.parties(), 3);
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| std::thread::spawn(|| Barrier::new("shared", 3).wait()))
+        .collect();
+    barrier.wait();
+
+    for handle in handles {
+        handle.join().expect("Thread panic");
+    }
+}
+
+#[test]
+fn barrier_timeout_withdraws() {
+    let barrier = Barrier::new("timeout", 2);
+    assert!(!barrier.wait_timeout(Duration::from_millis(50)));
+
+    // The timed-out arrival didn't count, so a single arrival doesn't release
+    barrier.arrive();
+    assert!(barrier.wait_timeout(Duration::from_millis(50)));
+}
+
+#[test]
+#[should_panic(expected = "already exists")]
+fn barrier_party_mismatch() {
+    let _barrier = Barrier::new("mismatch", 2);
+    let _other = Barrier::new("mismatch", 3);
+}
+
+#[test]
+fn arrive_on_space_exit() {
+    let barrier = Barrier::new("space-exit", 2);
+    let exited = Arc::new(AtomicBool::new(false));
+    let exited_thread = exited.clone();
+
+    let handle = std::thread::spawn(move || {
+        let space = Playspace::new().expect("Failed to create playspace");
+        space.arrive_on_exit(&Barrier::new("space-exit", 2));
+
+        std::thread::sleep(Duration::from_millis(100));
+        exited_thread.store(true, Ordering::Release);
+        drop(space);
+    });
+
+    barrier.wait();
+    assert!(exited.load(Ordering::Acquire));
+    Playspace::try_new().expect("Playspace should be free after the barrier");
+
+    handle.join().expect("Thread panic");
+}
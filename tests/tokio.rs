@@ -71,10 +71,9 @@ async fn files_and_envs() {
                 .unwrap();
             *path_during.lock() = space.directory().join("some_file.txt");
 
+            let path_inside = path_during.lock().clone();
             assert_eq!(
-                tokio::fs::read_to_string(&*path_during.lock())
-                    .await
-                    .unwrap(),
+                tokio::fs::read_to_string(&path_inside).await.unwrap(),
                 "some file contents"
             );
 
@@ -143,3 +142,135 @@ async fn wait_when_spaced() {
 
     assert_eq!(counter1.load(Ordering::Acquire), 4);
 }
+
+#[tokio::test]
+async fn scoped_with_files() {
+    let _serial = SERIAL.lock().await;
+
+    let contents = Playspace::scoped_with_files_async([("a/b.txt", "contents")], |_space| {
+        async move { tokio::fs::read_to_string("a/b.txt").await.unwrap() }.boxed()
+    })
+    .await
+    .expect("Failed to use playspace");
+    assert_eq!(contents, "contents");
+}
+
+#[tokio::test]
+async fn blocking_in_runtime() {
+    let _serial = SERIAL.lock().await;
+
+    #[allow(clippy::match_wild_err_arm)]
+    match Playspace::new() {
+        Err(playspace::SpaceError::WouldBlockRuntime) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(_) => panic!("Should not have worked"),
+    }
+
+    // Non-blocking construction is still fine
+    Playspace::try_new()
+        .expect("Failed to create playspace")
+        .exit()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn guarded_close() {
+    let _serial = SERIAL.lock().await;
+
+    let space = Playspace::guarded_async()
+        .await
+        .expect("Failed to create playspace");
+    space.write_file("file.txt", "").unwrap();
+    let directory = space.directory().to_owned();
+    space.close().await.expect("Failed to close");
+    assert!(!directory.exists());
+}
+
+#[tokio::test]
+async fn exit_async() {
+    let _serial = SERIAL.lock().await;
+    let original = std::env::current_dir().unwrap();
+
+    let space = Playspace::new_async()
+        .await
+        .expect("Failed to create playspace");
+    space.write_file("file.txt", "").unwrap();
+    let directory = space.directory().to_owned();
+    space.exit_async().await.expect("Failed to exit");
+
+    assert!(!directory.exists());
+    assert!(!Playspace::is_active());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+}
+
+#[tokio::test]
+async fn guarded_drop_is_reaped() {
+    let _serial = SERIAL.lock().await;
+
+    let directory = {
+        let space = Playspace::guarded_async()
+            .await
+            .expect("Failed to create playspace");
+        space.directory().to_owned()
+    };
+
+    // Waits for the reaper to release the Playspace
+    let space = Playspace::new_async()
+        .await
+        .expect("Failed to create playspace");
+    assert!(!directory.exists());
+    space.exit().unwrap();
+    assert!(playspace::AsyncPlayspaceGuard::deferred_errors().is_empty());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn run_async() {
+    let _serial = SERIAL.lock().await;
+
+    let space = Playspace::new_async()
+        .await
+        .expect("Failed to create space");
+    space.write_file("file.txt", "contents").unwrap();
+    space
+        .run_async(["cat", "file.txt"])
+        .await
+        .unwrap()
+        .assert_success()
+        .assert_stdout_contains("contents");
+    space.exit().unwrap();
+}
+
+#[tokio::test]
+async fn cancellation_and_wait_until_free() {
+    let _serial = SERIAL.lock().await;
+    let timeout = std::time::Duration::from_millis(50);
+
+    let space = Playspace::new_async()
+        .await
+        .expect("Failed to create space");
+
+    // Giving up waiting leaves nothing behind
+    assert!(tokio::time::timeout(timeout, Playspace::new_async())
+        .await
+        .is_err());
+    assert!(tokio::time::timeout(timeout, Playspace::wait_until_free())
+        .await
+        .is_err());
+
+    let waiter = tokio::spawn(async {
+        Playspace::wait_until_free().await;
+        // Never entered
+        assert!(Playspace::try_new().is_ok());
+    });
+    tokio::time::sleep(timeout).await;
+    assert!(!waiter.is_finished());
+
+    drop(space);
+    waiter.await.expect("Task panic");
+    Playspace::new_async()
+        .await
+        .expect("Failed to create space after cancellation")
+        .exit()
+        .expect("Failed to exit space");
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use playspace::Playspace;
+use serial_test::serial;
+
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn capture(f: impl FnOnce()) -> String {
+    let captured = Captured::default();
+    let writer = captured.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+    tracing::subscriber::with_default(subscriber, f);
+    let output = captured.0.lock().unwrap().clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+#[serial]
+fn events() {
+    let output = capture(|| {
+        let space = Playspace::new().expect("Failed to create space");
+        space.write_file("file.txt", "").unwrap();
+        space.set_envs([("PLAYSPACE_TRACED", Some("yes"))]);
+        std::fs::remove_dir_all(space.directory()).unwrap();
+        let _ignored = space.exit();
+    });
+
+    for expected in [
+        "acquired Playspace lock",
+        "entered Playspace",
+        "write_file",
+        "file.txt",
+        "set_envs",
+        "PLAYSPACE_TRACED",
+        "exit step failed",
+        "TempDir",
+        "exited Playspace",
+    ] {
+        assert!(output.contains(expected), "{expected:?} not in:\n{output}");
+    }
+}
+
+#[test]
+#[serial]
+fn lock_holder() {
+    let space = Playspace::new().expect("Failed to create space");
+    let root = space.directory().to_string_lossy().into_owned();
+    let waiter = std::thread::spawn(|| {
+        capture(|| {
+            Playspace::new().expect("Failed to create space");
+        })
+    });
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    drop(space);
+
+    let output = waiter.join().unwrap();
+    assert!(output.contains("waiting for Playspace lock"), "{output}");
+    assert!(output.contains(&root), "{output}");
+}
+
+#[test]
+#[serial]
+fn restore_order() {
+    let output = capture(|| {
+        let space = Playspace::new().expect("Failed to create space");
+        for key in [
+            "PLAYSPACE_ORDER_C",
+            "PLAYSPACE_ORDER_A",
+            "PLAYSPACE_ORDER_B",
+        ] {
+            space.set_envs([(key, Some("added"))]);
+        }
+        space.exit().expect("Failed to exit space");
+    });
+
+    let restored: Vec<_> = output
+        .lines()
+        .filter(|line| line.contains("restore_env"))
+        .collect();
+    assert_eq!(restored.len(), 3, "{output}");
+    for (line, key) in restored.iter().zip([
+        "PLAYSPACE_ORDER_A",
+        "PLAYSPACE_ORDER_B",
+        "PLAYSPACE_ORDER_C",
+    ]) {
+        assert!(line.contains(key), "{key:?} not in {line:?}");
+    }
+}
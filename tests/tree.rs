@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use playspace::{tree, Playspace, Tree, WriteError};
+
+#[test]
+fn tree_macro_layout() {
+    let layout = tree! {
+        "Cargo.toml" => "[package]",
+        "src" => {
+            "main.rs" => "fn main() {}",
+            "empty" => {},
+            "nested/deeper" => { "mod.rs" => String::from("// generated") },
+        },
+        "data.bin" => vec![0u8, 1, 2],
+    };
+
+    let expected = Tree::new()
+        .file("Cargo.toml", "[package]")
+        .dir(
+            "src",
+            Tree::new()
+                .file("main.rs", "fn main() {}")
+                .dir("empty", Tree::new())
+                .dir("nested/deeper", Tree::new().file("mod.rs", "// generated")),
+        )
+        .file("data.bin", [0u8, 1, 2]);
+    assert_eq!(layout, expected);
+
+    let files: Vec<PathBuf> = layout.clone().into_iter().map(|(path, _)| path).collect();
+    assert_eq!(
+        files,
+        [
+            PathBuf::from("Cargo.toml"),
+            PathBuf::from("src/main.rs"),
+            PathBuf::from("src/nested/deeper/mod.rs"),
+            PathBuf::from("data.bin"),
+        ]
+    );
+
+    Playspace::scoped(|space| {
+        space.write_tree(&layout).expect("Failed to write tree");
+
+        assert_eq!(
+            std::fs::read_to_string("src/nested/deeper/mod.rs").unwrap(),
+            "// generated"
+        );
+        assert!(space.directory().join("src/empty").is_dir());
+        assert_eq!(std::fs::read("data.bin").unwrap(), [0, 1, 2]);
+    })
+    .unwrap();
+}
+
+#[test]
+fn tree_on_entry() {
+    Playspace::scoped_with_files(tree! { "a" => { "b.txt" => "b" } }, |_space| {
+        assert_eq!(std::fs::read_to_string("a/b.txt").unwrap(), "b");
+    })
+    .unwrap();
+
+    assert_eq!(tree! {}, Tree::new());
+}
+
+#[test]
+fn tree_outside() {
+    let space = Playspace::new().expect("Failed to create playspace");
+
+    #[allow(clippy::match_wild_err_arm)]
+    match space.write_tree(&tree! { std::env::temp_dir().join("escape") => {} }) {
+        Err(WriteError::OutsidePlayspace(_)) => (),
+        Err(_) => panic!("Wrong error"),
+        Ok(()) => panic!("Should not have worked"),
+    }
+}
@@ -0,0 +1,44 @@
+use playspace::{Playspace, Workspace};
+
+#[test]
+fn workspace() {
+    let original = std::env::current_dir().unwrap();
+    let first = Workspace::new().unwrap();
+    let second = Workspace::new().unwrap();
+    assert_ne!(first.directory(), second.directory());
+    assert_eq!(std::env::current_dir().unwrap(), original);
+
+    first.write_files([("nested/file.txt", "first")]).unwrap();
+    second.write_file("file.txt", "second").unwrap();
+    first.assert_file_contents("nested/file.txt", "first");
+    second.assert_file_contents("file.txt", "second");
+    assert!(first.write_file("../escape.txt", "").is_err());
+    assert!(!first.contains(second.directory()));
+
+    // Usable from other threads at once
+    let directory = std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                first.create_dir_all("from_thread").unwrap();
+                first.directory().to_owned()
+            })
+            .join()
+            .unwrap()
+    });
+    assert!(directory.join("from_thread").is_dir());
+
+    first.exit().expect("Failed to remove workspace");
+    assert!(!directory.exists());
+
+    let kept = second.into_path().unwrap();
+    assert!(kept.join("file.txt").exists());
+    std::fs::remove_dir_all(kept).unwrap();
+
+    // Dropping removes it too
+    let dropped = Workspace::new().unwrap();
+    let directory = dropped.directory().to_owned();
+    drop(dropped);
+    assert!(!directory.exists());
+
+    assert!(!Playspace::is_active());
+}